@@ -1,5 +1,6 @@
 use std::{env, io};
 
+use futures_util::TryStreamExt;
 use wanikani_api::client::{SubjectFilter, WKClient};
 
 #[tokio::main(flavor = "current_thread")]
@@ -13,28 +14,14 @@ async fn main() {
 
     let filters = SubjectFilter::default();
 
-    let mut collection = client.get_subjects(&filters).await.expect("Get Subjects");
-
-    let mut subjects = collection.data;
-    log::info!(
-        "Total of {} subjects to download, have {}",
-        collection.total_count,
-        subjects.len()
-    );
-
-    while let Some(ref next_url) = collection.pages.next_url {
-        collection = client
-            .get_resource_by_url(next_url)
-            .await
-            .expect("Next page");
-        subjects.append(&mut collection.data);
-
-        log::info!(
-            "Total of {} subjects to download, have {}",
-            collection.total_count,
-            subjects.len()
-        );
-    }
+    let subjects: Vec<_> = client
+        .stream_subjects(&filters)
+        .inspect_ok(|subject| log::debug!("Received subject {}", subject.id))
+        .try_collect()
+        .await
+        .expect("Get Subjects");
+
+    log::info!("Downloaded {} subjects", subjects.len());
 
     serde_json::to_writer_pretty(io::stdout(), &subjects).expect("Serialize to stdout");
 }
@@ -0,0 +1,502 @@
+//! Progress analytics computed from a user's assignments, subjects and level
+//! progressions.
+//!
+//! A user levels up once they have passed 90% of the kanji on their current
+//! level, as described in the [`level_progression`](crate::level_progression)
+//! module. [`LevelAnalytics::level_progress`] reports how close the user is to
+//! that, which kanji are still locked behind radicals that have not been
+//! passed, and the earliest time the user could level up if every review from
+//! now on is answered correctly as soon as it becomes available.
+//!
+//! [`LevelAnalytics::level_times`] reports how long the user spent on each
+//! level in the past.
+//!
+//! ### Example
+//! ```rust
+//! # use wanikani_api::{analytics::LevelAnalytics, Resource};
+//! # use chrono::Utc;
+//! # let subjects = vec![
+//! #     serde_json::from_str(include_str!("../test_files/radical.json")).unwrap(),
+//! #     serde_json::from_str(include_str!("../test_files/kanji.json")).unwrap(),
+//! # ];
+//! # let system: Resource<_> = serde_json::from_str(
+//! #     include_str!("../test_files/spaced_repetition_system.json"),
+//! # ).unwrap();
+//! # let mut accelerated = system.clone();
+//! # accelerated.id = 2;
+//! # let systems = vec![system, accelerated];
+//! # let assignments = vec![];
+//! let analytics = LevelAnalytics::new(&subjects, &assignments, &systems);
+//! let progress = analytics.level_progress(1, Utc::now());
+//!
+//! assert_eq!(progress.kanji_total, 1);
+//! assert_eq!(progress.kanji_needed, 1);
+//! assert_eq!(progress.blocked_kanji[0].blocking_subject_ids, [1]);
+//! assert!(progress.earliest_level_up.is_some());
+//! ```
+
+use std::collections::HashMap;
+
+use chrono::{Duration, DurationRound};
+
+use crate::{
+    assignment::Assignment,
+    level_progression::LevelProgression,
+    reset::Reset,
+    spaced_repetition_system::SpacedRepetitionSystem,
+    subject::{Subject, SubjectType},
+    Id, Resource, Timestamp,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A kanji that cannot be learned yet, because some of its components have
+/// not been passed.
+pub struct BlockedKanji {
+    /// The kanji's subject ID.
+    pub subject_id: Id,
+    /// The subject IDs of the components that have not been passed.
+    pub blocking_subject_ids: Vec<Id>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Progress towards passing a level.
+pub struct LevelProgress {
+    /// The level.
+    pub level: u32,
+    /// The number of kanji on the level, not counting hidden subjects.
+    pub kanji_total: u32,
+    /// The number of kanji on the level the user has passed.
+    pub kanji_passed: u32,
+    /// The number of kanji that must be passed to pass the level.
+    pub kanji_needed: u32,
+    /// Kanji that are locked behind components the user has not passed.
+    pub blocked_kanji: Vec<BlockedKanji>,
+    /// The earliest time the level can be passed, if every review is answered
+    /// correctly as soon as it becomes available and every lesson is done as
+    /// soon as it unlocks. This is in the past if the level has already been
+    /// passed. `None` if it can't be determined, such as when a subject's
+    /// spaced repetition system is missing.
+    pub earliest_level_up: Option<Timestamp>,
+}
+
+impl LevelProgress {
+    /// The number of kanji that still have to be passed to pass the level.
+    pub fn kanji_remaining(&self) -> u32 {
+        self.kanji_needed.saturating_sub(self.kanji_passed)
+    }
+
+    /// Whether enough kanji have been passed to pass the level.
+    pub fn is_passed(&self) -> bool {
+        self.kanji_passed >= self.kanji_needed
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The time a user spent on a level.
+pub struct LevelTime {
+    /// The level.
+    pub level: u32,
+    /// When the user started the level. This is when the level was unlocked,
+    /// or when the user reset to it, whichever is later.
+    pub started_at: Timestamp,
+    /// When the user passed the level, if they have.
+    pub passed_at: Option<Timestamp>,
+}
+
+impl LevelTime {
+    /// The time spent on the level, up to `now` if it has not been passed.
+    pub fn duration(&self, now: Timestamp) -> Duration {
+        self.passed_at.unwrap_or(now) - self.started_at
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Computes level progress analytics. See the
+/// [module documentation](self) for details.
+pub struct LevelAnalytics<'a> {
+    subjects: HashMap<Id, &'a Subject>,
+    assignments: HashMap<Id, &'a Assignment>,
+    systems: HashMap<Id, &'a SpacedRepetitionSystem>,
+    level_progressions: Vec<&'a LevelProgression>,
+    resets: Vec<&'a Reset>,
+}
+
+impl<'a> LevelAnalytics<'a> {
+    /// Create analytics from a user's assignments, the subjects they belong
+    /// to, and the spaced repetition systems used by those subjects.
+    pub fn new(
+        subjects: &'a [Resource<Subject>],
+        assignments: &'a [Resource<Assignment>],
+        systems: &'a [Resource<SpacedRepetitionSystem>],
+    ) -> Self {
+        Self {
+            subjects: subjects.iter().map(|s| (s.id, &s.data)).collect(),
+            assignments: assignments
+                .iter()
+                .map(|a| (a.data.subject_id, &a.data))
+                .collect(),
+            systems: systems.iter().map(|s| (s.id, &s.data)).collect(),
+            level_progressions: Vec::new(),
+            resets: Vec::new(),
+        }
+    }
+
+    /// Also use the user's level progressions, which are needed by
+    /// [`level_times`](Self::level_times).
+    pub fn with_level_progressions(self, progressions: &'a [Resource<LevelProgression>]) -> Self {
+        Self {
+            level_progressions: progressions.iter().map(|p| &p.data).collect(),
+            ..self
+        }
+    }
+
+    /// Also use the user's resets, so that time spent on a level before a
+    /// reset is not counted by [`level_times`](Self::level_times).
+    pub fn with_resets(self, resets: &'a [Resource<Reset>]) -> Self {
+        Self {
+            resets: resets.iter().map(|r| &r.data).collect(),
+            ..self
+        }
+    }
+
+    /// Report progress towards passing `level`, as of `now`.
+    pub fn level_progress(&self, level: u32, now: Timestamp) -> LevelProgress {
+        let mut kanji: Vec<_> = self
+            .subjects
+            .iter()
+            .filter(|(_, subject)| {
+                let common = subject.common();
+                subject.subject_type() == SubjectType::Kanji
+                    && common.level == level
+                    && common.hidden_at.is_none()
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        kanji.sort_unstable();
+
+        let kanji_total = u32::try_from(kanji.len()).unwrap_or(u32::MAX);
+        let kanji_passed = kanji.iter().filter(|id| self.is_passed(**id)).count();
+        let kanji_passed = u32::try_from(kanji_passed).unwrap_or(u32::MAX);
+        let kanji_needed = (kanji_total * 9 + 9) / 10;
+
+        let blocked_kanji = kanji
+            .iter()
+            .filter(|id| !self.is_unlocked(**id))
+            .map(|id| BlockedKanji {
+                subject_id: *id,
+                blocking_subject_ids: self
+                    .components(*id)
+                    .iter()
+                    .copied()
+                    .filter(|c| !self.is_passed(*c))
+                    .collect(),
+            })
+            .filter(|blocked| !blocked.blocking_subject_ids.is_empty())
+            .collect();
+
+        let mut pass_times: Vec<_> = kanji
+            .iter()
+            .filter_map(|id| self.earliest_pass(*id, now))
+            .collect();
+        pass_times.sort_unstable();
+        let earliest_level_up = match kanji_needed {
+            0 => None,
+            needed => pass_times.get(needed as usize - 1).copied(),
+        };
+
+        LevelProgress {
+            level,
+            kanji_total,
+            kanji_passed,
+            kanji_needed,
+            blocked_kanji,
+            earliest_level_up,
+        }
+    }
+
+    /// The time spent on each level, ordered by level.
+    ///
+    /// Abandoned level progressions are skipped. When the user has reset to a
+    /// level, the time is counted from the reset rather than from when the
+    /// level was first unlocked.
+    pub fn level_times(&self) -> Vec<LevelTime> {
+        let mut latest: HashMap<u32, &LevelProgression> = HashMap::new();
+        for progression in &self.level_progressions {
+            if progression.abandoned_at.is_some() || progression.unlocked_at.is_none() {
+                continue;
+            }
+            let entry = latest.entry(progression.level).or_insert(progression);
+            if progression.created_at > entry.created_at {
+                *entry = progression;
+            }
+        }
+
+        let mut times: Vec<_> = latest
+            .into_values()
+            .filter_map(|progression| {
+                let unlocked_at = progression.unlocked_at?;
+                let reset_at = self
+                    .resets
+                    .iter()
+                    .filter(|reset| reset.target_level == progression.level)
+                    .filter_map(|reset| reset.confirmed_at)
+                    .filter(|confirmed_at| {
+                        progression
+                            .passed_at
+                            .map_or(true, |passed| *confirmed_at < passed)
+                    })
+                    .max();
+
+                Some(LevelTime {
+                    level: progression.level,
+                    started_at: reset_at.map_or(unlocked_at, |reset| reset.max(unlocked_at)),
+                    passed_at: progression.passed_at,
+                })
+            })
+            .collect();
+        times.sort_by_key(|time| time.level);
+        times
+    }
+
+    fn is_passed(&self, subject_id: Id) -> bool {
+        self.assignments
+            .get(&subject_id)
+            .map_or(false, |a| a.passed_at.is_some())
+    }
+
+    fn is_unlocked(&self, subject_id: Id) -> bool {
+        self.assignments
+            .get(&subject_id)
+            .map_or(false, |a| a.unlocked_at.is_some())
+    }
+
+    fn components(&self, subject_id: Id) -> &[Id] {
+        match self.subjects.get(&subject_id) {
+            Some(Subject::Kanji(kanji)) => &kanji.component_subject_ids,
+            Some(Subject::Vocabulary(vocabulary)) => &vocabulary.component_subject_ids,
+            _ => &[],
+        }
+    }
+
+    /// The earliest time `subject_id` can be passed, assuming all reviews are
+    /// done correctly as soon as possible.
+    fn earliest_pass(&self, subject_id: Id, now: Timestamp) -> Option<Timestamp> {
+        let assignment = self.assignments.get(&subject_id);
+        if let Some(passed_at) = assignment.and_then(|a| a.passed_at) {
+            return Some(passed_at);
+        }
+
+        let subject = self.subjects.get(&subject_id)?;
+        let system = self
+            .systems
+            .get(&subject.common().spaced_repetition_system_id)?;
+
+        let (mut stage, mut available_at) = match assignment {
+            Some(a) if a.srs_stage.position() > system.unlocking_stage_position => {
+                (a.srs_stage.position(), a.available_at?.max(now))
+            }
+            _ => {
+                // The lesson can be done once every component has been
+                // passed, or right away if it has already been unlocked.
+                let unlocked_at = match assignment.and_then(|a| a.unlocked_at) {
+                    Some(_) => now,
+                    None => self
+                        .components(subject_id)
+                        .iter()
+                        .map(|c| self.earliest_pass(*c, now))
+                        .try_fold(now, |latest, pass| Some(latest.max(pass?)))?,
+                };
+                let stage = system.starting_stage_position;
+                let interval = system.stage(stage)?.interval_duration()?;
+                (
+                    stage,
+                    top_of_hour(unlocked_at.checked_add_signed(interval)?),
+                )
+            }
+        };
+
+        loop {
+            stage += 1;
+            if system.is_passed(stage) {
+                return Some(available_at);
+            }
+            let interval = system.stage(stage)?.interval_duration()?;
+            available_at = top_of_hour(available_at.checked_add_signed(interval)?);
+        }
+    }
+}
+
+fn top_of_hour(time: Timestamp) -> Timestamp {
+    time.duration_trunc(Duration::hours(1)).unwrap_or(time)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, DurationRound, TimeZone, Utc};
+
+    use crate::{
+        assignment::Assignment, level_progression::LevelProgression, reset::Reset,
+        spaced_repetition_system::SpacedRepetitionSystem, subject::Subject, Id, Resource,
+        Timestamp,
+    };
+
+    use super::{LevelAnalytics, LevelTime};
+
+    fn now() -> Timestamp {
+        Utc.with_ymd_and_hms(2023, 6, 1, 10, 30, 0).unwrap()
+    }
+
+    fn systems() -> Vec<Resource<SpacedRepetitionSystem>> {
+        let json = include_str!("../test_files/spaced_repetition_system.json");
+        let system: Resource<SpacedRepetitionSystem> =
+            serde_json::from_str(json).expect("Deserialize");
+        let mut accelerated = system.clone();
+        accelerated.id = 2;
+        vec![system, accelerated]
+    }
+
+    /// The radical and kanji fixtures, plus nine more kanji built from the
+    /// radical, numbered 441 to 449.
+    fn subjects() -> Vec<Resource<Subject>> {
+        let radical: Resource<Subject> =
+            serde_json::from_str(include_str!("../test_files/radical.json")).expect("Radical");
+        let kanji: Resource<Subject> =
+            serde_json::from_str(include_str!("../test_files/kanji.json")).expect("Kanji");
+
+        let mut subjects = vec![radical, kanji.clone()];
+        for id in 441..450 {
+            let mut kanji = kanji.clone();
+            kanji.id = id;
+            subjects.push(kanji);
+        }
+        subjects
+    }
+
+    fn assignment(subject_id: Id, srs_stage: u32, passed: bool) -> Resource<Assignment> {
+        let json = include_str!("../test_files/assignment.json");
+        let mut assignment: Resource<Assignment> = serde_json::from_str(json).expect("Deserialize");
+        assignment.data.subject_id = subject_id;
+        assignment.data.srs_stage = srs_stage.try_into().expect("SRS stage");
+        assignment.data.available_at = Some(now() + Duration::hours(2));
+        assignment.data.passed_at = passed.then(|| now() - Duration::days(1));
+        assignment
+    }
+
+    #[test]
+    fn test_level_progress() {
+        let subjects = subjects();
+        let systems = systems();
+
+        // The radical is passed, seven kanji are passed, one is at Apprentice
+        // IV and two are unlocked but not started.
+        let mut assignments = vec![assignment(1, 5, true)];
+        assignments.extend((440..447).map(|id| assignment(id, 5, true)));
+        assignments.push(assignment(447, 4, false));
+        assignments.push(assignment(448, 0, false));
+
+        let analytics = LevelAnalytics::new(&subjects, &assignments, &systems);
+        let progress = analytics.level_progress(1, now());
+
+        assert_eq!(progress.kanji_total, 10);
+        assert_eq!(progress.kanji_passed, 7);
+        assert_eq!(progress.kanji_needed, 9);
+        assert_eq!(progress.kanji_remaining(), 2);
+        assert!(!progress.is_passed());
+        // 449 has not been unlocked yet, but its radical is passed.
+        assert!(progress.blocked_kanji.is_empty());
+
+        // The ninth kanji to pass is one of the unstarted ones: a lesson now,
+        // then four reviews at 4, 8, 23 and 47 hour intervals.
+        let expected =
+            Utc.with_ymd_and_hms(2023, 6, 1, 10, 0, 0).unwrap() + Duration::hours(4 + 8 + 23 + 47);
+        assert_eq!(progress.earliest_level_up, Some(expected));
+    }
+
+    #[test]
+    fn test_blocked_kanji() {
+        let subjects = subjects();
+        let systems = systems();
+        let assignments = vec![assignment(1, 2, false)];
+
+        let analytics = LevelAnalytics::new(&subjects, &assignments, &systems);
+        let progress = analytics.level_progress(1, now());
+
+        assert_eq!(progress.kanji_passed, 0);
+        assert_eq!(progress.blocked_kanji.len(), 10);
+        assert!(progress
+            .blocked_kanji
+            .iter()
+            .all(|blocked| blocked.blocking_subject_ids == [1]));
+
+        // The radical passes after three more reviews, starting in two hours,
+        // and the kanji then take another four reviews.
+        let radical_passed = now() + Duration::hours(2 + 23 + 47);
+        let expected = radical_passed + Duration::hours(4 + 8 + 23 + 47);
+        assert_eq!(
+            progress.earliest_level_up,
+            Some(expected.duration_trunc(Duration::hours(1)).expect("Hour"))
+        );
+    }
+
+    #[test]
+    fn test_level_times() {
+        let json = include_str!("../test_files/level_progression.json");
+        let template: Resource<LevelProgression> = serde_json::from_str(json).expect("Deserialize");
+        let day = |d: u32| Utc.with_ymd_and_hms(2023, 1, d, 0, 0, 0).unwrap();
+        let progression = |level, unlocked, passed: Option<u32>, abandoned: Option<u32>| {
+            let mut progression = template.clone();
+            progression.data = LevelProgression {
+                abandoned_at: abandoned.map(day),
+                completed_at: None,
+                created_at: day(unlocked),
+                level,
+                passed_at: passed.map(day),
+                started_at: Some(day(unlocked)),
+                unlocked_at: Some(day(unlocked)),
+            };
+            progression
+        };
+
+        let progressions = vec![
+            progression(1, 1, Some(4), None),
+            // Abandoned by the reset, and replaced by the progression below.
+            progression(2, 4, None, Some(10)),
+            progression(2, 4, Some(20), None),
+            progression(3, 20, None, None),
+        ];
+
+        let reset_json = include_str!("../test_files/reset.json");
+        let mut reset: Resource<Reset> = serde_json::from_str(reset_json).expect("Deserialize");
+        reset.data.target_level = 2;
+        reset.data.confirmed_at = Some(day(10));
+        let resets = vec![reset];
+
+        let analytics = LevelAnalytics::new(&[], &[], &[])
+            .with_level_progressions(&progressions)
+            .with_resets(&resets);
+        let times = analytics.level_times();
+
+        assert_eq!(
+            times,
+            [
+                LevelTime {
+                    level: 1,
+                    started_at: day(1),
+                    passed_at: Some(day(4)),
+                },
+                LevelTime {
+                    level: 2,
+                    started_at: day(10),
+                    passed_at: Some(day(20)),
+                },
+                LevelTime {
+                    level: 3,
+                    started_at: day(20),
+                    passed_at: None,
+                },
+            ]
+        );
+        assert_eq!(times[1].duration(day(30)), Duration::days(10));
+        assert_eq!(times[2].duration(day(30)), Duration::days(10));
+    }
+}
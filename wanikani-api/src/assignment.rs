@@ -4,7 +4,11 @@
 //! components of the given subject and the assignment is at or below their
 //! current level for the first time.
 
+use std::fmt::Display;
+
+use chrono::Duration;
 use serde::{Deserialize, Serialize};
+use thiserror::Error as E;
 
 use crate::{cross_feature::SubjectType, Id, Timestamp};
 
@@ -32,7 +36,7 @@ pub struct Assignment {
     pub resurrected_at: Option<Timestamp>,
     /// The current SRS stage interval. The interval range is determined by the
     /// related subject's spaced repetition system.
-    pub srs_stage: u32,
+    pub srs_stage: SrsStage,
     /// Timestamp when the user completes the lesson for the related subject.
     pub started_at: Option<Timestamp>,
     /// Unique identifier of the associated subject.
@@ -45,12 +49,208 @@ pub struct Assignment {
     /// Prerequisites are:
     ///
     /// - The subject components have reached SRS stage `5` once (they have been
-    /// "passed").
+    ///   "passed").
     /// - The user's level is equal to or greater than the level of the
-    /// assignment’s subject.
+    ///   assignment’s subject.
     pub unlocked_at: Option<Timestamp>,
 }
 
+impl Assignment {
+    /// The group of the assignment's SRS stage.
+    pub fn stage_group(&self) -> SrsStageGroup {
+        self.srs_stage.group()
+    }
+
+    /// Whether the assignment is available for review at `now`.
+    pub fn is_available_now(&self, now: Timestamp) -> bool {
+        self.available_at.map_or(false, |at| at <= now)
+    }
+
+    /// How long after `now` the assignment becomes available for review, or
+    /// zero if it already is. Returns `None` if it is not scheduled for
+    /// review, e.g. because its lesson has not been done or it is burned.
+    pub fn time_until_available(&self, now: Timestamp) -> Option<Duration> {
+        self.available_at.map(|at| (at - now).max(Duration::zero()))
+    }
+}
+
+#[derive(
+    Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[serde(try_from = "u32", into = "u32")]
+/// The SRS stage of an assignment, as used by both of WaniKani's spaced
+/// repetition systems. Stages are serialized as their position.
+///
+/// See the [Spaced Repetition System](crate#spaced-repetition-system) section
+/// of the crate documentation for how assignments move between stages.
+pub enum SrsStage {
+    #[default]
+    /// The subject is unlocked, but its lesson has not been done. Position
+    /// `0`.
+    Initiate,
+    /// Position `1`, the starting stage.
+    Apprentice1,
+    /// Position `2`.
+    Apprentice2,
+    /// Position `3`.
+    Apprentice3,
+    /// Position `4`.
+    Apprentice4,
+    /// Position `5`, the passing stage.
+    Guru1,
+    /// Position `6`.
+    Guru2,
+    /// Position `7`.
+    Master,
+    /// Position `8`.
+    Enlightened,
+    /// Position `9`, the burning stage. The subject is no longer reviewed.
+    Burned,
+}
+
+impl SrsStage {
+    /// Every stage, in order.
+    pub const ALL: [Self; 10] = [
+        Self::Initiate,
+        Self::Apprentice1,
+        Self::Apprentice2,
+        Self::Apprentice3,
+        Self::Apprentice4,
+        Self::Guru1,
+        Self::Guru2,
+        Self::Master,
+        Self::Enlightened,
+        Self::Burned,
+    ];
+
+    /// The stage's `position` in the spaced repetition system.
+    pub fn position(self) -> u32 {
+        self as u32
+    }
+
+    /// The group the stage belongs to.
+    pub fn group(self) -> SrsStageGroup {
+        match self {
+            Self::Initiate => SrsStageGroup::Initiate,
+            Self::Apprentice1 | Self::Apprentice2 | Self::Apprentice3 | Self::Apprentice4 => {
+                SrsStageGroup::Apprentice
+            }
+            Self::Guru1 | Self::Guru2 => SrsStageGroup::Guru,
+            Self::Master => SrsStageGroup::Master,
+            Self::Enlightened => SrsStageGroup::Enlightened,
+            Self::Burned => SrsStageGroup::Burned,
+        }
+    }
+
+    /// Whether the stage is at or above the passing stage, Guru I.
+    pub fn is_passed(self) -> bool {
+        self >= Self::Guru1
+    }
+
+    /// Whether the stage is the burning stage.
+    pub fn is_burned(self) -> bool {
+        self == Self::Burned
+    }
+
+    /// The stage after a review with no incorrect answers, or after the
+    /// lesson for [`Initiate`](Self::Initiate). Burned stays burned.
+    pub fn next(self) -> Self {
+        self.after_review(0)
+    }
+
+    /// The stage after a review with one incorrect answer.
+    pub fn previous(self) -> Self {
+        self.after_review(1)
+    }
+
+    /// The stage after a review with `incorrect_answers` incorrect meaning
+    /// and reading answers.
+    ///
+    /// Incorrect answers drop the stage by half their number, rounded up, and
+    /// by twice that from Guru I upwards. The stage never drops below
+    /// Apprentice I.
+    pub fn after_review(self, incorrect_answers: u32) -> Self {
+        let position = self.position();
+        let position = if incorrect_answers == 0 || self == Self::Initiate {
+            position + 1
+        } else {
+            let adjustment = (incorrect_answers + 1) / 2;
+            let penalty = if self.is_passed() { 2 } else { 1 };
+            position
+                .saturating_sub(adjustment * penalty)
+                .max(Self::Apprentice1.position())
+        };
+        Self::try_from(position).unwrap_or(Self::Burned)
+    }
+}
+
+impl TryFrom<u32> for SrsStage {
+    type Error = InvalidSrsStage;
+
+    fn try_from(position: u32) -> Result<Self, Self::Error> {
+        Self::ALL
+            .get(position as usize)
+            .copied()
+            .ok_or(InvalidSrsStage(position))
+    }
+}
+
+impl From<SrsStage> for u32 {
+    fn from(stage: SrsStage) -> Self {
+        stage.position()
+    }
+}
+
+impl Display for SrsStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Apprentice1 => write!(f, "Apprentice I"),
+            Self::Apprentice2 => write!(f, "Apprentice II"),
+            Self::Apprentice3 => write!(f, "Apprentice III"),
+            Self::Apprentice4 => write!(f, "Apprentice IV"),
+            Self::Guru1 => write!(f, "Guru I"),
+            Self::Guru2 => write!(f, "Guru II"),
+            stage => stage.group().fmt(f),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, E, PartialEq, Eq)]
+#[error("{0} is not an SRS stage position")]
+/// Returned when converting a position outside `0..=9` into an [`SrsStage`].
+pub struct InvalidSrsStage(pub u32);
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+/// The named groups of SRS stages shown to users.
+pub enum SrsStageGroup {
+    /// Lessons that have not been done.
+    Initiate,
+    /// Apprentice I to IV.
+    Apprentice,
+    /// Guru I and II.
+    Guru,
+    /// Master.
+    Master,
+    /// Enlightened.
+    Enlightened,
+    /// Burned.
+    Burned,
+}
+
+impl Display for SrsStageGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Initiate => write!(f, "Initiate"),
+            Self::Apprentice => write!(f, "Apprentice"),
+            Self::Guru => write!(f, "Guru"),
+            Self::Master => write!(f, "Master"),
+            Self::Enlightened => write!(f, "Enlightened"),
+            Self::Burned => write!(f, "Burned"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
 /// Mark the assignment as started, moving the assignment from the lessons queue
 /// to the review queue. Returns the updated assignment.
@@ -64,11 +264,11 @@ pub struct AssignmentStart {
 
 #[cfg(test)]
 mod tests {
-    use chrono::{DateTime, Utc};
+    use chrono::{DateTime, Duration, Utc};
 
     use crate::{cross_feature::SubjectType, Resource, ResourceCommon, ResourceType};
 
-    use super::Assignment;
+    use super::{Assignment, SrsStage, SrsStageGroup};
 
     #[test]
     fn test_deserialize_assignment() {
@@ -98,7 +298,7 @@ mod tests {
         );
         assert_eq!(data.subject_id, 8761);
         assert_eq!(data.subject_type, SubjectType::Radical);
-        assert_eq!(data.srs_stage, 8);
+        assert_eq!(data.srs_stage, SrsStage::Enlightened);
         assert_eq!(
             data.unlocked_at.expect("Timestamp"),
             DateTime::parse_from_rfc3339("2017-09-05T23:38:10.695133Z").expect("Timestamp")
@@ -129,7 +329,7 @@ mod tests {
             hidden: true,
             passed_at: Some(Utc::now()),
             resurrected_at: None,
-            srs_stage: 2,
+            srs_stage: SrsStage::Apprentice2,
             started_at: Some(Utc::now()),
             subject_id: 6969,
             subject_type: SubjectType::KanaVocabulary,
@@ -153,4 +353,53 @@ mod tests {
 
         assert_eq!(assignment, new_assignment);
     }
+
+    #[test]
+    fn test_srs_stage() {
+        assert_eq!(SrsStage::Initiate.next(), SrsStage::Apprentice1);
+        assert_eq!(SrsStage::Enlightened.next(), SrsStage::Burned);
+        assert_eq!(SrsStage::Burned.next(), SrsStage::Burned);
+
+        assert_eq!(SrsStage::Apprentice3.previous(), SrsStage::Apprentice2);
+        assert_eq!(SrsStage::Apprentice1.previous(), SrsStage::Apprentice1);
+        assert_eq!(SrsStage::Guru2.previous(), SrsStage::Apprentice4);
+        assert_eq!(SrsStage::Enlightened.after_review(3), SrsStage::Apprentice4);
+        assert_eq!(SrsStage::Master.after_review(10), SrsStage::Apprentice1);
+
+        assert!(SrsStage::Guru1.is_passed());
+        assert!(!SrsStage::Apprentice4.is_passed());
+        assert_eq!(SrsStage::Guru2.group(), SrsStageGroup::Guru);
+        assert_eq!(SrsStage::Apprentice4.to_string(), "Apprentice IV");
+        assert_eq!(SrsStage::Enlightened.to_string(), "Enlightened");
+
+        assert_eq!(SrsStage::try_from(7), Ok(SrsStage::Master));
+        assert!(SrsStage::try_from(10).is_err());
+        assert_eq!(
+            serde_json::to_string(&SrsStage::Guru1).expect("Serialize"),
+            "5"
+        );
+        assert!(serde_json::from_str::<SrsStage>("10").is_err());
+    }
+
+    #[test]
+    fn test_availability() {
+        let json = include_str!("../test_files/assignment.json");
+        let assignment: Resource<Assignment> = serde_json::from_str(json).expect("Deserialize");
+        let available_at = assignment.data.available_at.expect("Timestamp");
+
+        let before = available_at - Duration::hours(3);
+        assert!(!assignment.data.is_available_now(before));
+        assert_eq!(
+            assignment.data.time_until_available(before),
+            Some(Duration::hours(3))
+        );
+
+        let after = available_at + Duration::minutes(1);
+        assert!(assignment.data.is_available_now(after));
+        assert_eq!(
+            assignment.data.time_until_available(after),
+            Some(Duration::zero())
+        );
+        assert_eq!(assignment.data.stage_group(), SrsStageGroup::Enlightened);
+    }
 }
@@ -0,0 +1,725 @@
+//! `wk`, a command-line interface to the WaniKani API.
+//!
+//! The API token is read from `--token`, or from the `API_KEY` environment
+//! variable, which may be set in a `.env` file.
+
+use std::{io, process::ExitCode};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use futures_util::TryStreamExt;
+use serde::Serialize;
+use url::Url;
+use wanikani_api::{
+    assignment::{Assignment, SrsStage},
+    client::{AssignmentFilter, IdFilter, ReviewStatisticFilter, SubjectFilter, WKClientBuilder},
+    level_progression::LevelProgression,
+    reset::Reset,
+    review_statistic::ReviewStatistic,
+    study_material::{CreateStudyMaterial, StudyMaterial, UpdateStudyMaterial},
+    subject::{Subject, SubjectType},
+    summary::Summary,
+    user::{LessonPresentationOrder, UpdatePreferences, UpdateUser, User},
+    Id, Resource, Timestamp,
+};
+
+mod table;
+
+use table::{time, Table};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "wk",
+    version,
+    about = "Command-line interface to the WaniKani API"
+)]
+struct Cli {
+    /// WaniKani API token.
+    #[arg(long, env = "API_KEY", hide_env_values = true)]
+    token: String,
+    /// Base URL of the API, for use with proxies and mock servers.
+    #[arg(long)]
+    base_url: Option<Url>,
+    /// Output format.
+    #[arg(long, short, value_enum, default_value = "table")]
+    output: Format,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// A table for reading in a terminal.
+    Table,
+    /// Pretty-printed JSON, as returned by the API.
+    Json,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Show available lessons and upcoming reviews.
+    Summary,
+    /// List subjects.
+    Subjects(SubjectArgs),
+    /// List assignments.
+    Assignments(AssignmentArgs),
+    /// List review statistics.
+    ReviewStats(ReviewStatisticArgs),
+    /// Create or update study materials.
+    #[command(subcommand)]
+    StudyMaterials(StudyMaterialCommand),
+    /// Show or update the user.
+    #[command(subcommand)]
+    User(UserCommand),
+    /// List level progressions.
+    LevelProgressions(IdArgs),
+    /// List resets.
+    Resets(IdArgs),
+}
+
+#[derive(Debug, Args)]
+struct IdArgs {
+    /// Only include these IDs.
+    #[arg(long, value_delimiter = ',')]
+    ids: Option<Vec<Id>>,
+    /// Only include resources updated after this time (RFC 3339).
+    #[arg(long)]
+    updated_after: Option<Timestamp>,
+}
+
+impl From<IdArgs> for IdFilter {
+    fn from(args: IdArgs) -> Self {
+        Self {
+            ids: args.ids,
+            updated_after: args.updated_after,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+struct SubjectArgs {
+    /// Only include these subject IDs.
+    #[arg(long, value_delimiter = ',')]
+    ids: Option<Vec<Id>>,
+    /// Only include these subject types.
+    #[arg(long, value_delimiter = ',', value_parser = parse_subject_type)]
+    types: Option<Vec<SubjectType>>,
+    /// Only include these slugs.
+    #[arg(long, value_delimiter = ',')]
+    slugs: Option<Vec<String>>,
+    /// Only include subjects at these levels.
+    #[arg(long, value_delimiter = ',')]
+    levels: Option<Vec<u32>>,
+    /// Only include subjects that are, or are not, hidden.
+    #[arg(long)]
+    hidden: Option<bool>,
+    /// Only include subjects updated after this time (RFC 3339).
+    #[arg(long)]
+    updated_after: Option<Timestamp>,
+}
+
+impl From<SubjectArgs> for SubjectFilter {
+    fn from(args: SubjectArgs) -> Self {
+        Self {
+            ids: args.ids,
+            types: args.types,
+            slugs: args.slugs,
+            levels: args.levels,
+            hidden: args.hidden,
+            updated_after: args.updated_after,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+struct AssignmentArgs {
+    /// Only include assignments available at or after this time (RFC 3339).
+    #[arg(long)]
+    available_after: Option<Timestamp>,
+    /// Only include assignments available at or before this time (RFC 3339).
+    #[arg(long)]
+    available_before: Option<Timestamp>,
+    /// Only include assignments that are, or are not, burned.
+    #[arg(long)]
+    burned: Option<bool>,
+    /// Only include assignments that are, or are not, hidden.
+    #[arg(long)]
+    hidden: Option<bool>,
+    /// Only include these assignment IDs.
+    #[arg(long, value_delimiter = ',')]
+    ids: Option<Vec<Id>>,
+    /// Only include assignments available for lessons.
+    #[arg(long)]
+    available_for_lessons: bool,
+    /// Only include assignments available for review.
+    #[arg(long)]
+    available_for_review: bool,
+    /// Only include assignments that are in review.
+    #[arg(long)]
+    in_review: bool,
+    /// Only include assignments for subjects at these levels.
+    #[arg(long, value_delimiter = ',')]
+    levels: Option<Vec<u32>>,
+    /// Only include assignments at these SRS stages.
+    #[arg(long, value_delimiter = ',', value_parser = parse_srs_stage)]
+    srs_stages: Option<Vec<SrsStage>>,
+    /// Only include assignments that have, or have not, been started.
+    #[arg(long)]
+    started: Option<bool>,
+    /// Only include assignments for these subjects.
+    #[arg(long, value_delimiter = ',')]
+    subject_ids: Option<Vec<Id>>,
+    /// Only include assignments for these subject types.
+    #[arg(long, value_delimiter = ',', value_parser = parse_subject_type)]
+    subject_types: Option<Vec<SubjectType>>,
+    /// Only include assignments that are, or are not, unlocked.
+    #[arg(long)]
+    unlocked: Option<bool>,
+    /// Only include assignments updated after this time (RFC 3339).
+    #[arg(long)]
+    updated_after: Option<Timestamp>,
+}
+
+impl From<AssignmentArgs> for AssignmentFilter {
+    fn from(args: AssignmentArgs) -> Self {
+        Self {
+            available_after: args.available_after,
+            available_before: args.available_before,
+            burned: args.burned,
+            hidden: args.hidden,
+            ids: args.ids,
+            immediately_available_for_lessons: args.available_for_lessons,
+            immediately_available_for_review: args.available_for_review,
+            in_review: args.in_review,
+            levels: args.levels,
+            srs_stages: args.srs_stages,
+            started: args.started,
+            subject_ids: args.subject_ids,
+            subject_types: args.subject_types,
+            unlocked: args.unlocked,
+            updated_after: args.updated_after,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+struct ReviewStatisticArgs {
+    /// Only include statistics for subjects that are, or are not, hidden.
+    #[arg(long)]
+    hidden: Option<bool>,
+    /// Only include these review statistic IDs.
+    #[arg(long, value_delimiter = ',')]
+    ids: Option<Vec<Id>>,
+    /// Only include statistics with a correct percentage above this.
+    #[arg(long)]
+    percentages_greater_than: Option<u32>,
+    /// Only include statistics with a correct percentage below this.
+    #[arg(long)]
+    percentages_less_than: Option<u32>,
+    /// Only include statistics for these subjects.
+    #[arg(long, value_delimiter = ',')]
+    subject_ids: Option<Vec<Id>>,
+    /// Only include statistics for these subject types.
+    #[arg(long, value_delimiter = ',', value_parser = parse_subject_type)]
+    subject_types: Option<Vec<SubjectType>>,
+    /// Only include statistics updated after this time (RFC 3339).
+    #[arg(long)]
+    updated_after: Option<Timestamp>,
+}
+
+impl From<ReviewStatisticArgs> for ReviewStatisticFilter {
+    fn from(args: ReviewStatisticArgs) -> Self {
+        Self {
+            hidden: args.hidden,
+            ids: args.ids,
+            percentages_greater_than: args.percentages_greater_than,
+            percentages_less_than: args.percentages_less_than,
+            subject_ids: args.subject_ids,
+            subject_types: args.subject_types,
+            updated_after: args.updated_after,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+enum StudyMaterialCommand {
+    /// Create a study material for a subject.
+    Create {
+        /// The subject the study material is for.
+        subject_id: Id,
+        #[command(flatten)]
+        notes: StudyMaterialArgs,
+    },
+    /// Update an existing study material.
+    Update {
+        /// The ID of the study material.
+        id: Id,
+        #[command(flatten)]
+        notes: StudyMaterialArgs,
+    },
+}
+
+#[derive(Debug, Args)]
+struct StudyMaterialArgs {
+    /// The meaning note.
+    #[arg(long)]
+    meaning_note: Option<String>,
+    /// The reading note.
+    #[arg(long)]
+    reading_note: Option<String>,
+    /// Meaning synonyms, replacing any existing synonyms.
+    #[arg(long, value_delimiter = ',')]
+    meaning_synonyms: Option<Vec<String>>,
+}
+
+#[derive(Debug, Subcommand)]
+enum UserCommand {
+    /// Show the user's information and preferences.
+    Get,
+    /// Update the user's preferences.
+    SetPref(PreferenceArgs),
+}
+
+#[derive(Debug, Args)]
+struct PreferenceArgs {
+    /// The voice actor used for audio by default.
+    #[arg(long)]
+    default_voice_actor_id: Option<Id>,
+    /// Automatically play audio during extra study.
+    #[arg(long)]
+    extra_study_autoplay_audio: Option<bool>,
+    /// Automatically play audio during lessons.
+    #[arg(long)]
+    lessons_autoplay_audio: Option<bool>,
+    /// The number of subjects in each lesson batch.
+    #[arg(long)]
+    lessons_batch_size: Option<u32>,
+    /// The order lessons are presented in.
+    #[arg(long, value_parser = parse_presentation_order)]
+    lessons_presentation_order: Option<LessonPresentationOrder>,
+    /// Automatically play audio during reviews.
+    #[arg(long)]
+    reviews_autoplay_audio: Option<bool>,
+    /// Show the SRS stage indicator during reviews.
+    #[arg(long)]
+    reviews_display_srs_indicator: Option<bool>,
+}
+
+impl From<PreferenceArgs> for UpdatePreferences {
+    fn from(args: PreferenceArgs) -> Self {
+        Self {
+            default_voice_actor_id: args.default_voice_actor_id,
+            extra_study_autoplay_audio: args.extra_study_autoplay_audio,
+            lessons_autoplay_audio: args.lessons_autoplay_audio,
+            lessons_batch_size: args.lessons_batch_size,
+            lessons_presentation_order: args.lessons_presentation_order,
+            reviews_autoplay_audio: args.reviews_autoplay_audio,
+            reviews_display_srs_indicator: args.reviews_display_srs_indicator,
+        }
+    }
+}
+
+/// Parse a snake_case enum value, as it appears in the API.
+fn parse_snake_case<T: serde::de::DeserializeOwned>(value: &str) -> Result<T, String> {
+    serde_json::from_value(serde_json::Value::String(value.to_string()))
+        .map_err(|_| format!("invalid value '{value}'"))
+}
+
+fn parse_subject_type(value: &str) -> Result<SubjectType, String> {
+    parse_snake_case(value)
+}
+
+fn parse_srs_stage(value: &str) -> Result<SrsStage, String> {
+    let position: u32 = value
+        .parse()
+        .map_err(|_| format!("invalid value '{value}'"))?;
+    SrsStage::try_from(position).map_err(|e| e.to_string())
+}
+
+fn parse_presentation_order(value: &str) -> Result<LessonPresentationOrder, String> {
+    parse_snake_case(value)
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    dotenvy::dotenv().ok();
+    let cli = Cli::parse();
+
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("wk: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
+    let mut builder = WKClientBuilder::default();
+    builder.token(cli.token);
+    if let Some(base_url) = cli.base_url {
+        builder.base_url(base_url);
+    }
+    let client = builder.build()?;
+    let format = cli.output;
+
+    match cli.command {
+        Command::Summary => {
+            let summary = client.get_summary().await?;
+            output(format, &summary, summary_table)
+        }
+        Command::Subjects(args) => {
+            let subjects: Vec<_> = client.stream_subjects(&args.into()).try_collect().await?;
+            output(format, subjects.as_slice(), subjects_table)
+        }
+        Command::Assignments(args) => {
+            let assignments: Vec<_> = client
+                .stream_assignments(&args.into())
+                .try_collect()
+                .await?;
+            output(format, assignments.as_slice(), assignments_table)
+        }
+        Command::ReviewStats(args) => {
+            let statistics: Vec<_> = client
+                .stream_review_statistics(&args.into())
+                .try_collect()
+                .await?;
+            output(format, statistics.as_slice(), review_statistics_table)
+        }
+        Command::StudyMaterials(command) => {
+            let material = match command {
+                StudyMaterialCommand::Create { subject_id, notes } => {
+                    let create = CreateStudyMaterial {
+                        subject_id,
+                        meaning_note: notes.meaning_note,
+                        reading_note: notes.reading_note,
+                        meaning_synonyms: notes.meaning_synonyms,
+                    };
+                    client.create_study_material(&create).await?
+                }
+                StudyMaterialCommand::Update { id, notes } => {
+                    let update = UpdateStudyMaterial {
+                        meaning_note: notes.meaning_note,
+                        reading_note: notes.reading_note,
+                        meaning_synonyms: notes.meaning_synonyms,
+                    };
+                    client.update_study_material(id, &update).await?
+                }
+            };
+            output(format, &material, |m| {
+                study_materials_table(std::slice::from_ref(m))
+            })
+        }
+        Command::User(UserCommand::Get) => {
+            let user = client.get_user_information().await?;
+            output(format, &user, user_table)
+        }
+        Command::User(UserCommand::SetPref(args)) => {
+            let update = UpdateUser {
+                preferences: args.into(),
+            };
+            let user = client.update_user_information(&update).await?;
+            output(format, &user, user_table)
+        }
+        Command::LevelProgressions(args) => {
+            let progressions: Vec<_> = client
+                .stream_level_progressions(&args.into())
+                .try_collect()
+                .await?;
+            output(format, progressions.as_slice(), level_progressions_table)
+        }
+        Command::Resets(args) => {
+            let resets: Vec<_> = client.stream_resets(&args.into()).try_collect().await?;
+            output(format, resets.as_slice(), resets_table)
+        }
+    }
+}
+
+fn output<T: Serialize + ?Sized>(
+    format: Format,
+    value: &T,
+    table: impl FnOnce(&T) -> Table,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let stdout = io::stdout().lock();
+    match format {
+        Format::Json => serde_json::to_writer_pretty(stdout, value)?,
+        Format::Table => table(value).write(stdout)?,
+    }
+    Ok(())
+}
+
+fn summary_table(summary: &Summary) -> Table {
+    let mut table = Table::new(&["Available at", "Lessons", "Reviews"]);
+    let lessons: usize = summary
+        .data
+        .lessons
+        .iter()
+        .map(|l| l.subject_ids.len())
+        .sum();
+    let mut rows: Vec<(Timestamp, usize, usize)> = Vec::new();
+    if let Some(first) = summary.data.lessons.first() {
+        rows.push((first.available_at, lessons, 0));
+    }
+    for review in summary.data.reviews.iter() {
+        if review.subject_ids.is_empty() {
+            continue;
+        }
+        match rows.iter_mut().find(|r| r.0 == review.available_at) {
+            Some(row) => row.2 += review.subject_ids.len(),
+            None => rows.push((review.available_at, 0, review.subject_ids.len())),
+        }
+    }
+    for (available_at, lessons, reviews) in rows {
+        table.row(vec![
+            time(Some(available_at)),
+            lessons.to_string(),
+            reviews.to_string(),
+        ]);
+    }
+    table
+}
+
+fn subjects_table(subjects: &[Resource<Subject>]) -> Table {
+    let mut table = Table::new(&["ID", "Type", "Level", "Characters", "Meaning", "Reading"]);
+    for subject in subjects {
+        let common = subject.data.common();
+        let meaning = common
+            .meanings
+            .iter()
+            .find(|m| m.primary)
+            .map_or("", |m| m.meaning.as_str());
+        let (characters, reading) = match &subject.data {
+            Subject::Radical(r) => (r.characters.as_deref().unwrap_or(&common.slug), None),
+            Subject::Kanji(k) => (
+                k.characters.as_str(),
+                k.readings.iter().find(|r| r.primary).map(|r| &r.reading),
+            ),
+            Subject::Vocabulary(v) => (
+                v.characters.as_str(),
+                v.readings.iter().find(|r| r.primary).map(|r| &r.reading),
+            ),
+            Subject::KanaVocabulary(v) => (v.characters.as_str(), None),
+        };
+        table.row(vec![
+            subject.id.to_string(),
+            subject.data.subject_type().to_string(),
+            common.level.to_string(),
+            characters.to_string(),
+            meaning.to_string(),
+            reading.cloned().unwrap_or_default(),
+        ]);
+    }
+    table
+}
+
+fn assignments_table(assignments: &[Resource<Assignment>]) -> Table {
+    let mut table = Table::new(&[
+        "ID",
+        "Subject",
+        "Type",
+        "SRS stage",
+        "Available at",
+        "Passed at",
+    ]);
+    for assignment in assignments {
+        let data = &assignment.data;
+        table.row(vec![
+            assignment.id.to_string(),
+            data.subject_id.to_string(),
+            data.subject_type.to_string(),
+            data.srs_stage.to_string(),
+            time(data.available_at),
+            time(data.passed_at),
+        ]);
+    }
+    table
+}
+
+fn review_statistics_table(statistics: &[Resource<ReviewStatistic>]) -> Table {
+    let mut table = Table::new(&[
+        "ID",
+        "Subject",
+        "Type",
+        "Correct %",
+        "Meaning ✓/✗",
+        "Reading ✓/✗",
+        "Streaks",
+    ]);
+    for statistic in statistics {
+        let data = &statistic.data;
+        table.row(vec![
+            statistic.id.to_string(),
+            data.subject_id.to_string(),
+            data.subject_type.to_string(),
+            data.percentage_correct.to_string(),
+            format!("{}/{}", data.meaning_correct, data.meaning_incorrect),
+            format!("{}/{}", data.reading_correct, data.reading_incorrect),
+            format!(
+                "{}/{}",
+                data.meaning_current_streak, data.reading_current_streak
+            ),
+        ]);
+    }
+    table
+}
+
+fn study_materials_table(materials: &[Resource<StudyMaterial>]) -> Table {
+    let mut table = Table::new(&["ID", "Subject", "Meaning note", "Reading note", "Synonyms"]);
+    for material in materials {
+        let data = &material.data;
+        table.row(vec![
+            material.id.to_string(),
+            data.subject_id.to_string(),
+            data.meaning_note.clone().unwrap_or_default(),
+            data.reading_note.clone().unwrap_or_default(),
+            data.meaning_synonyms.join(", "),
+        ]);
+    }
+    table
+}
+
+fn user_table(user: &User) -> Table {
+    let data = &user.data;
+    let preferences = &data.preferences;
+    let mut table = Table::new(&["Field", "Value"]);
+    let rows = [
+        ("username", data.username.clone()),
+        ("level", data.level.to_string()),
+        ("started_at", time(Some(data.started_at))),
+        (
+            "max_level_granted",
+            data.subscription.max_level_granted.to_string(),
+        ),
+        (
+            "default_voice_actor_id",
+            preferences.default_voice_actor_id.to_string(),
+        ),
+        (
+            "extra_study_autoplay_audio",
+            preferences.extra_study_autoplay_audio.to_string(),
+        ),
+        (
+            "lessons_autoplay_audio",
+            preferences.lessons_autoplay_audio.to_string(),
+        ),
+        (
+            "lessons_batch_size",
+            preferences.lessons_batch_size.to_string(),
+        ),
+        (
+            "lessons_presentation_order",
+            serde_json::to_value(preferences.lessons_presentation_order)
+                .ok()
+                .and_then(|v| v.as_str().map(String::from))
+                .unwrap_or_default(),
+        ),
+        (
+            "reviews_autoplay_audio",
+            preferences.reviews_autoplay_audio.to_string(),
+        ),
+        (
+            "reviews_display_srs_indicator",
+            preferences.reviews_display_srs_indicator.to_string(),
+        ),
+    ];
+    for (field, value) in rows {
+        table.row(vec![field.to_string(), value]);
+    }
+    table
+}
+
+fn level_progressions_table(progressions: &[Resource<LevelProgression>]) -> Table {
+    let mut table = Table::new(&[
+        "ID",
+        "Level",
+        "Unlocked at",
+        "Started at",
+        "Passed at",
+        "Completed at",
+        "Abandoned at",
+    ]);
+    for progression in progressions {
+        let data = &progression.data;
+        table.row(vec![
+            progression.id.to_string(),
+            data.level.to_string(),
+            time(data.unlocked_at),
+            time(data.started_at),
+            time(data.passed_at),
+            time(data.completed_at),
+            time(data.abandoned_at),
+        ]);
+    }
+    table
+}
+
+fn resets_table(resets: &[Resource<Reset>]) -> Table {
+    let mut table = Table::new(&[
+        "ID",
+        "Created at",
+        "Confirmed at",
+        "Original level",
+        "Target level",
+    ]);
+    for reset in resets {
+        let data = &reset.data;
+        table.row(vec![
+            reset.id.to_string(),
+            time(Some(data.created_at)),
+            time(data.confirmed_at),
+            data.original_level.to_string(),
+            data.target_level.to_string(),
+        ]);
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{CommandFactory, Parser};
+    use wanikani_api::{client::SubjectFilter, subject::SubjectType};
+
+    use super::{Cli, Command, Format};
+
+    #[test]
+    fn test_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_subject_filter() {
+        let cli = Cli::try_parse_from([
+            "wk",
+            "--token",
+            "TOKEN",
+            "--output",
+            "json",
+            "subjects",
+            "--types",
+            "kanji,kana_vocabulary",
+            "--levels",
+            "1,2",
+            "--hidden",
+            "false",
+            "--updated-after",
+            "2023-01-01T00:00:00Z",
+        ])
+        .expect("Valid arguments");
+        assert_eq!(cli.output, Format::Json);
+
+        let filter: SubjectFilter = match cli.command {
+            Command::Subjects(args) => args.into(),
+            command => panic!("Parsed {command:?}"),
+        };
+        assert_eq!(
+            filter.types,
+            Some(vec![SubjectType::Kanji, SubjectType::KanaVocabulary])
+        );
+        assert_eq!(filter.levels, Some(vec![1, 2]));
+        assert_eq!(filter.hidden, Some(false));
+        assert!(filter.updated_after.is_some());
+
+        assert!(
+            Cli::try_parse_from(["wk", "--token", "T", "subjects", "--types", "kana"]).is_err()
+        );
+    }
+}
@@ -0,0 +1,96 @@
+//! Plain text tables for terminal output.
+
+use std::io::{self, Write};
+
+use wanikani_api::Timestamp;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// A table with a header row, printed with each column padded to its widest
+/// cell.
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    pub fn write(&self, mut writer: impl Write) -> io::Result<()> {
+        let mut widths: Vec<_> = self.headers.iter().map(|h| width(h)).collect();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(self::width(cell));
+            }
+        }
+
+        write_row(&mut writer, &self.headers, &widths)?;
+        let rule: Vec<_> = widths.iter().map(|w| "-".repeat(*w)).collect();
+        write_row(&mut writer, &rule, &widths)?;
+        for row in &self.rows {
+            write_row(&mut writer, row, &widths)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_row(writer: &mut impl Write, row: &[String], widths: &[usize]) -> io::Result<()> {
+    let cells: Vec<_> = row
+        .iter()
+        .zip(widths)
+        .map(|(cell, w)| format!("{cell}{}", " ".repeat(w - width(cell))))
+        .collect();
+    writeln!(writer, "{}", cells.join("  ").trim_end())
+}
+
+/// The width of `text` in a terminal. Japanese characters take up two
+/// columns.
+fn width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c {
+            '\u{1100}'..='\u{115F}'
+            | '\u{2E80}'..='\u{A4CF}'
+            | '\u{AC00}'..='\u{D7A3}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FE30}'..='\u{FE4F}'
+            | '\u{FF00}'..='\u{FF60}'
+            | '\u{FFE0}'..='\u{FFE6}' => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Format an optional timestamp for a table cell.
+pub fn time(timestamp: Option<Timestamp>) -> String {
+    timestamp.map_or_else(|| "-".into(), |t| t.format("%Y-%m-%d %H:%M").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Table;
+
+    #[test]
+    fn test_table() {
+        let mut table = Table::new(&["ID", "Characters", "Meaning"]);
+        table.row(vec!["1".into(), "一".into(), "Ground".into()]);
+        table.row(vec!["440".into(), "一つ".into(), "One Thing".into()]);
+
+        let mut output = Vec::new();
+        table.write(&mut output).expect("Written");
+        assert_eq!(
+            String::from_utf8(output).expect("UTF-8"),
+            "ID   Characters  Meaning\n\
+             ---  ----------  ---------\n\
+             1    一          Ground\n\
+             440  一つ        One Thing\n"
+        );
+    }
+}
@@ -0,0 +1,895 @@
+//! A blocking (synchronous) WaniKani client.
+//!
+//! [`WKBlockingClient`] wraps a [`WKClient`] and mirrors every endpoint
+//! method, using the same filter structs and returning the same types and
+//! errors, but blocks the current thread until the request completes. The
+//! `stream_*` methods are replaced by `iter_*` methods, which return an
+//! [`Iterator`] that fetches pages as it goes.
+//!
+//! The blocking client runs requests on its own single-threaded Tokio
+//! runtime, so it must **not** be used from within an async context; doing so
+//! will panic. Use [`WKClient`] there instead.
+//!
+//! ### Example
+//! ```rust,no_run
+//! # use wanikani_api::blocking::WKBlockingClient;
+//! let client = WKBlockingClient::new("MY_TOKEN".to_string());
+//!
+//! let summary = client.get_summary().unwrap();
+//! ```
+
+use std::{fmt::Debug, pin::Pin};
+
+use futures_util::{Stream, StreamExt};
+use serde::Deserialize;
+use tokio::runtime::{Builder, Runtime};
+use url::Url;
+
+use crate::{
+    client::{Fetched, Response, Validators, WKClient},
+    Collection, Error, Resource,
+};
+
+#[cfg(feature = "level_progression")]
+use crate::level_progression::LevelProgression;
+#[cfg(feature = "reset")]
+use crate::reset::Reset;
+#[cfg(feature = "spaced_repetition_system")]
+use crate::spaced_repetition_system::SpacedRepetitionSystem;
+#[cfg(feature = "summary")]
+use crate::summary::Summary;
+#[cfg(feature = "user")]
+use crate::user::{UpdateUser, User};
+#[cfg(feature = "voice_actor")]
+use crate::voice_actor::VoiceActor;
+#[cfg(feature = "assignment")]
+use crate::{
+    assignment::{Assignment, AssignmentStart},
+    client::AssignmentFilter,
+};
+#[cfg(feature = "review")]
+use crate::{
+    client::ReviewFilter,
+    review::{CreateReview, CreatedReview, Review},
+};
+#[cfg(feature = "review_statistic")]
+use crate::{client::ReviewStatisticFilter, review_statistic::ReviewStatistic};
+#[cfg(feature = "study_material")]
+use crate::{
+    client::StudyMaterialFilter,
+    study_material::{CreateStudyMaterial, StudyMaterial, UpdateStudyMaterial},
+};
+#[cfg(feature = "subject")]
+use crate::{
+    client::SubjectFilter,
+    subject::{Subject, WaniKaniSubject},
+};
+
+#[cfg(any(
+    feature = "level_progression",
+    feature = "reset",
+    feature = "spaced_repetition_system",
+    feature = "voice_actor"
+))]
+use crate::client::IdFilter;
+#[cfg(any(
+    feature = "assignment",
+    feature = "level_progression",
+    feature = "reset",
+    feature = "review",
+    feature = "review_statistic",
+    feature = "spaced_repetition_system",
+    feature = "study_material",
+    feature = "subject",
+    feature = "voice_actor"
+))]
+use crate::Id;
+
+/// A blocking client for the WaniKani API.
+///
+/// See the [module documentation](self) for details.
+pub struct WKBlockingClient {
+    client: WKClient,
+    runtime: Runtime,
+}
+
+impl Debug for WKBlockingClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WKBlockingClient")
+            .field("client", &self.client)
+            .finish_non_exhaustive()
+    }
+}
+
+impl From<WKClient> for WKBlockingClient {
+    fn from(client: WKClient) -> Self {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("Runtime can be created");
+
+        Self { client, runtime }
+    }
+}
+
+/// An iterator over every resource in a collection, fetching further pages as
+/// needed. Returned by the `iter_*` methods of [`WKBlockingClient`].
+pub struct ResourceIter<'a, T> {
+    runtime: &'a Runtime,
+    stream: Pin<Box<dyn Stream<Item = Result<Resource<T>, Error>> + 'a>>,
+}
+
+impl<T> Debug for ResourceIter<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResourceIter").finish_non_exhaustive()
+    }
+}
+
+impl<T> Iterator for ResourceIter<'_, T> {
+    type Item = Result<Resource<T>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runtime.block_on(self.stream.next())
+    }
+}
+
+impl WKBlockingClient {
+    /// Create a new client using the given API token and default settings.
+    ///
+    /// To configure the client further, build a [`WKClient`] with
+    /// [`WKClientBuilder`](crate::client::WKClientBuilder) and convert it with
+    /// [`WKBlockingClient::from`].
+    pub fn new(token: String) -> Self {
+        WKClient::new(token, reqwest::Client::default()).into()
+    }
+
+    /// The async client used to make requests.
+    pub fn inner(&self) -> &WKClient {
+        &self.client
+    }
+
+    fn iter<'a, T>(
+        &'a self,
+        stream: impl Stream<Item = Result<Resource<T>, Error>> + 'a,
+    ) -> ResourceIter<'a, T> {
+        ResourceIter {
+            runtime: &self.runtime,
+            stream: Box::pin(stream),
+        }
+    }
+
+    /// Fetch a resource by its URL. See [`WKClient::get_resource_by_url`].
+    pub fn get_resource_by_url<T>(&self, url: &Url) -> Result<T, Error>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        self.runtime.block_on(self.client.get_resource_by_url(url))
+    }
+
+    /// See [`WKClient::get_resource_by_url_with_meta`].
+    pub fn get_resource_by_url_with_meta<T>(&self, url: &Url) -> Result<Response<T>, Error>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        self.runtime
+            .block_on(self.client.get_resource_by_url_with_meta(url))
+    }
+
+    /// Fetch a resource by its URL, unless it has not changed since
+    /// `validators` were issued. See
+    /// [`WKClient::get_resource_by_url_if_modified`].
+    pub fn get_resource_by_url_if_modified<T>(
+        &self,
+        url: &Url,
+        validators: &Validators,
+    ) -> Result<Fetched<T>, Error>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        self.runtime
+            .block_on(self.client.get_resource_by_url_if_modified(url, validators))
+    }
+
+    /// Iterate over every resource in the collection at `url`. See
+    /// [`WKClient::stream_resources_by_url`].
+    pub fn iter_resources_by_url<T>(&self, url: &Url) -> ResourceIter<'_, T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        self.iter(self.client.stream_resources_by_url(url))
+    }
+
+    /// Fetch the remaining pages of a collection. See
+    /// [`WKClient::collect_all`].
+    pub fn collect_all<T>(&self, collection: Collection<T>) -> Result<Vec<Resource<T>>, Error>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        self.runtime.block_on(self.client.collect_all(collection))
+    }
+}
+
+#[cfg(feature = "assignment")]
+impl WKBlockingClient {
+    /// See [`WKClient::get_assignments`].
+    pub fn get_assignments(
+        &self,
+        filters: &AssignmentFilter,
+    ) -> Result<Collection<Assignment>, Error> {
+        self.runtime.block_on(self.client.get_assignments(filters))
+    }
+
+    /// See [`WKClient::get_assignments_with_meta`].
+    pub fn get_assignments_with_meta(
+        &self,
+        filters: &AssignmentFilter,
+    ) -> Result<Response<Collection<Assignment>>, Error> {
+        self.runtime
+            .block_on(self.client.get_assignments_with_meta(filters))
+    }
+
+    /// See [`WKClient::get_assignments_if_modified`].
+    pub fn get_assignments_if_modified(
+        &self,
+        filters: &AssignmentFilter,
+        validators: &Validators,
+    ) -> Result<Fetched<Collection<Assignment>>, Error> {
+        self.runtime
+            .block_on(self.client.get_assignments_if_modified(filters, validators))
+    }
+
+    /// See [`WKClient::stream_assignments`].
+    pub fn iter_assignments(&self, filters: &AssignmentFilter) -> ResourceIter<'_, Assignment> {
+        self.iter(self.client.stream_assignments(filters))
+    }
+
+    /// See [`WKClient::get_specific_assignment`].
+    pub fn get_specific_assignment(&self, id: Id) -> Result<Resource<Assignment>, Error> {
+        self.runtime
+            .block_on(self.client.get_specific_assignment(id))
+    }
+
+    /// See [`WKClient::get_specific_assignment_with_meta`].
+    pub fn get_specific_assignment_with_meta(
+        &self,
+        id: Id,
+    ) -> Result<Response<Resource<Assignment>>, Error> {
+        self.runtime
+            .block_on(self.client.get_specific_assignment_with_meta(id))
+    }
+
+    /// See [`WKClient::start_assignment`].
+    pub fn start_assignment(
+        &self,
+        id: Id,
+        body: &AssignmentStart,
+    ) -> Result<Resource<Assignment>, Error> {
+        self.runtime
+            .block_on(self.client.start_assignment(id, body))
+    }
+
+    /// See [`WKClient::start_assignment_with_meta`].
+    pub fn start_assignment_with_meta(
+        &self,
+        id: Id,
+        body: &AssignmentStart,
+    ) -> Result<Response<Resource<Assignment>>, Error> {
+        self.runtime
+            .block_on(self.client.start_assignment_with_meta(id, body))
+    }
+}
+
+#[cfg(feature = "level_progression")]
+impl WKBlockingClient {
+    /// See [`WKClient::get_level_progressions`].
+    pub fn get_level_progressions(
+        &self,
+        filters: &IdFilter,
+    ) -> Result<Collection<LevelProgression>, Error> {
+        self.runtime
+            .block_on(self.client.get_level_progressions(filters))
+    }
+
+    /// See [`WKClient::get_level_progressions_with_meta`].
+    pub fn get_level_progressions_with_meta(
+        &self,
+        filters: &IdFilter,
+    ) -> Result<Response<Collection<LevelProgression>>, Error> {
+        self.runtime
+            .block_on(self.client.get_level_progressions_with_meta(filters))
+    }
+
+    /// See [`WKClient::get_level_progressions_if_modified`].
+    pub fn get_level_progressions_if_modified(
+        &self,
+        filters: &IdFilter,
+        validators: &Validators,
+    ) -> Result<Fetched<Collection<LevelProgression>>, Error> {
+        self.runtime.block_on(
+            self.client
+                .get_level_progressions_if_modified(filters, validators),
+        )
+    }
+
+    /// See [`WKClient::stream_level_progressions`].
+    pub fn iter_level_progressions(
+        &self,
+        filters: &IdFilter,
+    ) -> ResourceIter<'_, LevelProgression> {
+        self.iter(self.client.stream_level_progressions(filters))
+    }
+
+    /// See [`WKClient::get_specific_level_progression`].
+    pub fn get_specific_level_progression(
+        &self,
+        id: Id,
+    ) -> Result<Resource<LevelProgression>, Error> {
+        self.runtime
+            .block_on(self.client.get_specific_level_progression(id))
+    }
+
+    /// See [`WKClient::get_specific_level_progression_with_meta`].
+    pub fn get_specific_level_progression_with_meta(
+        &self,
+        id: Id,
+    ) -> Result<Response<Resource<LevelProgression>>, Error> {
+        self.runtime
+            .block_on(self.client.get_specific_level_progression_with_meta(id))
+    }
+}
+
+#[cfg(feature = "reset")]
+impl WKBlockingClient {
+    /// See [`WKClient::get_resets`].
+    pub fn get_resets(&self, filters: &IdFilter) -> Result<Collection<Reset>, Error> {
+        self.runtime.block_on(self.client.get_resets(filters))
+    }
+
+    /// See [`WKClient::get_resets_with_meta`].
+    pub fn get_resets_with_meta(
+        &self,
+        filters: &IdFilter,
+    ) -> Result<Response<Collection<Reset>>, Error> {
+        self.runtime
+            .block_on(self.client.get_resets_with_meta(filters))
+    }
+
+    /// See [`WKClient::get_resets_if_modified`].
+    pub fn get_resets_if_modified(
+        &self,
+        filters: &IdFilter,
+        validators: &Validators,
+    ) -> Result<Fetched<Collection<Reset>>, Error> {
+        self.runtime
+            .block_on(self.client.get_resets_if_modified(filters, validators))
+    }
+
+    /// See [`WKClient::stream_resets`].
+    pub fn iter_resets(&self, filters: &IdFilter) -> ResourceIter<'_, Reset> {
+        self.iter(self.client.stream_resets(filters))
+    }
+
+    /// See [`WKClient::get_specific_reset`].
+    pub fn get_specific_reset(&self, id: Id) -> Result<Resource<Reset>, Error> {
+        self.runtime.block_on(self.client.get_specific_reset(id))
+    }
+
+    /// See [`WKClient::get_specific_reset_with_meta`].
+    pub fn get_specific_reset_with_meta(&self, id: Id) -> Result<Response<Resource<Reset>>, Error> {
+        self.runtime
+            .block_on(self.client.get_specific_reset_with_meta(id))
+    }
+}
+
+#[cfg(feature = "review")]
+impl WKBlockingClient {
+    /// See [`WKClient::get_reviews`].
+    pub fn get_reviews(&self, filters: &ReviewFilter) -> Result<Collection<Review>, Error> {
+        self.runtime.block_on(self.client.get_reviews(filters))
+    }
+
+    /// See [`WKClient::get_reviews_with_meta`].
+    pub fn get_reviews_with_meta(
+        &self,
+        filters: &ReviewFilter,
+    ) -> Result<Response<Collection<Review>>, Error> {
+        self.runtime
+            .block_on(self.client.get_reviews_with_meta(filters))
+    }
+
+    /// See [`WKClient::get_reviews_if_modified`].
+    pub fn get_reviews_if_modified(
+        &self,
+        filters: &ReviewFilter,
+        validators: &Validators,
+    ) -> Result<Fetched<Collection<Review>>, Error> {
+        self.runtime
+            .block_on(self.client.get_reviews_if_modified(filters, validators))
+    }
+
+    /// See [`WKClient::stream_reviews`].
+    pub fn iter_reviews(&self, filters: &ReviewFilter) -> ResourceIter<'_, Review> {
+        self.iter(self.client.stream_reviews(filters))
+    }
+
+    /// See [`WKClient::get_specific_review`].
+    pub fn get_specific_review(&self, id: Id) -> Result<Resource<Review>, Error> {
+        self.runtime.block_on(self.client.get_specific_review(id))
+    }
+
+    /// See [`WKClient::get_specific_review_with_meta`].
+    pub fn get_specific_review_with_meta(
+        &self,
+        id: Id,
+    ) -> Result<Response<Resource<Review>>, Error> {
+        self.runtime
+            .block_on(self.client.get_specific_review_with_meta(id))
+    }
+
+    /// See [`WKClient::create_review`].
+    pub fn create_review(&self, review: &CreateReview) -> Result<CreatedReview, Error> {
+        self.runtime.block_on(self.client.create_review(review))
+    }
+
+    /// See [`WKClient::create_review_with_meta`].
+    pub fn create_review_with_meta(
+        &self,
+        review: &CreateReview,
+    ) -> Result<Response<CreatedReview>, Error> {
+        self.runtime
+            .block_on(self.client.create_review_with_meta(review))
+    }
+}
+
+#[cfg(feature = "review_statistic")]
+impl WKBlockingClient {
+    /// See [`WKClient::get_review_statistics`].
+    pub fn get_review_statistics(
+        &self,
+        filters: &ReviewStatisticFilter,
+    ) -> Result<Collection<ReviewStatistic>, Error> {
+        self.runtime
+            .block_on(self.client.get_review_statistics(filters))
+    }
+
+    /// See [`WKClient::get_review_statistics_with_meta`].
+    pub fn get_review_statistics_with_meta(
+        &self,
+        filters: &ReviewStatisticFilter,
+    ) -> Result<Response<Collection<ReviewStatistic>>, Error> {
+        self.runtime
+            .block_on(self.client.get_review_statistics_with_meta(filters))
+    }
+
+    /// See [`WKClient::get_review_statistics_if_modified`].
+    pub fn get_review_statistics_if_modified(
+        &self,
+        filters: &ReviewStatisticFilter,
+        validators: &Validators,
+    ) -> Result<Fetched<Collection<ReviewStatistic>>, Error> {
+        self.runtime.block_on(
+            self.client
+                .get_review_statistics_if_modified(filters, validators),
+        )
+    }
+
+    /// See [`WKClient::stream_review_statistics`].
+    pub fn iter_review_statistics(
+        &self,
+        filters: &ReviewStatisticFilter,
+    ) -> ResourceIter<'_, ReviewStatistic> {
+        self.iter(self.client.stream_review_statistics(filters))
+    }
+
+    /// See [`WKClient::get_specific_review_statistic`].
+    pub fn get_specific_review_statistic(
+        &self,
+        id: Id,
+    ) -> Result<Resource<ReviewStatistic>, Error> {
+        self.runtime
+            .block_on(self.client.get_specific_review_statistic(id))
+    }
+
+    /// See [`WKClient::get_specific_review_statistic_with_meta`].
+    pub fn get_specific_review_statistic_with_meta(
+        &self,
+        id: Id,
+    ) -> Result<Response<Resource<ReviewStatistic>>, Error> {
+        self.runtime
+            .block_on(self.client.get_specific_review_statistic_with_meta(id))
+    }
+}
+
+#[cfg(feature = "spaced_repetition_system")]
+impl WKBlockingClient {
+    /// See [`WKClient::get_spaced_repetition_systems`].
+    pub fn get_spaced_repetition_systems(
+        &self,
+        filters: &IdFilter,
+    ) -> Result<Collection<SpacedRepetitionSystem>, Error> {
+        self.runtime
+            .block_on(self.client.get_spaced_repetition_systems(filters))
+    }
+
+    /// See [`WKClient::get_spaced_repetition_systems_with_meta`].
+    pub fn get_spaced_repetition_systems_with_meta(
+        &self,
+        filters: &IdFilter,
+    ) -> Result<Response<Collection<SpacedRepetitionSystem>>, Error> {
+        self.runtime
+            .block_on(self.client.get_spaced_repetition_systems_with_meta(filters))
+    }
+
+    /// See [`WKClient::get_spaced_repetition_systems_if_modified`].
+    pub fn get_spaced_repetition_systems_if_modified(
+        &self,
+        filters: &IdFilter,
+        validators: &Validators,
+    ) -> Result<Fetched<Collection<SpacedRepetitionSystem>>, Error> {
+        self.runtime.block_on(
+            self.client
+                .get_spaced_repetition_systems_if_modified(filters, validators),
+        )
+    }
+
+    /// See [`WKClient::stream_spaced_repetition_systems`].
+    pub fn iter_spaced_repetition_systems(
+        &self,
+        filters: &IdFilter,
+    ) -> ResourceIter<'_, SpacedRepetitionSystem> {
+        self.iter(self.client.stream_spaced_repetition_systems(filters))
+    }
+
+    /// See [`WKClient::get_specific_spaced_repetition_system`].
+    pub fn get_specific_spaced_repetition_system(
+        &self,
+        id: Id,
+    ) -> Result<Resource<SpacedRepetitionSystem>, Error> {
+        self.runtime
+            .block_on(self.client.get_specific_spaced_repetition_system(id))
+    }
+
+    /// See [`WKClient::get_specific_spaced_repetition_system_with_meta`].
+    pub fn get_specific_spaced_repetition_system_with_meta(
+        &self,
+        id: Id,
+    ) -> Result<Response<Resource<SpacedRepetitionSystem>>, Error> {
+        self.runtime.block_on(
+            self.client
+                .get_specific_spaced_repetition_system_with_meta(id),
+        )
+    }
+}
+
+#[cfg(feature = "study_material")]
+impl WKBlockingClient {
+    /// See [`WKClient::get_study_materials`].
+    pub fn get_study_materials(
+        &self,
+        filters: &StudyMaterialFilter,
+    ) -> Result<Collection<StudyMaterial>, Error> {
+        self.runtime
+            .block_on(self.client.get_study_materials(filters))
+    }
+
+    /// See [`WKClient::get_study_materials_with_meta`].
+    pub fn get_study_materials_with_meta(
+        &self,
+        filters: &StudyMaterialFilter,
+    ) -> Result<Response<Collection<StudyMaterial>>, Error> {
+        self.runtime
+            .block_on(self.client.get_study_materials_with_meta(filters))
+    }
+
+    /// See [`WKClient::get_study_materials_if_modified`].
+    pub fn get_study_materials_if_modified(
+        &self,
+        filters: &StudyMaterialFilter,
+        validators: &Validators,
+    ) -> Result<Fetched<Collection<StudyMaterial>>, Error> {
+        self.runtime.block_on(
+            self.client
+                .get_study_materials_if_modified(filters, validators),
+        )
+    }
+
+    /// See [`WKClient::stream_study_materials`].
+    pub fn iter_study_materials(
+        &self,
+        filters: &StudyMaterialFilter,
+    ) -> ResourceIter<'_, StudyMaterial> {
+        self.iter(self.client.stream_study_materials(filters))
+    }
+
+    /// See [`WKClient::get_specific_study_material`].
+    pub fn get_specific_study_material(&self, id: Id) -> Result<Resource<StudyMaterial>, Error> {
+        self.runtime
+            .block_on(self.client.get_specific_study_material(id))
+    }
+
+    /// See [`WKClient::get_specific_study_material_with_meta`].
+    pub fn get_specific_study_material_with_meta(
+        &self,
+        id: Id,
+    ) -> Result<Response<Resource<StudyMaterial>>, Error> {
+        self.runtime
+            .block_on(self.client.get_specific_study_material_with_meta(id))
+    }
+
+    /// See [`WKClient::create_study_material`].
+    pub fn create_study_material(
+        &self,
+        material: &CreateStudyMaterial,
+    ) -> Result<Resource<StudyMaterial>, Error> {
+        self.runtime
+            .block_on(self.client.create_study_material(material))
+    }
+
+    /// See [`WKClient::create_study_material_with_meta`].
+    pub fn create_study_material_with_meta(
+        &self,
+        material: &CreateStudyMaterial,
+    ) -> Result<Response<Resource<StudyMaterial>>, Error> {
+        self.runtime
+            .block_on(self.client.create_study_material_with_meta(material))
+    }
+
+    /// See [`WKClient::update_study_material`].
+    pub fn update_study_material(
+        &self,
+        id: Id,
+        material: &UpdateStudyMaterial,
+    ) -> Result<Resource<StudyMaterial>, Error> {
+        self.runtime
+            .block_on(self.client.update_study_material(id, material))
+    }
+
+    /// See [`WKClient::update_study_material_with_meta`].
+    pub fn update_study_material_with_meta(
+        &self,
+        id: Id,
+        material: &UpdateStudyMaterial,
+    ) -> Result<Response<Resource<StudyMaterial>>, Error> {
+        self.runtime
+            .block_on(self.client.update_study_material_with_meta(id, material))
+    }
+}
+
+#[cfg(feature = "subject")]
+impl WKBlockingClient {
+    /// See [`WKClient::get_subjects`].
+    pub fn get_subjects(&self, filters: &SubjectFilter) -> Result<Collection<Subject>, Error> {
+        self.runtime.block_on(self.client.get_subjects(filters))
+    }
+
+    /// See [`WKClient::get_subjects_with_meta`].
+    pub fn get_subjects_with_meta(
+        &self,
+        filters: &SubjectFilter,
+    ) -> Result<Response<Collection<Subject>>, Error> {
+        self.runtime
+            .block_on(self.client.get_subjects_with_meta(filters))
+    }
+
+    /// See [`WKClient::get_subjects_if_modified`].
+    pub fn get_subjects_if_modified(
+        &self,
+        filters: &SubjectFilter,
+        validators: &Validators,
+    ) -> Result<Fetched<Collection<Subject>>, Error> {
+        self.runtime
+            .block_on(self.client.get_subjects_if_modified(filters, validators))
+    }
+
+    /// See [`WKClient::stream_subjects`].
+    pub fn iter_subjects(&self, filters: &SubjectFilter) -> ResourceIter<'_, Subject> {
+        self.iter(self.client.stream_subjects(filters))
+    }
+
+    /// See [`WKClient::get_specific_subject`].
+    pub fn get_specific_subject<T: WaniKaniSubject>(&self, id: Id) -> Result<Resource<T>, Error> {
+        self.runtime.block_on(self.client.get_specific_subject(id))
+    }
+
+    /// See [`WKClient::get_specific_subject_with_meta`].
+    pub fn get_specific_subject_with_meta<T: WaniKaniSubject>(
+        &self,
+        id: Id,
+    ) -> Result<Response<Resource<T>>, Error> {
+        self.runtime
+            .block_on(self.client.get_specific_subject_with_meta(id))
+    }
+}
+
+#[cfg(feature = "summary")]
+impl WKBlockingClient {
+    /// See [`WKClient::get_summary`].
+    pub fn get_summary(&self) -> Result<Summary, Error> {
+        self.runtime.block_on(self.client.get_summary())
+    }
+
+    /// See [`WKClient::get_summary_with_meta`].
+    pub fn get_summary_with_meta(&self) -> Result<Response<Summary>, Error> {
+        self.runtime.block_on(self.client.get_summary_with_meta())
+    }
+
+    /// See [`WKClient::get_summary_if_modified`].
+    pub fn get_summary_if_modified(
+        &self,
+        validators: &Validators,
+    ) -> Result<Fetched<Summary>, Error> {
+        self.runtime
+            .block_on(self.client.get_summary_if_modified(validators))
+    }
+}
+
+#[cfg(feature = "user")]
+impl WKBlockingClient {
+    /// See [`WKClient::get_user_information`].
+    pub fn get_user_information(&self) -> Result<User, Error> {
+        self.runtime.block_on(self.client.get_user_information())
+    }
+
+    /// See [`WKClient::get_user_information_with_meta`].
+    pub fn get_user_information_with_meta(&self) -> Result<Response<User>, Error> {
+        self.runtime
+            .block_on(self.client.get_user_information_with_meta())
+    }
+
+    /// See [`WKClient::get_user_information_if_modified`].
+    pub fn get_user_information_if_modified(
+        &self,
+        validators: &Validators,
+    ) -> Result<Fetched<User>, Error> {
+        self.runtime
+            .block_on(self.client.get_user_information_if_modified(validators))
+    }
+
+    /// See [`WKClient::update_user_information`].
+    pub fn update_user_information(&self, user: &UpdateUser) -> Result<User, Error> {
+        self.runtime
+            .block_on(self.client.update_user_information(user))
+    }
+
+    /// See [`WKClient::update_user_information_with_meta`].
+    pub fn update_user_information_with_meta(
+        &self,
+        user: &UpdateUser,
+    ) -> Result<Response<User>, Error> {
+        self.runtime
+            .block_on(self.client.update_user_information_with_meta(user))
+    }
+}
+
+#[cfg(feature = "voice_actor")]
+impl WKBlockingClient {
+    /// See [`WKClient::get_voice_actors`].
+    pub fn get_voice_actors(&self, filters: &IdFilter) -> Result<Collection<VoiceActor>, Error> {
+        self.runtime.block_on(self.client.get_voice_actors(filters))
+    }
+
+    /// See [`WKClient::get_voice_actors_with_meta`].
+    pub fn get_voice_actors_with_meta(
+        &self,
+        filters: &IdFilter,
+    ) -> Result<Response<Collection<VoiceActor>>, Error> {
+        self.runtime
+            .block_on(self.client.get_voice_actors_with_meta(filters))
+    }
+
+    /// See [`WKClient::get_voice_actors_if_modified`].
+    pub fn get_voice_actors_if_modified(
+        &self,
+        filters: &IdFilter,
+        validators: &Validators,
+    ) -> Result<Fetched<Collection<VoiceActor>>, Error> {
+        self.runtime.block_on(
+            self.client
+                .get_voice_actors_if_modified(filters, validators),
+        )
+    }
+
+    /// See [`WKClient::stream_voice_actors`].
+    pub fn iter_voice_actors(&self, filters: &IdFilter) -> ResourceIter<'_, VoiceActor> {
+        self.iter(self.client.stream_voice_actors(filters))
+    }
+
+    /// See [`WKClient::get_specific_voice_actor`].
+    pub fn get_specific_voice_actor(&self, id: Id) -> Result<Resource<VoiceActor>, Error> {
+        self.runtime
+            .block_on(self.client.get_specific_voice_actor(id))
+    }
+
+    /// See [`WKClient::get_specific_voice_actor_with_meta`].
+    pub fn get_specific_voice_actor_with_meta(
+        &self,
+        id: Id,
+    ) -> Result<Response<Resource<VoiceActor>>, Error> {
+        self.runtime
+            .block_on(self.client.get_specific_voice_actor_with_meta(id))
+    }
+}
+
+#[cfg(all(test, feature = "model"))]
+mod tests {
+    use crate::{
+        client::{Fetched, StudyMaterialFilter, SubjectFilter, Validators},
+        study_material::CreateStudyMaterial,
+        subject::Subject,
+        test_util::{MockConfig, MockDataset, MockWaniKani},
+        Error,
+    };
+
+    use super::WKBlockingClient;
+
+    fn start_server(config: MockConfig) -> MockWaniKani {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("Runtime")
+            .block_on(MockWaniKani::start_with(MockDataset::fixtures(), config))
+    }
+
+    #[test]
+    fn test_blocking_client() {
+        let server = start_server(MockConfig {
+            page_size: Some(1),
+            ..MockConfig::default()
+        });
+        let client = WKBlockingClient::from(server.client());
+
+        let summary = client.get_summary().expect("Summary");
+        assert_eq!(Some(&summary), server.dataset().summary.as_ref());
+
+        let subjects = client
+            .get_subjects(&SubjectFilter::default())
+            .expect("Subjects");
+        assert_eq!(subjects.total_count, 4);
+        assert_eq!(subjects.data.len(), 1);
+        assert_eq!(client.collect_all(subjects).expect("All subjects").len(), 4);
+
+        let ids = client
+            .iter_subjects(&SubjectFilter::default())
+            .map(|subject| subject.map(|subject| subject.id))
+            .collect::<Result<Vec<_>, _>>()
+            .expect("All subjects");
+        assert_eq!(ids, [1, 440, 2467, 9210]);
+
+        let kanji = client
+            .get_specific_subject::<Subject>(440)
+            .expect("Subject");
+        assert_eq!(kanji.id, 440);
+
+        let created = client
+            .create_study_material(&CreateStudyMaterial {
+                subject_id: 440,
+                meaning_note: Some("One".into()),
+                reading_note: None,
+                meaning_synonyms: None,
+            })
+            .expect("Study material");
+        assert_eq!(created.data.subject_id, 440);
+
+        let fetched = client
+            .get_study_materials_if_modified(
+                &StudyMaterialFilter::default(),
+                &Validators::default(),
+            )
+            .expect("Study materials");
+        let (_, validators) = fetched.into_modified().expect("Modified");
+        let fetched = client
+            .get_study_materials_if_modified(&StudyMaterialFilter::default(), &validators)
+            .expect("Study materials");
+        assert_eq!(fetched, Fetched::NotModified);
+    }
+
+    #[test]
+    fn test_blocking_client_error() {
+        let server = start_server(MockConfig::default());
+        let mut builder = server.client_builder();
+        let client = WKBlockingClient::from(builder.token("WRONG".into()).build().expect("Client"));
+
+        let result = client.get_user_information();
+        assert!(matches!(result, Err(Error::Unauthorized { .. })));
+    }
+}
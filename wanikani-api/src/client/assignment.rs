@@ -1,12 +1,13 @@
+use futures_util::Stream;
 use url::Url;
 
 use crate::{
-    assignment::{Assignment, AssignmentStart},
+    assignment::{Assignment, AssignmentStart, SrsStage},
     cross_feature::SubjectType,
     Collection, Error, Id, Resource, Timestamp,
 };
 
-use super::{Filter, WKClient};
+use super::{Fetched, Filter, Response, Validators, WKClient};
 
 const ASSIGNMENT_PATH: &str = "assignments";
 
@@ -17,6 +18,15 @@ impl WKClient {
         &self,
         filters: &AssignmentFilter,
     ) -> Result<Collection<Assignment>, Error> {
+        Ok(self.get_assignments_with_meta(filters).await?.data)
+    }
+
+    /// Like [`get_assignments`](Self::get_assignments), but also returns the
+    /// response's [metadata](super::ResponseMeta).
+    pub async fn get_assignments_with_meta(
+        &self,
+        filters: &AssignmentFilter,
+    ) -> Result<Response<Collection<Assignment>>, Error> {
         let mut url = self.base_url.clone();
         url.path_segments_mut()
             .expect("Valid URL")
@@ -26,11 +36,56 @@ impl WKClient {
 
         let req = self.client.get(url);
 
-        self.do_request("get_assignments", req).await
+        self.do_request_with_meta("get_assignments", req).await
+    }
+
+    /// Returns the collection of assignments matching `filters`, unless it has not
+    /// changed since `validators` were issued.
+    pub async fn get_assignments_if_modified(
+        &self,
+        filters: &AssignmentFilter,
+        validators: &Validators,
+    ) -> Result<Fetched<Collection<Assignment>>, Error> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("Valid URL")
+            .push(ASSIGNMENT_PATH);
+
+        filters.apply_filters(&mut url);
+
+        let req = self.client.get(url);
+
+        self.do_conditional_request("get_assignments_if_modified", req, validators)
+            .await
+    }
+
+    /// Streams every assignment matching `filters`, fetching each page of the
+    /// collection as it is needed.
+    pub fn stream_assignments(
+        &self,
+        filters: &AssignmentFilter,
+    ) -> impl Stream<Item = Result<Resource<Assignment>, Error>> + '_ {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("Valid URL")
+            .push(ASSIGNMENT_PATH);
+
+        filters.apply_filters(&mut url);
+
+        self.stream_resources("stream_assignments".into(), url)
     }
 
     /// Retrieves a specific assignment by its `id`.
     pub async fn get_specific_assignment(&self, id: Id) -> Result<Resource<Assignment>, Error> {
+        Ok(self.get_specific_assignment_with_meta(id).await?.data)
+    }
+
+    /// Like [`get_specific_assignment`](Self::get_specific_assignment), but
+    /// also returns the response's [metadata](super::ResponseMeta).
+    pub async fn get_specific_assignment_with_meta(
+        &self,
+        id: Id,
+    ) -> Result<Response<Resource<Assignment>>, Error> {
         let mut url = self.base_url.clone();
         url.path_segments_mut()
             .expect("Valid URL")
@@ -39,7 +94,8 @@ impl WKClient {
 
         let req = self.client.get(url);
 
-        self.do_request("get_specific_assignment", req).await
+        self.do_request_with_meta("get_specific_assignment", req)
+            .await
     }
 
     /// Mark the assignment as started, moving the assignment from the lessons
@@ -60,6 +116,16 @@ impl WKClient {
         id: Id,
         body: &AssignmentStart,
     ) -> Result<Resource<Assignment>, Error> {
+        Ok(self.start_assignment_with_meta(id, body).await?.data)
+    }
+
+    /// Like [`start_assignment`](Self::start_assignment), but also returns the
+    /// response's [metadata](super::ResponseMeta).
+    pub async fn start_assignment_with_meta(
+        &self,
+        id: Id,
+        body: &AssignmentStart,
+    ) -> Result<Response<Resource<Assignment>>, Error> {
         let mut url = self.base_url.clone();
         url.path_segments_mut()
             .expect("Valid URL")
@@ -69,7 +135,7 @@ impl WKClient {
 
         let req = self.client.put(url).json(body);
 
-        self.do_request("start_assignment", req).await
+        self.do_request_with_meta("start_assignment", req).await
     }
 }
 
@@ -100,7 +166,7 @@ pub struct AssignmentFilter {
     pub levels: Option<Vec<u32>>,
     /// Only assignments where `data.srs_stage` matches one of the array values
     /// are returned.
-    pub srs_stages: Option<Vec<u32>>,
+    pub srs_stages: Option<Vec<SrsStage>>,
     /// When set to `true`, returns assignments that have a value in
     /// `data.started_at`. Returns assignments with a `null` `data.started_at`
     /// if `false`.
@@ -169,7 +235,7 @@ impl Filter for AssignmentFilter {
                 "srs_stages",
                 value
                     .iter()
-                    .map(ToString::to_string)
+                    .map(|stage| stage.position().to_string())
                     .collect::<Vec<_>>()
                     .join(",")
                     .as_str(),
@@ -211,7 +277,7 @@ impl Filter for AssignmentFilter {
 
 #[cfg(test)]
 mod tests {
-    use crate::client::{create_client, init_tests};
+    use crate::client::{create_cassette_client, init_tests};
 
     #[tokio::test]
     async fn test_get_assignments() {
@@ -219,7 +285,9 @@ mod tests {
 
         init_tests();
 
-        let client = create_client();
+        let Some(client) = create_cassette_client("assignment/get_assignments").await else {
+            return;
+        };
         let filters = AssignmentFilter {
             levels: Some(vec![2]),
             ..AssignmentFilter::default()
@@ -230,13 +298,16 @@ mod tests {
     async fn test_get_specific_assignment() {
         init_tests();
 
-        let client = create_client();
+        let Some(client) = create_cassette_client("assignment/get_specific_assignment").await
+        else {
+            return;
+        };
         let assignments = client
             .get_assignments(&Default::default())
             .await
             .expect("Get all assignments");
 
-        if let Some(assignment) = assignments.data.get(0) {
+        if let Some(assignment) = assignments.data.first() {
             assert!(client.get_specific_assignment(assignment.id).await.is_ok());
         } else {
             log::warn!("No assignments detected, this test should not be considered reliable");
@@ -0,0 +1,141 @@
+use reqwest::{
+    header::{HeaderMap, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+    RequestBuilder,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq, Hash)]
+/// The caching validators returned with a response, used to make
+/// [conditional requests](crate#conditional-requests).
+///
+/// Store these alongside the data they were returned with, and pass them to
+/// one of the `_if_modified` methods of [`WKClient`](super::WKClient) on the
+/// next request for the same data. The default value has no validators, and
+/// will always fetch the data.
+pub struct Validators {
+    /// The `ETag` header of the response, sent as `If-None-Match`.
+    pub etag: Option<String>,
+    /// The `Last-Modified` header of the response, sent as
+    /// `If-Modified-Since`.
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(ToString::to_string)
+        };
+
+        Self {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        }
+    }
+
+    pub(crate) fn apply(&self, mut req: RequestBuilder) -> RequestBuilder {
+        if let Some(ref etag) = self.etag {
+            req = req.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(ref last_modified) = self.last_modified {
+            req = req.header(IF_MODIFIED_SINCE, last_modified);
+        }
+        req
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The result of a conditional request.
+pub enum Fetched<T> {
+    /// The data has changed since the validators were issued. Contains the new
+    /// data, and the validators to use for the next request.
+    Modified(T, Validators),
+    /// The data has not changed since the validators were issued.
+    NotModified,
+}
+
+impl<T> Fetched<T> {
+    /// Returns `true` if new data was received.
+    pub fn is_modified(&self) -> bool {
+        matches!(self, Self::Modified(..))
+    }
+
+    /// Returns the new data and validators, if the data was modified.
+    pub fn into_modified(self) -> Option<(T, Validators)> {
+        match self {
+            Self::Modified(data, validators) => Some((data, validators)),
+            Self::NotModified => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::{
+        header::{HeaderMap, HeaderValue},
+        Client,
+    };
+
+    use super::{Fetched, Validators};
+
+    #[test]
+    fn test_validators_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(Validators::from_headers(&headers), Validators::default());
+
+        headers.insert("ETag", HeaderValue::from_static("W/\"abc123\""));
+        headers.insert(
+            "Last-Modified",
+            HeaderValue::from_static("Fri, 11 Nov 2011 11:11:11 GMT"),
+        );
+
+        let validators = Validators::from_headers(&headers);
+        assert_eq!(validators.etag.expect("ETag"), "W/\"abc123\"");
+        assert_eq!(
+            validators.last_modified.expect("Last-Modified"),
+            "Fri, 11 Nov 2011 11:11:11 GMT"
+        );
+    }
+
+    #[test]
+    fn test_validators_apply() {
+        let client = Client::default();
+
+        let req = Validators::default()
+            .apply(client.get("https://api.wanikani.com/v2/subjects"))
+            .build()
+            .expect("Request");
+        assert!(req.headers().get("If-None-Match").is_none());
+        assert!(req.headers().get("If-Modified-Since").is_none());
+
+        let validators = Validators {
+            etag: Some("W/\"abc123\"".into()),
+            last_modified: Some("Fri, 11 Nov 2011 11:11:11 GMT".into()),
+        };
+        let req = validators
+            .apply(client.get("https://api.wanikani.com/v2/subjects"))
+            .build()
+            .expect("Request");
+        assert_eq!(
+            req.headers().get("If-None-Match").expect("Header"),
+            "W/\"abc123\""
+        );
+        assert_eq!(
+            req.headers().get("If-Modified-Since").expect("Header"),
+            "Fri, 11 Nov 2011 11:11:11 GMT"
+        );
+    }
+
+    #[test]
+    fn test_fetched() {
+        let modified = Fetched::Modified(5, Validators::default());
+        assert!(modified.is_modified());
+        assert_eq!(modified.into_modified(), Some((5, Validators::default())));
+
+        let not_modified: Fetched<u32> = Fetched::NotModified;
+        assert!(!not_modified.is_modified());
+        assert_eq!(not_modified.into_modified(), None);
+    }
+}
@@ -1,6 +1,8 @@
+use futures_util::Stream;
+
 use crate::{level_progression::LevelProgression, Collection, Error, Id, Resource};
 
-use super::{Filter, IdFilter, WKClient};
+use super::{Fetched, Filter, IdFilter, Response, Validators, WKClient};
 
 const PROG_PATH: &str = "level_progressions";
 
@@ -11,6 +13,33 @@ impl WKClient {
         &self,
         filters: &IdFilter,
     ) -> Result<Collection<LevelProgression>, Error> {
+        Ok(self.get_level_progressions_with_meta(filters).await?.data)
+    }
+
+    /// Like [`get_level_progressions`](Self::get_level_progressions), but also
+    /// returns the response's [metadata](super::ResponseMeta).
+    pub async fn get_level_progressions_with_meta(
+        &self,
+        filters: &IdFilter,
+    ) -> Result<Response<Collection<LevelProgression>>, Error> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut().expect("Valid URL").push(PROG_PATH);
+
+        filters.apply_filters(&mut url);
+
+        let req = self.client.get(url);
+
+        self.do_request_with_meta("get_level_progressions", req)
+            .await
+    }
+
+    /// Returns the collection of level progressions matching `filters`, unless it has not
+    /// changed since `validators` were issued.
+    pub async fn get_level_progressions_if_modified(
+        &self,
+        filters: &IdFilter,
+        validators: &Validators,
+    ) -> Result<Fetched<Collection<LevelProgression>>, Error> {
         let mut url = self.base_url.clone();
         url.path_segments_mut().expect("Valid URL").push(PROG_PATH);
 
@@ -18,7 +47,22 @@ impl WKClient {
 
         let req = self.client.get(url);
 
-        self.do_request("get_level_progressions", req).await
+        self.do_conditional_request("get_level_progressions_if_modified", req, validators)
+            .await
+    }
+
+    /// Streams every level progression matching `filters`, fetching each page
+    /// of the collection as it is needed.
+    pub fn stream_level_progressions(
+        &self,
+        filters: &IdFilter,
+    ) -> impl Stream<Item = Result<Resource<LevelProgression>, Error>> + '_ {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut().expect("Valid URL").push(PROG_PATH);
+
+        filters.apply_filters(&mut url);
+
+        self.stream_resources("stream_level_progressions".into(), url)
     }
 
     /// Retrieves a specific level progression by its id.
@@ -26,6 +70,19 @@ impl WKClient {
         &self,
         id: Id,
     ) -> Result<Resource<LevelProgression>, Error> {
+        Ok(self
+            .get_specific_level_progression_with_meta(id)
+            .await?
+            .data)
+    }
+
+    /// Like
+    /// [`get_specific_level_progression`](Self::get_specific_level_progression),
+    /// but also returns the response's [metadata](super::ResponseMeta).
+    pub async fn get_specific_level_progression_with_meta(
+        &self,
+        id: Id,
+    ) -> Result<Response<Resource<LevelProgression>>, Error> {
         let mut url = self.base_url.clone();
         url.path_segments_mut()
             .expect("Valid URL")
@@ -34,19 +91,23 @@ impl WKClient {
 
         let req = self.client.get(url);
 
-        self.do_request("get_specific_level_progression", req).await
+        self.do_request_with_meta("get_specific_level_progression", req)
+            .await
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::client::{create_client, init_tests, IdFilter};
+    use crate::client::{create_cassette_client, init_tests, IdFilter};
 
     #[tokio::test]
     async fn test_get_level_progressions() {
         init_tests();
 
-        let client = create_client();
+        let Some(client) = create_cassette_client("level_progression/get_level_progressions").await
+        else {
+            return;
+        };
 
         assert!(client
             .get_level_progressions(&IdFilter::default())
@@ -59,13 +120,17 @@ mod tests {
     async fn test_get_specific_level_progression() {
         init_tests();
 
-        let client = create_client();
+        let Some(client) =
+            create_cassette_client("level_progression/get_specific_level_progression").await
+        else {
+            return;
+        };
         let progressions = client
             .get_level_progressions(&Default::default())
             .await
             .expect("Get all progs");
 
-        if let Some(prog) = progressions.data.get(0) {
+        if let Some(prog) = progressions.data.first() {
             assert!(client.get_specific_level_progression(prog.id).await.is_ok());
         } else {
             log::warn!(
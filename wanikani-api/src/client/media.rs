@@ -0,0 +1,714 @@
+use std::{
+    collections::HashSet,
+    io,
+    path::{Path, PathBuf},
+};
+
+use futures_util::{stream, StreamExt, TryStreamExt};
+use mime::Mime;
+use reqwest::{
+    header::{CONTENT_RANGE, RANGE},
+    Response as HttpResponse, StatusCode,
+};
+use thiserror::Error as E;
+use tokio::{fs, io::AsyncWriteExt};
+use url::Url;
+
+use crate::{
+    subject::{ImageMetadata, PronunciationAudio, Subject},
+    voice_actor::Gender,
+    Error, Id, Resource,
+};
+
+use super::{trace::RequestSpan, WKClient};
+
+/// The extension given to partially downloaded files.
+const PARTIAL_EXTENSION: &str = "part";
+
+/// The endpoint name media downloads are reported under.
+const MEDIA_ENDPOINT: &str = "download_media";
+
+#[derive(Debug, E)]
+/// Possible error conditions when downloading subject media.
+pub enum MediaError {
+    #[error("Download error: {0}")]
+    /// A file could not be downloaded.
+    Download(#[from] Error),
+    #[error("File error: {0}")]
+    /// A file could not be written to the media directory.
+    Io(#[from] io::Error),
+    #[error("Download of {url} was incomplete: expected {expected} bytes, received {received}")]
+    /// A download ended before the whole file was received. The partial file
+    /// is kept, and resumed by the next download.
+    Incomplete {
+        /// Where the file was being downloaded from.
+        url: Url,
+        /// The size of the file reported by the server.
+        expected: u64,
+        /// The size of the file received.
+        received: u64,
+    },
+}
+
+impl From<reqwest::Error> for MediaError {
+    fn from(error: reqwest::Error) -> Self {
+        Self::Download(error.into())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Which radical images to download.
+pub enum ImageSelection {
+    #[default]
+    /// Every image.
+    All,
+    /// No images.
+    None,
+    /// SVG images. If `inline_styles` is set, only the images that do, or do
+    /// not, contain built-in CSS styling.
+    Svg {
+        /// Whether the SVG asset contains built-in CSS styling.
+        inline_styles: Option<bool>,
+    },
+    /// PNG images. If `style_name` is set, only the images with that style,
+    /// such as `original` or `64px`.
+    Png {
+        /// The name of the PNG style.
+        style_name: Option<String>,
+    },
+}
+
+impl ImageSelection {
+    /// Whether an image with `metadata` is selected.
+    pub fn matches(&self, metadata: &ImageMetadata) -> bool {
+        match (self, metadata) {
+            (Self::All, _) => true,
+            (Self::Svg { inline_styles }, ImageMetadata::SVG { inline_styles: i }) => {
+                inline_styles.map_or(true, |s| s == *i)
+            }
+            (Self::Png { style_name }, ImageMetadata::PNG { style_name: n, .. }) => {
+                style_name.as_ref().map_or(true, |s| s == n)
+            }
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Which pronunciation audio to download. Audio must match every filter that
+/// is set.
+pub struct AudioFilter {
+    /// Only audio by one of these voice actors.
+    pub voice_actor_ids: Option<Vec<Id>>,
+    /// Only audio by voice actors of this gender.
+    pub gender: Option<Gender>,
+    /// Only audio of one of these content types, such as `audio/mpeg`.
+    pub content_types: Option<Vec<Mime>>,
+}
+
+impl AudioFilter {
+    /// A filter for the audio of the user's default voice actor, which is the
+    /// audio they hear in lessons and reviews.
+    #[cfg(feature = "user")]
+    pub fn for_user(user: &crate::user::User) -> Self {
+        Self {
+            voice_actor_ids: Some(vec![user.data.preferences.default_voice_actor_id]),
+            ..Self::default()
+        }
+    }
+
+    /// Whether `audio` is selected.
+    pub fn matches(&self, audio: &PronunciationAudio) -> bool {
+        let metadata = &audio.metadata;
+        self.voice_actor_ids
+            .as_ref()
+            .map_or(true, |ids| ids.contains(&metadata.voice_actor_id))
+            && self.gender.map_or(true, |g| g == metadata.gender)
+            && self.content_types.as_ref().map_or(true, |types| {
+                types
+                    .iter()
+                    .any(|t| t.essence_str() == audio.content_type.essence_str())
+            })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Controls which media [`WKClient::download_media`] downloads, and how.
+pub struct MediaOptions {
+    /// Which radical images to download.
+    pub images: ImageSelection,
+    /// Which pronunciation audio to download, or `None` to skip audio.
+    pub audio: Option<AudioFilter>,
+    /// The maximum number of files downloaded at once.
+    pub concurrency: usize,
+}
+
+impl Default for MediaOptions {
+    fn default() -> Self {
+        Self {
+            images: ImageSelection::default(),
+            audio: Some(AudioFilter::default()),
+            concurrency: 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A media file in the media directory.
+pub struct MediaFile {
+    /// The subject the file belongs to.
+    pub subject_id: Id,
+    /// Where the file was downloaded from.
+    pub url: Url,
+    /// The content type of the file.
+    pub content_type: Mime,
+    /// The location of the file.
+    pub path: PathBuf,
+    /// `true` if the file was downloaded, or `false` if it was already in the
+    /// media directory.
+    pub downloaded: bool,
+}
+
+impl WKClient {
+    /// Download the radical images and pronunciation audio of `subjects`
+    /// selected by `options` into `dir`, returning every selected file.
+    ///
+    /// Files are named by [`PronunciationAudio::file_name`] and
+    /// [`CharacterImage::file_name`](crate::subject::CharacterImage::file_name),
+    /// which are unique to each URL, so the directory can be shared between
+    /// runs, and files already in it are not downloaded again. This is also
+    /// the layout `AnkiExport::with_media_dir` expects.
+    ///
+    /// Downloads go through the client's retry policy and middleware like any
+    /// other request, but without the API token, which the CDN does not need.
+    ///
+    /// Files are written with a `.part` extension until they are complete,
+    /// which is when their size matches the one reported by the server. If a
+    /// download is interrupted, the next call resumes it where it left off.
+    ///
+    /// ### Example
+    /// ```rust
+    /// # use wanikani_api::client::{AudioFilter, MediaOptions, SubjectFilter, WKClient};
+    /// # use futures_util::TryStreamExt;
+    /// # let client = WKClient::new("MY_TOKEN".to_string(), reqwest::Client::default());
+    /// # async move {
+    /// let user = client.get_user_information().await.unwrap();
+    /// let subjects: Vec<_> = client
+    ///     .stream_subjects(&SubjectFilter::default())
+    ///     .try_collect()
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// let options = MediaOptions {
+    ///     audio: Some(AudioFilter::for_user(&user)),
+    ///     ..MediaOptions::default()
+    /// };
+    /// let files = client
+    ///     .download_media(&subjects, "media".as_ref(), &options)
+    ///     .await
+    ///     .unwrap();
+    /// # };
+    /// ```
+    pub async fn download_media(
+        &self,
+        subjects: &[Resource<Subject>],
+        dir: &Path,
+        options: &MediaOptions,
+    ) -> Result<Vec<MediaFile>, MediaError> {
+        fs::create_dir_all(dir).await?;
+
+        let mut names = HashSet::new();
+        let mut files = Vec::new();
+        for subject in subjects {
+            for (url, content_type, name) in selected_media(&subject.data, options) {
+                let path = dir.join(&name);
+                if names.insert(name) {
+                    files.push(MediaFile {
+                        subject_id: subject.id,
+                        url: url.clone(),
+                        content_type: content_type.clone(),
+                        path,
+                        downloaded: false,
+                    });
+                }
+            }
+        }
+
+        log::debug!("download_media selected {} files", files.len());
+
+        stream::iter(files.into_iter().map(|mut file| async move {
+            if fs::metadata(&file.path).await.is_err() {
+                self.download_file(&file.url, &file.path).await?;
+                file.downloaded = true;
+            }
+            Ok::<_, MediaError>(file)
+        }))
+        .buffered(options.concurrency.max(1))
+        .try_collect()
+        .await
+    }
+
+    async fn download_file(&self, url: &Url, path: &Path) -> Result<(), MediaError> {
+        let partial = path.with_extension(match path.extension() {
+            Some(extension) => format!("{}.{PARTIAL_EXTENSION}", extension.to_string_lossy()),
+            None => PARTIAL_EXTENSION.into(),
+        });
+        let offset = fs::metadata(&partial).await.map_or(0, |m| m.len());
+
+        if offset > 0 {
+            log::debug!("download_media resuming {url} from byte {offset}");
+        }
+        let mut resp = match self.get_media(url, offset).await {
+            Err(error)
+                if offset > 0 && error.status() == Some(StatusCode::RANGE_NOT_SATISFIABLE) =>
+            {
+                log::warn!("download_media could not resume {url}, restarting");
+                self.get_media(url, 0).await?
+            }
+            resp => resp?,
+        };
+
+        let resumed = resp.status() == StatusCode::PARTIAL_CONTENT;
+        let expected = if resumed {
+            content_range_size(&resp)
+                .or_else(|| resp.content_length().map(|length| offset + length))
+        } else {
+            resp.content_length()
+        };
+
+        let mut file = if resumed {
+            fs::OpenOptions::new().append(true).open(&partial).await?
+        } else {
+            fs::File::create(&partial).await?
+        };
+        while let Some(chunk) = resp.chunk().await? {
+            file.write_all(&chunk).await?;
+        }
+        file.flush().await?;
+
+        let received = fs::metadata(&partial).await?.len();
+        if let Some(expected) = expected.filter(|expected| *expected != received) {
+            if received > expected {
+                // Resuming cannot repair a file that is too long
+                fs::remove_file(&partial).await?;
+            }
+            return Err(MediaError::Incomplete {
+                url: url.to_owned(),
+                expected,
+                received,
+            });
+        }
+
+        fs::rename(&partial, path).await?;
+        log::debug!("download_media downloaded {url}");
+        Ok(())
+    }
+
+    /// Request the media file at `url`, from byte `offset` on.
+    async fn get_media(&self, url: &Url, offset: u64) -> Result<HttpResponse, Error> {
+        let mut req = self.client.get(url.to_owned());
+        if offset > 0 {
+            req = req.header(RANGE, format!("bytes={offset}-"));
+        }
+
+        let (_, resp) = RequestSpan::new(MEDIA_ENDPOINT)
+            .instrument(self.send_with_retry(MEDIA_ENDPOINT, req, false))
+            .await?;
+        Ok(resp)
+    }
+}
+
+/// The complete size of the file a partial response is part of, from its
+/// `Content-Range` header.
+fn content_range_size(resp: &reqwest::Response) -> Option<u64> {
+    let range = resp.headers().get(CONTENT_RANGE)?.to_str().ok()?;
+    range.rsplit_once('/')?.1.parse().ok()
+}
+
+/// The URL, content type and file name of each file of `subject` selected by
+/// `options`.
+fn selected_media<'a>(
+    subject: &'a Subject,
+    options: &'a MediaOptions,
+) -> impl Iterator<Item = (&'a Url, &'a Mime, String)> {
+    let (images, audios) = match subject {
+        Subject::Radical(radical) => (radical.character_images.as_slice(), &[][..]),
+        Subject::Kanji(_) => (&[][..], &[][..]),
+        Subject::Vocabulary(vocabulary) => (&[][..], vocabulary.pronunciation_audios.as_slice()),
+        Subject::KanaVocabulary(vocabulary) => {
+            (&[][..], vocabulary.pronunciation_audios.as_slice())
+        }
+    };
+
+    let images = images
+        .iter()
+        .filter(|image| options.images.matches(&image.metadata))
+        .filter_map(|image| Some((&image.url, &image.content_type, image.file_name()?)));
+    let audios = audios
+        .iter()
+        .filter(|audio| options.audio.as_ref().map_or(false, |f| f.matches(audio)))
+        .filter_map(|audio| Some((&audio.url, &audio.content_type, audio.file_name()?)));
+
+    images.chain(audios)
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+    use wiremock::{
+        matchers::{header, header_exists, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use crate::{
+        client::{create_mock_client, init_tests, RetryPolicy, WKClientBuilder},
+        subject::{ImageMetadata, Subject},
+        voice_actor::Gender,
+        Resource,
+    };
+
+    use super::{AudioFilter, ImageSelection, MediaError, MediaOptions};
+
+    fn subjects(server: &MockServer) -> Vec<Resource<Subject>> {
+        let mut subjects: Vec<Resource<Subject>> = vec![
+            serde_json::from_str(include_str!("../../test_files/radical.json"))
+                .expect("Deserialize"),
+            serde_json::from_str(include_str!("../../test_files/vocabulary.json"))
+                .expect("Deserialize"),
+        ];
+        for subject in &mut subjects {
+            match &mut subject.data {
+                Subject::Radical(radical) => {
+                    for image in &mut radical.character_images {
+                        image.url = on_server(&image.url, server);
+                    }
+                }
+                Subject::Vocabulary(vocabulary) => {
+                    for audio in &mut vocabulary.pronunciation_audios {
+                        audio.url = on_server(&audio.url, server);
+                    }
+                }
+                _ => {}
+            }
+        }
+        subjects
+    }
+
+    /// `url`, moved from the CDN to `server`.
+    fn on_server(url: &Url, server: &MockServer) -> Url {
+        let mut moved: Url = server.uri().parse().expect("URL");
+        moved.set_path(url.path());
+        moved.set_query(url.query());
+        moved
+    }
+
+    /// The file name of the SVG image without inline styles.
+    fn svg_name(subjects: &[Resource<Subject>]) -> String {
+        let Subject::Radical(radical) = &subjects[0].data else {
+            panic!("Expected radical");
+        };
+        radical
+            .character_images
+            .iter()
+            .find(|image| {
+                image.metadata
+                    == ImageMetadata::SVG {
+                        inline_styles: false,
+                    }
+            })
+            .and_then(|image| image.file_name())
+            .expect("SVG image")
+    }
+
+    /// The file name of the MP3 audio by voice actor `2`.
+    fn mp3_name(subjects: &[Resource<Subject>]) -> String {
+        let Subject::Vocabulary(vocabulary) = &subjects[1].data else {
+            panic!("Expected vocabulary");
+        };
+        vocabulary
+            .pronunciation_audios
+            .iter()
+            .find(|audio| {
+                audio.metadata.voice_actor_id == 2
+                    && audio.content_type.essence_str() == "audio/mpeg"
+            })
+            .and_then(|audio| audio.file_name())
+            .expect("MP3 audio")
+    }
+
+    fn media_dir(name: &str) -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("wanikani-media-{name}-{}", std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        dir
+    }
+
+    #[test]
+    fn test_image_selection() {
+        let svg = ImageMetadata::SVG {
+            inline_styles: false,
+        };
+        let png = ImageMetadata::PNG {
+            color: "#000000".into(),
+            dimensions: "64x64".into(),
+            style_name: "64px".into(),
+        };
+
+        assert!(ImageSelection::All.matches(&svg));
+        assert!(!ImageSelection::None.matches(&png));
+        assert!(ImageSelection::Svg {
+            inline_styles: None
+        }
+        .matches(&svg));
+        assert!(!ImageSelection::Svg {
+            inline_styles: Some(true)
+        }
+        .matches(&svg));
+        assert!(!ImageSelection::Svg {
+            inline_styles: None
+        }
+        .matches(&png));
+        assert!(ImageSelection::Png {
+            style_name: Some("64px".into())
+        }
+        .matches(&png));
+        assert!(!ImageSelection::Png {
+            style_name: Some("original".into())
+        }
+        .matches(&png));
+    }
+
+    #[tokio::test]
+    async fn test_download_media() {
+        init_tests();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(
+                "/images/legacy/576-subject-1-without-css-original.svg",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_string("<svg/>"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/audios/3020-subject-2467.mp3"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ID3"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = create_mock_client(&server);
+        let subjects = subjects(&server);
+        let dir = media_dir("download");
+        let options = MediaOptions {
+            audio: Some(AudioFilter {
+                gender: Some(Gender::Male),
+                content_types: Some(vec!["audio/mpeg".parse().expect("MIME")]),
+                ..AudioFilter::default()
+            }),
+            ..MediaOptions::default()
+        };
+
+        let files = client
+            .download_media(&subjects, &dir, &options)
+            .await
+            .expect("Downloaded");
+
+        let names: Vec<_> = files
+            .iter()
+            .map(|f| {
+                (
+                    f.subject_id,
+                    f.path.file_name().expect("Name").to_string_lossy(),
+                    f.downloaded,
+                )
+            })
+            .collect();
+        assert_eq!(
+            names,
+            [
+                (1, svg_name(&subjects).into(), true),
+                (2467, mp3_name(&subjects).into(), true),
+            ]
+        );
+        assert_eq!(
+            std::fs::read_to_string(&files[1].path).expect("Audio file"),
+            "ID3"
+        );
+
+        let files = client
+            .download_media(&subjects, &dir, &options)
+            .await
+            .expect("Cached");
+        assert!(files.iter().all(|f| !f.downloaded));
+
+        std::fs::remove_dir_all(dir).expect("Cleaned up");
+    }
+
+    #[tokio::test]
+    async fn test_download_media_retry() {
+        init_tests();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/audios/3020-subject-2467.mp3"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/audios/3020-subject-2467.mp3"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ID3"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = WKClientBuilder::default()
+            .token("MOCK_TOKEN".into())
+            .base_url(server.uri().parse().expect("Mock server URL"))
+            .retry_policy(RetryPolicy {
+                initial_backoff: std::time::Duration::ZERO,
+                ..RetryPolicy::default()
+            })
+            .build()
+            .expect("Mock client");
+        let subjects = subjects(&server);
+        let dir = media_dir("retry");
+        let options = MediaOptions {
+            images: ImageSelection::None,
+            audio: Some(AudioFilter {
+                content_types: Some(vec!["audio/mpeg".parse().expect("MIME")]),
+                voice_actor_ids: Some(vec![2]),
+                ..AudioFilter::default()
+            }),
+            concurrency: 1,
+        };
+
+        let files = client
+            .download_media(&subjects, &dir, &options)
+            .await
+            .expect("Retried");
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(
+            std::fs::read_to_string(&files[0].path).expect("Audio file"),
+            "ID3"
+        );
+
+        std::fs::remove_dir_all(dir).expect("Cleaned up");
+    }
+
+    #[tokio::test]
+    async fn test_download_media_resume() {
+        init_tests();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/audios/3020-subject-2467.mp3"))
+            .and(header("Range", "bytes=3-"))
+            .respond_with(
+                ResponseTemplate::new(206)
+                    .insert_header("Content-Range", "bytes 3-6/7")
+                    .set_body_string("DATA"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(header_exists("Authorization"))
+            .respond_with(ResponseTemplate::new(403))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let client = create_mock_client(&server);
+        let subjects = subjects(&server);
+        let dir = media_dir("resume");
+        std::fs::create_dir_all(&dir).expect("Media directory");
+        std::fs::write(dir.join(format!("{}.part", mp3_name(&subjects))), "ID3")
+            .expect("Partial file");
+
+        let options = MediaOptions {
+            images: ImageSelection::None,
+            audio: Some(AudioFilter {
+                content_types: Some(vec!["audio/mpeg".parse().expect("MIME")]),
+                voice_actor_ids: Some(vec![2]),
+                ..AudioFilter::default()
+            }),
+            concurrency: 1,
+        };
+
+        let files = client
+            .download_media(&subjects, &dir, &options)
+            .await
+            .expect("Resumed");
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(
+            std::fs::read_to_string(&files[0].path).expect("Audio file"),
+            "ID3DATA"
+        );
+        assert!(!dir.join(format!("{}.part", mp3_name(&subjects))).exists());
+
+        std::fs::remove_dir_all(dir).expect("Cleaned up");
+    }
+
+    #[tokio::test]
+    async fn test_download_media_incomplete() {
+        init_tests();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/audios/3020-subject-2467.mp3"))
+            .and(header("Range", "bytes=3-"))
+            .respond_with(
+                ResponseTemplate::new(206)
+                    .insert_header("Content-Range", "bytes 3-9/10")
+                    .set_body_string("DATA"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = create_mock_client(&server);
+        let subjects = subjects(&server);
+        let dir = media_dir("incomplete");
+        std::fs::create_dir_all(&dir).expect("Media directory");
+        std::fs::write(dir.join(format!("{}.part", mp3_name(&subjects))), "ID3")
+            .expect("Partial file");
+
+        let options = MediaOptions {
+            images: ImageSelection::None,
+            audio: Some(AudioFilter {
+                content_types: Some(vec!["audio/mpeg".parse().expect("MIME")]),
+                voice_actor_ids: Some(vec![2]),
+                ..AudioFilter::default()
+            }),
+            concurrency: 1,
+        };
+
+        let error = client
+            .download_media(&subjects, &dir, &options)
+            .await
+            .expect_err("Incomplete");
+
+        assert!(matches!(
+            error,
+            MediaError::Incomplete {
+                expected: 10,
+                received: 7,
+                ..
+            }
+        ));
+        assert!(!dir.join(mp3_name(&subjects)).exists());
+        assert_eq!(
+            std::fs::read_to_string(dir.join(format!("{}.part", mp3_name(&subjects))))
+                .expect("Partial file"),
+            "ID3DATA"
+        );
+
+        std::fs::remove_dir_all(dir).expect("Cleaned up");
+    }
+}
@@ -0,0 +1,168 @@
+use std::fmt::Debug;
+
+use futures_util::future::{self, BoxFuture};
+use reqwest::{RequestBuilder, Response};
+
+use crate::Error;
+
+/// A layer that can inspect or modify the requests a
+/// [`WKClient`](super::WKClient) sends, and the responses it receives.
+///
+/// Layers are registered with
+/// [`WKClientBuilder::middleware`](super::WKClientBuilder::middleware). Each
+/// request passes through the layers in the order they were registered, and
+/// each response passes through them in reverse order, so the first layer
+/// registered sees the request first and the response last.
+///
+/// The hooks run for every attempt at a request, including retries. Requests
+/// already carry the authorization and revision headers when they reach
+/// [`on_request`](Self::on_request), and responses are passed to
+/// [`on_response`](Self::on_response) before their status is checked, so
+/// error responses are seen as well.
+///
+/// `endpoint` is the name of the client method that made the request, such
+/// as `get_assignments`.
+///
+/// ### Example
+/// ```rust
+/// # use std::sync::atomic::{AtomicU64, Ordering};
+/// # use wanikani_api::client::{Middleware, WKClientBuilder};
+/// # use wanikani_api::prelude::reqwest::RequestBuilder;
+/// #[derive(Debug, Default)]
+/// struct CountRequests(AtomicU64);
+///
+/// impl Middleware for CountRequests {
+///     fn on_request(&self, endpoint: &str, request: RequestBuilder) -> RequestBuilder {
+///         let count = self.0.fetch_add(1, Ordering::Relaxed) + 1;
+///         println!("{endpoint}: request #{count}");
+///         request.header("X-Request-Count", count)
+///     }
+/// }
+///
+/// let client = WKClientBuilder::default()
+///     .token("MY_TOKEN".into())
+///     .middleware(CountRequests::default())
+///     .build()
+///     .unwrap();
+/// ```
+pub trait Middleware: Debug + Send + Sync {
+    /// Called before a request is sent. Returns the request to send, which
+    /// may have been modified.
+    fn on_request(&self, endpoint: &str, request: RequestBuilder) -> RequestBuilder {
+        let _ = endpoint;
+        request
+    }
+
+    /// Called when a response is received. Returns the response the client
+    /// should handle, which may have been modified or replaced. Returning an
+    /// error fails the request with that error.
+    fn on_response<'a>(
+        &'a self,
+        endpoint: &'a str,
+        response: Response,
+    ) -> BoxFuture<'a, Result<Response, Error>> {
+        let _ = endpoint;
+        Box::pin(future::ready(Ok(response)))
+    }
+
+    /// Called when a request could not be sent, or no response was received,
+    /// for example because the connection failed.
+    fn on_error(&self, endpoint: &str, error: &reqwest::Error) {
+        let _ = (endpoint, error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use futures_util::future::BoxFuture;
+    use reqwest::{header::HeaderValue, RequestBuilder, Response};
+    use wiremock::{
+        matchers::{header_exists, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use super::Middleware;
+    use crate::{client::WKClientBuilder, Error};
+
+    #[derive(Debug)]
+    struct Layer {
+        name: &'static str,
+        calls: &'static Mutex<Vec<String>>,
+    }
+
+    impl Middleware for Layer {
+        fn on_request(&self, endpoint: &str, request: RequestBuilder) -> RequestBuilder {
+            self.calls
+                .lock()
+                .expect("Lock not poisoned")
+                .push(format!("{} request {endpoint}", self.name));
+            request.header(self.name, "1")
+        }
+
+        fn on_response<'a>(
+            &'a self,
+            endpoint: &'a str,
+            mut response: Response,
+        ) -> BoxFuture<'a, Result<Response, Error>> {
+            self.calls
+                .lock()
+                .expect("Lock not poisoned")
+                .push(format!("{} response {endpoint}", self.name));
+            response
+                .headers_mut()
+                .insert("Ratelimit-Remaining", HeaderValue::from_static("7"));
+            Box::pin(async move { Ok(response) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_middleware() {
+        static CALLS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/user"))
+            .and(header_exists("X-First"))
+            .and(header_exists("X-Second"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw(r#"{"object":"user"}"#, "application/json"),
+            )
+            .mount(&server)
+            .await;
+
+        let client = WKClientBuilder::default()
+            .token("MOCK_TOKEN".into())
+            .base_url(server.uri().parse().expect("Mock server URL"))
+            .middleware(Layer {
+                name: "X-First",
+                calls: &CALLS,
+            })
+            .middleware(Layer {
+                name: "X-Second",
+                calls: &CALLS,
+            })
+            .build()
+            .expect("Mock client");
+
+        let url = format!("{}/user", server.uri()).parse().expect("URL");
+        let response = client
+            .get_resource_by_url_with_meta::<serde_json::Value>(&url)
+            .await
+            .expect("Success");
+
+        assert_eq!(response.meta.rate_limit_remaining, Some(7));
+        let calls = CALLS.lock().expect("Lock not poisoned");
+        let endpoint = "get_resource_by_url<serde_json::value::Value>";
+        assert_eq!(
+            *calls,
+            [
+                format!("X-First request {endpoint}"),
+                format!("X-Second request {endpoint}"),
+                format!("X-Second response {endpoint}"),
+                format!("X-First response {endpoint}"),
+            ]
+        );
+    }
+}
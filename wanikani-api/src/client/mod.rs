@@ -1,17 +1,32 @@
 //! HTTP client implementation for consuming the WaniKani API
 
-use std::{any::type_name, fmt::Debug};
+use std::{
+    any::type_name,
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
 
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{DateTime, NaiveDate};
 use derive_builder::Builder;
-use reqwest::{header::HeaderMap, Client, RequestBuilder, Response, StatusCode};
+use futures_util::{stream, Stream, TryStreamExt};
+use reqwest::{
+    header::HeaderMap, Client, Method, RequestBuilder, Response as HttpResponse, StatusCode,
+};
 use serde::Deserialize;
 use url::Url;
 
-use crate::{Error, Id, Timestamp, WanikaniError, API_VERSION, URL_BASE};
+use self::{retry::RateLimitStatus, trace::RequestSpan};
+use crate::{
+    Collection, Error, ErrorContext, Id, Resource, Timestamp, WanikaniError, API_VERSION, URL_BASE,
+};
 
 const REVISION_HEADER: &str = "Wanikani-Revision";
 
+/// The number of characters of an undecodable response body kept in
+/// [`Error::Decode`].
+const BODY_SNIPPET_LENGTH: usize = 200;
+
 pub(crate) trait Filter {
     fn apply_filters(&self, url: &mut Url);
 }
@@ -56,9 +71,45 @@ mod level_progression;
 #[cfg(feature = "reset")]
 mod reset;
 
+#[cfg(feature = "review")]
+mod review;
+
+#[cfg(feature = "review")]
+pub use review::ReviewFilter;
+
+mod conditional;
+
+pub use conditional::{Fetched, Validators};
+
+mod middleware;
+
+pub use middleware::Middleware;
+
+mod response;
+
+pub use response::{Response, ResponseMeta};
+
+mod retry;
+
+pub use retry::RetryPolicy;
+
+mod trace;
+
+#[cfg(feature = "media")]
+mod media;
+
+#[cfg(feature = "media")]
+pub use media::{AudioFilter, ImageSelection, MediaError, MediaFile, MediaOptions};
+
 #[cfg(feature = "review_statistic")]
 mod review_statistic;
 
+#[cfg(feature = "review_statistic")]
+pub use review_statistic::ReviewStatisticFilter;
+
+#[cfg(feature = "spaced_repetition_system")]
+mod spaced_repetition_system;
+
 #[cfg(feature = "study_material")]
 mod study_material;
 
@@ -82,16 +133,76 @@ mod voice_actor;
 
 /// The WaniKani client struct performs requests to the API.
 #[derive(Builder)]
+#[builder(build_fn(validate = "Self::validate"))]
 pub struct WKClient {
-    #[builder(setter(skip), default = "URL_BASE.parse().expect(\"Valid URL\")")]
+    /// The base URL requests are made against. Defaults to [`URL_BASE`].
+    ///
+    /// This is useful for pointing the client at a proxy or a mock server. Any
+    /// path segments in the URL are kept, so `http://localhost:8080/wk/v2`
+    /// will request subjects from `http://localhost:8080/wk/v2/subjects`.
+    #[builder(setter(custom), default = "URL_BASE.parse().expect(\"Valid URL\")")]
     base_url: Url,
     /// The V2 API token the client should use
     token: String,
     /// The reqwest [`Client`] to use for performing HTTP requests
     #[builder(default)]
     client: Client,
-    #[builder(setter(skip), default = "API_VERSION")]
-    version: &'static str,
+    /// The API revision sent in the `Wanikani-Revision` header. Defaults to
+    /// [`API_VERSION`]. Revisions are dates in the `YYYYMMDD` format.
+    #[builder(setter(into), default = "API_VERSION.into()")]
+    version: String,
+    /// How failed requests should be retried. By default, requests are not
+    /// retried.
+    #[builder(setter(strip_option), default)]
+    retry_policy: Option<RetryPolicy>,
+    /// The layers each request and response pass through. See
+    /// [`Middleware`].
+    #[builder(setter(custom), default)]
+    middleware: Vec<Arc<dyn Middleware>>,
+    #[builder(setter(skip))]
+    rate_limit: Mutex<Option<RateLimitStatus>>,
+}
+
+impl WKClientBuilder {
+    /// The base URL requests are made against. Defaults to [`URL_BASE`].
+    ///
+    /// A trailing slash is ignored, so `http://localhost:8080/wk/v2/` and
+    /// `http://localhost:8080/wk/v2` are equivalent.
+    pub fn base_url(&mut self, mut url: Url) -> &mut Self {
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.pop_if_empty();
+        }
+        self.base_url = Some(url);
+        self
+    }
+
+    /// Add a [`Middleware`] layer. Layers see requests in the order they were
+    /// added, and responses in the reverse order.
+    pub fn middleware(&mut self, layer: impl Middleware + 'static) -> &mut Self {
+        self.middleware
+            .get_or_insert_with(Vec::new)
+            .push(Arc::new(layer));
+        self
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(ref url) = self.base_url {
+            if url.cannot_be_a_base() || !matches!(url.scheme(), "http" | "https") {
+                return Err(format!("{url} is not a valid HTTP base URL"));
+            }
+        }
+        if let Some(ref version) = self.version {
+            let is_date = version.len() == 8
+                && version.bytes().all(|b| b.is_ascii_digit())
+                && NaiveDate::parse_from_str(version, "%Y%m%d").is_ok();
+            if !is_date {
+                return Err(format!(
+                    "\"{version}\" is not a valid API revision, expected YYYYMMDD"
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Debug for WKClient {
@@ -100,6 +211,8 @@ impl Debug for WKClient {
             .field("base_url", &self.base_url)
             .field("client", &self.client)
             .field("version", &self.version)
+            .field("retry_policy", &self.retry_policy)
+            .field("middleware", &self.middleware)
             .field("token", &"*snip*")
             .finish()
     }
@@ -113,13 +226,16 @@ impl WKClient {
             base_url,
             token,
             client,
-            version: API_VERSION,
+            version: API_VERSION.into(),
+            retry_policy: None,
+            middleware: Vec::new(),
+            rate_limit: Mutex::default(),
         }
     }
 
     fn add_required_headers(&self, req: RequestBuilder) -> RequestBuilder {
         req.bearer_auth(&self.token)
-            .header(REVISION_HEADER, self.version)
+            .header(REVISION_HEADER, &self.version)
     }
 
     fn rate_limit_reset(&self, headers: &HeaderMap) -> Timestamp {
@@ -143,22 +259,58 @@ impl WKClient {
         DateTime::from_timestamp_millis(reset * MILLIS_IN_SECOND).expect("Valid range")
     }
 
-    async fn handle_error(&self, response: Response) -> Error {
+    async fn handle_error(&self, method: Method, response: HttpResponse) -> Error {
         let status = response.status();
-        let headers = response.headers().to_owned();
         log::error!("Status code {status} received");
-        match response.json::<WanikaniError>().await {
-            Ok(error) => {
-                if status == StatusCode::TOO_MANY_REQUESTS {
-                    Error::RateLimit {
-                        error,
-                        reset_time: self.rate_limit_reset(&headers),
-                    }
-                } else {
-                    error.into()
+        let context = Box::new(ErrorContext::new(method, &response));
+        let body = match response.bytes().await {
+            Ok(body) => body,
+            Err(e) => return e.into(),
+        };
+        let error = serde_json::from_slice::<WanikaniError>(&body);
+        let or_status = |error: Result<WanikaniError, _>| {
+            error.unwrap_or_else(|_| WanikaniError {
+                code: status.as_u16().into(),
+                error: None,
+            })
+        };
+
+        match status {
+            StatusCode::TOO_MANY_REQUESTS => Error::RateLimit {
+                reset_time: self.rate_limit_reset(&context.headers),
+                error: or_status(error),
+                context,
+            },
+            StatusCode::UNAUTHORIZED => Error::Unauthorized {
+                error: or_status(error),
+                context,
+            },
+            StatusCode::NOT_FOUND => Error::NotFound {
+                error: or_status(error),
+                context,
+            },
+            StatusCode::UNPROCESSABLE_ENTITY => Error::Unprocessable {
+                errors: or_status(error).error.into_iter().collect(),
+                context,
+            },
+            status if status.is_server_error() => {
+                if let Err(ref e) = error {
+                    log::warn!("Server error response was not a WaniKani error: {e}");
+                }
+                Error::ServerError {
+                    body_snippet: error.is_err().then(|| body_snippet(&body)),
+                    error: error.ok(),
+                    context,
                 }
             }
-            Err(e) => e.into(),
+            _ => match error {
+                Ok(error) => Error::Http { error, context },
+                Err(source) => Error::Decode {
+                    source,
+                    body_snippet: body_snippet(&body),
+                    context,
+                },
+            },
         }
     }
 
@@ -187,6 +339,15 @@ impl WKClient {
     /// }
     /// # };
     pub async fn get_resource_by_url<T>(&self, url: &Url) -> Result<T, Error>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        Ok(self.get_resource_by_url_with_meta(url).await?.data)
+    }
+
+    /// Like [`get_resource_by_url`](Self::get_resource_by_url), but also
+    /// returns the response's [metadata](ResponseMeta).
+    pub async fn get_resource_by_url_with_meta<T>(&self, url: &Url) -> Result<Response<T>, Error>
     where
         T: for<'de> Deserialize<'de>,
     {
@@ -194,26 +355,347 @@ impl WKClient {
 
         let req = self.client.get(url.to_owned());
 
-        self.do_request(&fn_signature, req).await
+        self.do_request_with_meta(&fn_signature, req).await
     }
 
-    async fn do_request<T>(&self, caller: &str, req: RequestBuilder) -> Result<T, Error>
+    /// Fetch a resource by its URL, unless it has not changed since
+    /// `validators` were issued.
+    ///
+    /// The `url` of a previously fetched resource or collection can be used to
+    /// check it for changes. See [conditional requests](crate#conditional-requests).
+    ///
+    /// ### Example
+    /// ```rust
+    /// # use wanikani_api::{Collection, client::{Fetched, Validators, WKClient}};
+    /// # type VoiceActor = serde_json::Value;
+    /// # let client = WKClient::new("MY_TOKEN".to_string(), reqwest::Client::default());
+    /// # async move {
+    /// let url = "https://api.wanikani.com/v2/voice_actors".parse().unwrap();
+    /// # let validators = Validators::default();
+    /// // let validators: Validators = ...;
+    ///
+    /// match client
+    ///     .get_resource_by_url_if_modified::<Collection<VoiceActor>>(&url, &validators)
+    ///     .await
+    ///     .unwrap()
+    /// {
+    ///     Fetched::Modified(collection, validators) => {
+    ///         // Store the new data and validators
+    ///     }
+    ///     Fetched::NotModified => {
+    ///         // Keep using the stored data
+    ///     }
+    /// }
+    /// # };
+    /// ```
+    pub async fn get_resource_by_url_if_modified<T>(
+        &self,
+        url: &Url,
+        validators: &Validators,
+    ) -> Result<Fetched<T>, Error>
     where
         T: for<'de> Deserialize<'de>,
     {
-        let req = self.add_required_headers(req);
+        let fn_signature = format!("get_resource_by_url_if_modified<{}>", type_name::<T>());
+
+        let req = self.client.get(url.to_owned());
+
+        self.do_conditional_request(&fn_signature, req, validators)
+            .await
+    }
+
+    /// Stream every resource of the collection at `url`, fetching the
+    /// following pages as they are needed.
+    ///
+    /// The stream ends after the last page has been consumed, or after the
+    /// first error is yielded.
+    ///
+    /// ### Example
+    /// ```rust
+    /// # use wanikani_api::{Error, Resource, client::WKClient};
+    /// # use futures_util::TryStreamExt;
+    /// # type VoiceActor = serde_json::Value;
+    /// # let client = WKClient::new("MY_TOKEN".to_string(), reqwest::Client::default());
+    /// # async move {
+    /// let url = "https://api.wanikani.com/v2/voice_actors".parse().unwrap();
+    ///
+    /// let voice_actors: Vec<Resource<VoiceActor>> = client
+    ///     .stream_resources_by_url(&url)
+    ///     .try_collect()
+    ///     .await
+    ///     .unwrap();
+    /// # };
+    /// ```
+    pub fn stream_resources_by_url<T>(
+        &self,
+        url: &Url,
+    ) -> impl Stream<Item = Result<Resource<T>, Error>> + '_
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let caller = format!("stream_resources_by_url<{}>", type_name::<T>());
+
+        self.stream_resources(caller, url.to_owned())
+    }
+
+    /// Fetch the remaining pages of a collection, returning every resource in
+    /// it.
+    ///
+    /// `collection` is the first page, as returned by one of the `get_*`
+    /// collection methods. If the number of resources received does not
+    /// match the collection's `total_count`, an [`Error::IncompleteCollection`]
+    /// is returned. This can happen if the collection is modified while it is
+    /// being fetched.
+    ///
+    /// ### Example
+    /// ```rust
+    /// # use wanikani_api::client::{IdFilter, WKClient};
+    /// # let client = WKClient::new("MY_TOKEN".to_string(), reqwest::Client::default());
+    /// # async move {
+    /// let first_page = client
+    ///     .get_voice_actors(&IdFilter::default())
+    ///     .await
+    ///     .unwrap();
+    /// let voice_actors = client.collect_all(first_page).await.unwrap();
+    /// # };
+    /// ```
+    pub async fn collect_all<T>(&self, collection: Collection<T>) -> Result<Vec<Resource<T>>, Error>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let expected = collection.total_count;
+        let mut resources = collection.data;
+
+        if let Some(next_url) = collection.pages.next_url {
+            let caller = format!("collect_all<{}>", type_name::<T>());
+            let mut remaining: Vec<_> = self
+                .stream_resources(caller, next_url)
+                .try_collect()
+                .await?;
+            resources.append(&mut remaining);
+        }
+
+        let received = resources.len() as u64;
+        if received != expected {
+            return Err(Error::IncompleteCollection { expected, received });
+        }
+
+        Ok(resources)
+    }
+
+    fn stream_resources<T>(
+        &self,
+        caller: String,
+        url: Url,
+    ) -> impl Stream<Item = Result<Resource<T>, Error>> + '_
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        stream::try_unfold(Some(url), move |next_url| {
+            let caller = caller.clone();
+            async move {
+                let Some(url) = next_url else {
+                    return Ok::<_, Error>(None);
+                };
+
+                let req = self.client.get(url);
+                let span = RequestSpan::new(&caller);
+                let page: Collection<T> = span
+                    .instrument(self.request_with_meta(&caller, req))
+                    .await?
+                    .data;
+
+                span.record_page(&caller, page.data.len(), page.total_count);
+
+                Ok(Some((page.data, page.pages.next_url)))
+            }
+        })
+        .map_ok(|data| stream::iter(data.into_iter().map(Ok)))
+        .try_flatten()
+    }
+
+    async fn do_request_with_meta<T>(
+        &self,
+        caller: &str,
+        req: RequestBuilder,
+    ) -> Result<Response<T>, Error>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        RequestSpan::new(caller)
+            .instrument(self.request_with_meta(caller, req))
+            .await
+    }
+
+    async fn request_with_meta<T>(
+        &self,
+        caller: &str,
+        req: RequestBuilder,
+    ) -> Result<Response<T>, Error>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let (method, resp) = self.execute(caller, req, false).await?;
+        let meta = ResponseMeta::from_headers(resp.headers());
+
+        Ok(Response {
+            data: decode(method, resp).await?,
+            meta,
+        })
+    }
+
+    async fn do_conditional_request<T>(
+        &self,
+        caller: &str,
+        req: RequestBuilder,
+        validators: &Validators,
+    ) -> Result<Fetched<T>, Error>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        RequestSpan::new(caller)
+            .instrument(async {
+                let (method, resp) = self.execute(caller, validators.apply(req), true).await?;
+
+                if resp.status() == StatusCode::NOT_MODIFIED {
+                    log::debug!("{caller} not modified");
+                    return Ok(Fetched::NotModified);
+                }
+
+                let validators = Validators::from_headers(resp.headers());
+
+                Ok(Fetched::Modified(decode(method, resp).await?, validators))
+            })
+            .await
+    }
 
-        log::debug!("{caller} request: {req:?}");
+    /// Send `req` to the API, retrying according to the retry policy. A
+    /// `304 Not Modified` response is only accepted if `conditional` is set.
+    async fn execute(
+        &self,
+        caller: &str,
+        req: RequestBuilder,
+        conditional: bool,
+    ) -> Result<(Method, HttpResponse), Error> {
+        self.send_with_retry(caller, self.add_required_headers(req), conditional)
+            .await
+    }
+
+    /// Send `req` as it is, without the API's authorization and revision
+    /// headers, retrying according to the retry policy.
+    async fn send_with_retry(
+        &self,
+        caller: &str,
+        req: RequestBuilder,
+        conditional: bool,
+    ) -> Result<(Method, HttpResponse), Error> {
+        let Some(ref policy) = self.retry_policy else {
+            return self.send_request(caller, req, conditional).await;
+        };
+
+        let method = req
+            .try_clone()
+            .and_then(|req| req.build().ok())
+            .map_or(Method::POST, |req| req.method().clone());
+
+        let mut attempt = 1;
+        loop {
+            if let Some(delay) = self
+                .last_rate_limit()
+                .and_then(|s| policy.throttle_delay(&s))
+            {
+                log::info!("{caller} throttled for {delay:?} to stay within rate limit");
+                tokio::time::sleep(delay).await;
+            }
+
+            let Some(attempt_req) = req.try_clone() else {
+                log::warn!("{caller} request cannot be retried, sending once");
+                return self.send_request(caller, req, conditional).await;
+            };
+
+            match self.send_request(caller, attempt_req, conditional).await {
+                Ok(resp) => return Ok(resp),
+                Err(error) => match policy.retry_delay(&method, &error, attempt) {
+                    Some(delay) => {
+                        log::warn!(
+                            "{caller} attempt {attempt} failed, retrying in {delay:?}: {error}"
+                        );
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                    }
+                    None => return Err(error),
+                },
+            }
+        }
+    }
+
+    async fn send_request(
+        &self,
+        caller: &str,
+        req: RequestBuilder,
+        conditional: bool,
+    ) -> Result<(Method, HttpResponse), Error> {
+        let req = self
+            .middleware
+            .iter()
+            .fold(req, |req, layer| layer.on_request(caller, req));
+        let (client, req) = req.build_split();
+        let req = req?;
+        let method = req.method().clone();
+
+        let span = RequestSpan::current();
+        span.record_request(caller, &req);
 
-        let resp = req.send().await?;
+        let start = Instant::now();
+        let mut resp = client.execute(req).await.map_err(|error| {
+            for layer in self.middleware.iter().rev() {
+                layer.on_error(caller, &error);
+            }
+            error
+        })?;
+        for layer in self.middleware.iter().rev() {
+            resp = layer.on_response(caller, resp).await?;
+        }
 
-        log::debug!("{caller} response: {resp:?}");
+        span.record_response(caller, &resp, start.elapsed());
+
+        if let Some(status) = RateLimitStatus::from_headers(resp.headers()) {
+            *self.rate_limit.lock().expect("Lock not poisoned") = Some(status);
+        }
 
-        match resp.status() {
-            StatusCode::OK => Ok(resp.json().await?),
-            _ => Err(self.handle_error(resp).await),
+        let status = resp.status();
+        if status.is_success() || (conditional && status == StatusCode::NOT_MODIFIED) {
+            Ok((method, resp))
+        } else {
+            Err(self.handle_error(method, resp).await)
         }
     }
+
+    fn last_rate_limit(&self) -> Option<RateLimitStatus> {
+        *self.rate_limit.lock().expect("Lock not poisoned")
+    }
+}
+
+/// Deserialize the body of a successful response.
+async fn decode<T>(method: Method, resp: HttpResponse) -> Result<T, Error>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    let context = ErrorContext::new(method, &resp);
+    let body = resp.bytes().await?;
+
+    serde_json::from_slice(&body).map_err(|source| Error::Decode {
+        source,
+        body_snippet: body_snippet(&body),
+        context: Box::new(context),
+    })
+}
+
+fn body_snippet(body: &[u8]) -> String {
+    String::from_utf8_lossy(body)
+        .chars()
+        .take(BODY_SNIPPET_LENGTH)
+        .collect()
 }
 
 #[cfg(test)]
@@ -235,11 +717,42 @@ fn create_client() -> WKClient {
     )
 }
 
+/// A client replaying the cassette `name` from `test_files/cassettes`, or
+/// recording it if `WK_RECORD` is set. A cassette that has not been recorded
+/// yet is recorded if `API_KEY` is set, and `None` is returned otherwise.
+#[cfg(all(test, feature = "model"))]
+async fn create_cassette_client(name: &str) -> Option<crate::test_util::CassetteClient> {
+    use crate::test_util::{CassetteClient, RECORD_VAR, TOKEN_VAR};
+
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("test_files/cassettes")
+        .join(format!("{name}.json"));
+    if std::env::var_os(RECORD_VAR).is_none() && !path.exists() {
+        let Some(token) = std::env::var(TOKEN_VAR).ok().filter(|t| !t.is_empty()) else {
+            log::warn!("Skipping, {name} has not been recorded and {TOKEN_VAR} is not set");
+            return None;
+        };
+        let base_url = URL_BASE.parse().expect("Valid URL");
+        return Some(CassetteClient::record(path, base_url, token));
+    }
+
+    Some(CassetteClient::from_env(path).await.expect("Cassette"))
+}
+
+#[cfg(test)]
+fn create_mock_client(server: &wiremock::MockServer) -> WKClient {
+    WKClientBuilder::default()
+        .token("MOCK_TOKEN".into())
+        .base_url(server.uri().parse().expect("Mock server URL"))
+        .build()
+        .expect("Mock client")
+}
+
 #[cfg(test)]
 mod tests {
     use reqwest::ClientBuilder;
 
-    use crate::{Collection, URL_BASE};
+    use crate::{Collection, API_VERSION, URL_BASE};
 
     use super::{create_client, init_tests, WKClientBuilder};
 
@@ -265,7 +778,10 @@ mod tests {
             }
         };
 
-        let Error::RateLimit { error, reset_time } = error else {
+        let Error::RateLimit {
+            error, reset_time, ..
+        } = error
+        else {
             panic!("Didn't get rate-limited");
         };
 
@@ -295,6 +811,213 @@ mod tests {
             .is_ok())
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn test_rate_limit_retry() {
+        use super::RetryPolicy;
+
+        init_tests();
+
+        let client = WKClientBuilder::default()
+            .token(std::env::var("API_KEY").expect("API_KEY provided"))
+            .retry_policy(RetryPolicy::default())
+            .build()
+            .expect("Client");
+
+        let url = format!("{}/subjects?levels=5000", URL_BASE)
+            .parse()
+            .expect("URL");
+
+        for _ in 0..120 {
+            client
+                .get_resource_by_url::<Collection<()>>(&url)
+                .await
+                .expect("Requests are throttled and retried");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_taxonomy() {
+        use wiremock::{
+            matchers::{method, path},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        use super::create_mock_client;
+        use crate::Error;
+
+        init_tests();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/subjects/1"))
+            .respond_with(
+                ResponseTemplate::new(404)
+                    .set_body_raw(r#"{"error":"Not found","code":404}"#, "application/json"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/summary"))
+            .respond_with(
+                ResponseTemplate::new(502)
+                    .insert_header("Server", "proxy")
+                    .set_body_raw("<html>Bad Gateway</html>", "text/html"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/user"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw("{\"object\":", "application/json"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/reviews"))
+            .respond_with(ResponseTemplate::new(304))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/resets"))
+            .respond_with(
+                ResponseTemplate::new(403)
+                    .set_body_raw(r#"{"error":"Forbidden","code":403}"#, "application/json"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/level_progressions"))
+            .respond_with(
+                ResponseTemplate::new(429)
+                    .insert_header("RateLimit-Reset", "1700000000")
+                    .set_body_raw(
+                        r#"{"error":"Rate limit exceeded","code":429}"#,
+                        "application/json",
+                    ),
+            )
+            .mount(&server)
+            .await;
+
+        let client = create_mock_client(&server);
+        let url = |p: &str| format!("{}{p}", server.uri()).parse().expect("URL");
+
+        let error = client
+            .get_resource_by_url::<serde_json::Value>(&url("/subjects/1"))
+            .await
+            .expect_err("404 is an error");
+        assert!(matches!(error, Error::NotFound { ref error, .. } if error.code == 404));
+        assert_eq!(error.status(), Some(reqwest::StatusCode::NOT_FOUND));
+        assert!(!error.is_retryable());
+
+        let error = client
+            .get_resource_by_url::<serde_json::Value>(&url("/summary"))
+            .await
+            .expect_err("502 is an error");
+        assert!(error.is_retryable());
+        let Error::ServerError {
+            error,
+            body_snippet,
+            context,
+        } = error
+        else {
+            panic!("Expected a server error");
+        };
+        assert!(error.is_none());
+        assert_eq!(body_snippet.as_deref(), Some("<html>Bad Gateway</html>"));
+        assert_eq!(context.status, 502);
+        assert_eq!(context.headers["Server"], "proxy");
+
+        let error = client
+            .get_resource_by_url::<serde_json::Value>(&url("/user"))
+            .await
+            .expect_err("Truncated body is an error");
+        let Error::Decode {
+            body_snippet,
+            context,
+            ..
+        } = error
+        else {
+            panic!("Expected a decode error");
+        };
+        assert_eq!(body_snippet, "{\"object\":");
+        assert_eq!(context.method, "GET");
+        assert_eq!(context.url.path(), "/user");
+
+        let error = client
+            .get_resource_by_url::<serde_json::Value>(&url("/reviews"))
+            .await
+            .expect_err("304 to an unconditional request is an error");
+        assert_eq!(error.status(), Some(reqwest::StatusCode::NOT_MODIFIED));
+
+        let error = client
+            .get_resource_by_url::<serde_json::Value>(&url("/resets"))
+            .await
+            .expect_err("403 is an error");
+        assert_eq!(error.status(), Some(reqwest::StatusCode::FORBIDDEN));
+        let Error::Http { error, context } = error else {
+            panic!("Expected an HTTP error");
+        };
+        assert_eq!(error.code, 403);
+        assert_eq!(context.method, "GET");
+        assert_eq!(context.url.path(), "/resets");
+
+        let error = client
+            .get_resource_by_url::<serde_json::Value>(&url("/level_progressions"))
+            .await
+            .expect_err("429 is an error");
+        assert_eq!(error.status(), Some(reqwest::StatusCode::TOO_MANY_REQUESTS));
+        let context = error.context().expect("Context");
+        assert_eq!(context.url.path(), "/level_progressions");
+        assert_eq!(context.headers["RateLimit-Reset"], "1700000000");
+    }
+
+    #[tokio::test]
+    async fn test_response_meta() {
+        use wiremock::{
+            matchers::{method, path},
+            Mock, MockServer, ResponseTemplate,
+        };
+
+        use super::create_mock_client;
+
+        init_tests();
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/user"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Ratelimit-Limit", "60")
+                    .insert_header("Ratelimit-Remaining", "42")
+                    .insert_header("Ratelimit-Reset", "1697544060")
+                    .insert_header("ETag", "W/\"user\"")
+                    .set_body_raw(r#"{"object":"report"}"#, "application/json"),
+            )
+            .mount(&server)
+            .await;
+
+        let client = create_mock_client(&server);
+        let url = format!("{}/user", server.uri()).parse().expect("URL");
+
+        let response = client
+            .get_resource_by_url_with_meta::<serde_json::Value>(&url)
+            .await
+            .expect("Success");
+        assert_eq!(response.data["object"], "report");
+        assert_eq!(response.meta.rate_limit, Some(60));
+        assert_eq!(response.meta.rate_limit_remaining, Some(42));
+        assert_eq!(
+            response
+                .meta
+                .rate_limit_reset
+                .map(|reset| reset.timestamp()),
+            Some(1_697_544_060)
+        );
+        assert_eq!(response.meta.validators.etag.as_deref(), Some("W/\"user\""));
+        assert!(response.meta.date.is_some());
+    }
+
     #[test]
     fn test_builder() {
         WKClientBuilder::default()
@@ -315,4 +1038,61 @@ mod tests {
             .build()
             .expect("Client with custom client");
     }
+
+    #[test]
+    fn test_builder_base_url() {
+        let client = WKClientBuilder::default()
+            .token("SomeToken".into())
+            .build()
+            .expect("Client with default URL");
+        assert_eq!(client.base_url.as_str(), URL_BASE);
+
+        let client = WKClientBuilder::default()
+            .token("SomeToken".into())
+            .base_url("http://localhost:8080/wk/v2/".parse().expect("URL"))
+            .build()
+            .expect("Client with custom URL");
+
+        let mut url = client.base_url.clone();
+        url.path_segments_mut()
+            .expect("Valid URL")
+            .push("subjects")
+            .push("1");
+        assert_eq!(url.as_str(), "http://localhost:8080/wk/v2/subjects/1");
+
+        WKClientBuilder::default()
+            .token("SomeToken".into())
+            .base_url("mailto:someone@example.com".parse().expect("URL"))
+            .build()
+            .expect_err("Not an HTTP URL");
+        WKClientBuilder::default()
+            .token("SomeToken".into())
+            .base_url("ftp://example.com/v2".parse().expect("URL"))
+            .build()
+            .expect_err("Not an HTTP URL");
+    }
+
+    #[test]
+    fn test_builder_version() {
+        let client = WKClientBuilder::default()
+            .token("SomeToken".into())
+            .build()
+            .expect("Client with default version");
+        assert_eq!(client.version, API_VERSION);
+
+        let client = WKClientBuilder::default()
+            .token("SomeToken".into())
+            .version("20230101")
+            .build()
+            .expect("Client with custom version");
+        assert_eq!(client.version, "20230101");
+
+        for version in ["", "2017071", "2017-07-10", "20171310", "latest"] {
+            WKClientBuilder::default()
+                .token("SomeToken".into())
+                .version(version)
+                .build()
+                .expect_err("Invalid version");
+        }
+    }
 }
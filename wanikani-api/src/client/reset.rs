@@ -1,6 +1,8 @@
+use futures_util::Stream;
+
 use crate::{reset::Reset, Collection, Error, Id, Resource};
 
-use super::{Filter, IdFilter, WKClient};
+use super::{Fetched, Filter, IdFilter, Response, Validators, WKClient};
 
 const RESET_PATH: &str = "resets";
 
@@ -8,6 +10,32 @@ impl WKClient {
     /// Returns a collection of all resets, ordered by ascending
     /// `created_at`, 500 at a time.
     pub async fn get_resets(&self, filters: &IdFilter) -> Result<Collection<Reset>, Error> {
+        Ok(self.get_resets_with_meta(filters).await?.data)
+    }
+
+    /// Like [`get_resets`](Self::get_resets), but also returns the response's
+    /// [metadata](super::ResponseMeta).
+    pub async fn get_resets_with_meta(
+        &self,
+        filters: &IdFilter,
+    ) -> Result<Response<Collection<Reset>>, Error> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut().expect("Valid URL").push(RESET_PATH);
+
+        filters.apply_filters(&mut url);
+
+        let req = self.client.get(url);
+
+        self.do_request_with_meta("get_resets", req).await
+    }
+
+    /// Returns the collection of resets matching `filters`, unless it has not
+    /// changed since `validators` were issued.
+    pub async fn get_resets_if_modified(
+        &self,
+        filters: &IdFilter,
+        validators: &Validators,
+    ) -> Result<Fetched<Collection<Reset>>, Error> {
         let mut url = self.base_url.clone();
         url.path_segments_mut().expect("Valid URL").push(RESET_PATH);
 
@@ -15,11 +43,35 @@ impl WKClient {
 
         let req = self.client.get(url);
 
-        self.do_request("get_resets", req).await
+        self.do_conditional_request("get_resets_if_modified", req, validators)
+            .await
+    }
+
+    /// Streams every reset matching `filters`, fetching each page of the
+    /// collection as it is needed.
+    pub fn stream_resets(
+        &self,
+        filters: &IdFilter,
+    ) -> impl Stream<Item = Result<Resource<Reset>, Error>> + '_ {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut().expect("Valid URL").push(RESET_PATH);
+
+        filters.apply_filters(&mut url);
+
+        self.stream_resources("stream_resets".into(), url)
     }
 
     /// Retrieves a specific reset by its `id`.
     pub async fn get_specific_reset(&self, id: Id) -> Result<Resource<Reset>, Error> {
+        Ok(self.get_specific_reset_with_meta(id).await?.data)
+    }
+
+    /// Like [`get_specific_reset`](Self::get_specific_reset), but also returns
+    /// the response's [metadata](super::ResponseMeta).
+    pub async fn get_specific_reset_with_meta(
+        &self,
+        id: Id,
+    ) -> Result<Response<Resource<Reset>>, Error> {
         let mut url = self.base_url.clone();
         url.path_segments_mut()
             .expect("Valid URL")
@@ -28,19 +80,21 @@ impl WKClient {
 
         let req = self.client.get(url);
 
-        self.do_request("get_specific_reset", req).await
+        self.do_request_with_meta("get_specific_reset", req).await
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::client::{create_client, init_tests};
+    use crate::client::{create_cassette_client, init_tests};
 
     #[tokio::test]
     async fn test_get_resets() {
         init_tests();
 
-        let client = create_client();
+        let Some(client) = create_cassette_client("reset/get_resets").await else {
+            return;
+        };
 
         assert!(client.get_resets(&Default::default()).await.is_ok());
     }
@@ -50,14 +104,16 @@ mod tests {
     async fn test_get_specific_reset() {
         init_tests();
 
-        let client = create_client();
+        let Some(client) = create_cassette_client("reset/get_specific_reset").await else {
+            return;
+        };
 
         let resets = client
             .get_resets(&Default::default())
             .await
             .expect("Get all resets");
 
-        if let Some(reset) = resets.data.get(0) {
+        if let Some(reset) = resets.data.first() {
             assert!(client.get_specific_reset(reset.id).await.is_ok());
         } else {
             log::warn!("No resets detected, this test should not be considered reliable");
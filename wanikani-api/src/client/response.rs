@@ -0,0 +1,119 @@
+use chrono::{DateTime, Duration};
+use reqwest::header::{HeaderMap, DATE};
+
+use crate::Timestamp;
+
+use super::{
+    retry::{REMAINING_HEADER, RESET_HEADER},
+    Validators,
+};
+
+const LIMIT_HEADER: &str = "Ratelimit-Limit";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Metadata parsed from the headers of a response. Headers that are missing
+/// or invalid are `None`.
+pub struct ResponseMeta {
+    /// The server's clock when the response was sent, from the `Date` header.
+    pub date: Option<Timestamp>,
+    /// The number of requests allowed in each rate limit window, from the
+    /// `Ratelimit-Limit` header.
+    pub rate_limit: Option<u32>,
+    /// The number of requests remaining in the current rate limit window, from
+    /// the `Ratelimit-Remaining` header.
+    pub rate_limit_remaining: Option<u32>,
+    /// When the current rate limit window ends, from the `Ratelimit-Reset`
+    /// header.
+    pub rate_limit_reset: Option<Timestamp>,
+    /// The caching validators of the response, from the `ETag` and
+    /// `Last-Modified` headers.
+    pub validators: Validators,
+}
+
+impl ResponseMeta {
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name| headers.get(name)?.to_str().ok();
+        let number = |name| header(name)?.parse::<i64>().ok();
+
+        Self {
+            date: header(DATE.as_str())
+                .and_then(|date| DateTime::parse_from_rfc2822(date).ok())
+                .map(|date| date.to_utc()),
+            rate_limit: number(LIMIT_HEADER).and_then(|n| n.try_into().ok()),
+            rate_limit_remaining: number(REMAINING_HEADER).and_then(|n| n.try_into().ok()),
+            rate_limit_reset: number(RESET_HEADER)
+                .and_then(|reset| Timestamp::from_timestamp(reset, 0)),
+            validators: Validators::from_headers(headers),
+        }
+    }
+
+    /// How far the server's clock was ahead of `now` when the response was
+    /// sent. Negative if the server's clock is behind.
+    ///
+    /// Pass the local time the response was received, to correct local
+    /// timestamps before comparing them with ones from the API, such as
+    /// `available_at`.
+    pub fn clock_offset(&self, now: Timestamp) -> Option<Duration> {
+        Some(self.date? - now)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A response body, along with metadata from the response's headers.
+/// Returned by the `_with_meta` methods of [`WKClient`](super::WKClient).
+pub struct Response<T> {
+    /// The deserialized body.
+    pub data: T,
+    /// The response's metadata.
+    pub meta: ResponseMeta,
+}
+
+impl<T> Response<T> {
+    /// Discard the metadata, returning the body.
+    pub fn into_data(self) -> T {
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    use super::ResponseMeta;
+
+    #[test]
+    fn test_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "Date",
+            HeaderValue::from_static("Tue, 17 Oct 2023 12:00:05 GMT"),
+        );
+        headers.insert("Ratelimit-Limit", HeaderValue::from_static("60"));
+        headers.insert("Ratelimit-Remaining", HeaderValue::from_static("59"));
+        headers.insert("Ratelimit-Reset", HeaderValue::from_static("1697544060"));
+        headers.insert("ETag", HeaderValue::from_static("W/\"abc\""));
+
+        let meta = ResponseMeta::from_headers(&headers);
+
+        let date = Utc.with_ymd_and_hms(2023, 10, 17, 12, 0, 5).unwrap();
+        assert_eq!(meta.date, Some(date));
+        assert_eq!(meta.rate_limit, Some(60));
+        assert_eq!(meta.rate_limit_remaining, Some(59));
+        assert_eq!(
+            meta.rate_limit_reset,
+            Some(Utc.with_ymd_and_hms(2023, 10, 17, 12, 1, 0).unwrap())
+        );
+        assert_eq!(meta.validators.etag.as_deref(), Some("W/\"abc\""));
+        assert!(meta.validators.last_modified.is_none());
+        assert_eq!(
+            meta.clock_offset(date - Duration::seconds(2)),
+            Some(Duration::seconds(2))
+        );
+
+        assert_eq!(
+            ResponseMeta::from_headers(&HeaderMap::new()),
+            ResponseMeta::default()
+        );
+    }
+}
@@ -0,0 +1,268 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use reqwest::{header::HeaderMap, Method};
+
+use crate::{Error, Timestamp};
+
+pub(super) const REMAINING_HEADER: &str = "Ratelimit-Remaining";
+pub(super) const RESET_HEADER: &str = "Ratelimit-Reset";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Controls how the client retries failed requests.
+///
+/// When a request is rate limited, the client sleeps until the rate limit
+/// resets and tries again. Server errors (`5xx`) and connection failures are
+/// retried with an exponential backoff, but only for idempotent requests such
+/// as `GET` and `PUT`. A `POST`, such as creating a review, may already have
+/// been applied when the error occurred, so it is not sent again. Any other
+/// error, i.e. any error for which [`Error::is_retryable`] is `false`, is
+/// returned immediately.
+///
+/// The client also tracks the `Ratelimit-Remaining` header of every response.
+/// Once fewer than `throttle_threshold` requests remain in the current window,
+/// the client spreads its remaining requests out until the window resets
+/// instead of running into the limit.
+///
+/// ### Example
+/// ```rust
+/// # use std::time::Duration;
+/// # use wanikani_api::client::{RetryPolicy, WKClientBuilder};
+/// let client = WKClientBuilder::default()
+///     .token("MY_TOKEN".into())
+///     .retry_policy(RetryPolicy {
+///         max_attempts: 5,
+///         ..RetryPolicy::default()
+///     })
+///     .build()
+///     .unwrap();
+/// ```
+pub struct RetryPolicy {
+    /// The maximum number of times a request is attempted, including the first
+    /// attempt.
+    pub max_attempts: u32,
+    /// How long to wait before the first retry of a server or connection
+    /// error. This doubles for each subsequent retry.
+    pub initial_backoff: Duration,
+    /// The longest the client will wait between retries of a server or
+    /// connection error.
+    pub max_backoff: Duration,
+    /// Extra time to wait after the rate limit reset time, to account for
+    /// clock differences between the client and the API server.
+    pub rate_limit_margin: Duration,
+    /// When `Ratelimit-Remaining` falls below this value, requests are spaced
+    /// out evenly until the rate limit resets. Set to `0` to disable.
+    pub throttle_threshold: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            rate_limit_margin: Duration::from_secs(1),
+            throttle_threshold: 5,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// How long to wait before retrying a `method` request that failed with
+    /// `error`, where `attempt` is the number of attempts made so far. Returns
+    /// `None` if the request should not be retried.
+    pub(crate) fn retry_delay(
+        &self,
+        method: &Method,
+        error: &Error,
+        attempt: u32,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+
+        match error {
+            Error::RateLimit { reset_time, .. } => {
+                Some(until(*reset_time).unwrap_or_default() + self.rate_limit_margin)
+            }
+            error if error.is_retryable() && is_idempotent(method) => Some(self.backoff(attempt)),
+            _ => None,
+        }
+    }
+
+    /// How long to wait before sending a request, given the last known rate
+    /// limit status.
+    pub(crate) fn throttle_delay(&self, status: &RateLimitStatus) -> Option<Duration> {
+        if status.remaining >= self.throttle_threshold {
+            return None;
+        }
+
+        let window = until(status.reset_time)?;
+
+        if status.remaining == 0 {
+            Some(window + self.rate_limit_margin)
+        } else {
+            Some(window / (status.remaining + 1))
+        }
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The rate limit state reported by the most recent response.
+pub(crate) struct RateLimitStatus {
+    /// The number of requests remaining in the current window.
+    pub remaining: u32,
+    /// When the current window ends.
+    pub reset_time: Timestamp,
+}
+
+impl RateLimitStatus {
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name| headers.get(name)?.to_str().ok()?.parse::<i64>().ok();
+
+        let remaining = header(REMAINING_HEADER)?.try_into().ok()?;
+        let reset_time = Timestamp::from_timestamp(header(RESET_HEADER)?, 0)?;
+
+        Some(Self {
+            remaining,
+            reset_time,
+        })
+    }
+}
+
+/// Whether sending a request with `method` twice has the same effect as
+/// sending it once.
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
+    )
+}
+
+fn until(time: Timestamp) -> Option<Duration> {
+    (time - Utc::now()).to_std().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::Utc;
+    use reqwest::{
+        header::{HeaderMap, HeaderValue},
+        Method,
+    };
+
+    use crate::{Error, ErrorContext, WanikaniError, URL_BASE};
+
+    use super::{RateLimitStatus, RetryPolicy};
+
+    #[test]
+    fn test_retry_delay() {
+        let policy = RetryPolicy {
+            max_attempts: 4,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(3),
+            ..RetryPolicy::default()
+        };
+
+        let server_error = Error::WaniKaniError(WanikaniError {
+            code: 503,
+            error: None,
+        });
+        assert_eq!(
+            policy.retry_delay(&Method::GET, &server_error, 1),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(
+            policy.retry_delay(&Method::GET, &server_error, 2),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            policy.retry_delay(&Method::GET, &server_error, 3),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(policy.retry_delay(&Method::GET, &server_error, 4), None);
+
+        let not_found = Error::WaniKaniError(WanikaniError {
+            code: 404,
+            error: None,
+        });
+        assert_eq!(policy.retry_delay(&Method::GET, &not_found, 1), None);
+
+        let rate_limit = Error::RateLimit {
+            error: WanikaniError {
+                code: 429,
+                error: None,
+            },
+            reset_time: Utc::now() + chrono::Duration::seconds(10),
+            context: Box::new(ErrorContext {
+                status: reqwest::StatusCode::TOO_MANY_REQUESTS,
+                method: Method::GET,
+                url: URL_BASE.parse().expect("URL"),
+                headers: HeaderMap::new(),
+            }),
+        };
+        let delay = policy
+            .retry_delay(&Method::GET, &rate_limit, 1)
+            .expect("Retry");
+        assert!(delay > Duration::from_secs(10));
+        assert!(delay <= Duration::from_secs(11));
+
+        assert_eq!(policy.retry_delay(&Method::POST, &server_error, 1), None);
+        assert!(policy.retry_delay(&Method::POST, &rate_limit, 1).is_some());
+    }
+
+    #[test]
+    fn test_throttle_delay() {
+        let policy = RetryPolicy::default();
+        let reset_time = Utc::now() + chrono::Duration::seconds(30);
+
+        let plenty = RateLimitStatus {
+            remaining: 50,
+            reset_time,
+        };
+        assert_eq!(policy.throttle_delay(&plenty), None);
+
+        let few = RateLimitStatus {
+            remaining: 2,
+            reset_time,
+        };
+        let delay = policy.throttle_delay(&few).expect("Throttled");
+        assert!(delay <= Duration::from_secs(10));
+        assert!(delay > Duration::from_secs(9));
+
+        let none = RateLimitStatus {
+            remaining: 0,
+            reset_time,
+        };
+        let delay = policy.throttle_delay(&none).expect("Throttled");
+        assert!(delay > Duration::from_secs(30));
+
+        let expired = RateLimitStatus {
+            remaining: 0,
+            reset_time: Utc::now() - chrono::Duration::seconds(1),
+        };
+        assert_eq!(policy.throttle_delay(&expired), None);
+    }
+
+    #[test]
+    fn test_rate_limit_status_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert!(RateLimitStatus::from_headers(&headers).is_none());
+
+        headers.insert("Ratelimit-Remaining", HeaderValue::from_static("42"));
+        headers.insert("Ratelimit-Reset", HeaderValue::from_static("1700000000"));
+
+        let status = RateLimitStatus::from_headers(&headers).expect("Status");
+        assert_eq!(status.remaining, 42);
+        assert_eq!(status.reset_time.timestamp(), 1700000000);
+    }
+}
@@ -0,0 +1,264 @@
+use futures_util::Stream;
+use url::Url;
+
+use crate::{
+    review::{CreateReview, CreatedReview, Review},
+    Collection, Error, Id, Resource, Timestamp,
+};
+
+use super::{Fetched, Filter, Response, Validators, WKClient};
+
+const REVIEW_PATH: &str = "reviews";
+
+impl WKClient {
+    /// Returns a collection of all reviews, ordered by ascending `created_at`,
+    /// 1000 at a time.
+    pub async fn get_reviews(&self, filters: &ReviewFilter) -> Result<Collection<Review>, Error> {
+        Ok(self.get_reviews_with_meta(filters).await?.data)
+    }
+
+    /// Like [`get_reviews`](Self::get_reviews), but also returns the response's
+    /// [metadata](super::ResponseMeta).
+    pub async fn get_reviews_with_meta(
+        &self,
+        filters: &ReviewFilter,
+    ) -> Result<Response<Collection<Review>>, Error> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("Valid URL")
+            .push(REVIEW_PATH);
+
+        filters.apply_filters(&mut url);
+
+        let req = self.client.get(url);
+
+        self.do_request_with_meta("get_reviews", req).await
+    }
+
+    /// Returns the collection of reviews matching `filters`, unless it has not
+    /// changed since `validators` were issued.
+    pub async fn get_reviews_if_modified(
+        &self,
+        filters: &ReviewFilter,
+        validators: &Validators,
+    ) -> Result<Fetched<Collection<Review>>, Error> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("Valid URL")
+            .push(REVIEW_PATH);
+
+        filters.apply_filters(&mut url);
+
+        let req = self.client.get(url);
+
+        self.do_conditional_request("get_reviews_if_modified", req, validators)
+            .await
+    }
+
+    /// Streams every review matching `filters`, fetching each page of the
+    /// collection as it is needed.
+    pub fn stream_reviews(
+        &self,
+        filters: &ReviewFilter,
+    ) -> impl Stream<Item = Result<Resource<Review>, Error>> + '_ {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("Valid URL")
+            .push(REVIEW_PATH);
+
+        filters.apply_filters(&mut url);
+
+        self.stream_resources("stream_reviews".into(), url)
+    }
+
+    /// Retrieves a specific review by its `id`.
+    pub async fn get_specific_review(&self, id: Id) -> Result<Resource<Review>, Error> {
+        Ok(self.get_specific_review_with_meta(id).await?.data)
+    }
+
+    /// Like [`get_specific_review`](Self::get_specific_review), but also
+    /// returns the response's [metadata](super::ResponseMeta).
+    pub async fn get_specific_review_with_meta(
+        &self,
+        id: Id,
+    ) -> Result<Response<Resource<Review>>, Error> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("Valid URL")
+            .push(REVIEW_PATH)
+            .push(&id.to_string());
+
+        let req = self.client.get(url);
+
+        self.do_request_with_meta("get_specific_review", req).await
+    }
+
+    /// Creates a review for a specific `assignment_id`. Using the related
+    /// `subject_id` is also a valid alternative.
+    ///
+    /// The response includes the created review, along with the assignment
+    /// and review statistic that were updated as a result.
+    ///
+    /// ## Expected Starting State
+    ///
+    /// The assignment must be in the following valid state:
+    ///
+    /// Attribute      | State
+    /// ---------------|----------
+    /// `available_at` | Not null; Before the current time
+    /// `burned_at`    | Null
+    /// `srs_stage`    | Must be greater than `0`
+    /// `started_at`   | Not null
+    /// `unlocked_at`  | Not null
+    pub async fn create_review(&self, review: &CreateReview) -> Result<CreatedReview, Error> {
+        Ok(self.create_review_with_meta(review).await?.data)
+    }
+
+    /// Like [`create_review`](Self::create_review), but also returns the
+    /// response's [metadata](super::ResponseMeta).
+    pub async fn create_review_with_meta(
+        &self,
+        review: &CreateReview,
+    ) -> Result<Response<CreatedReview>, Error> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("Valid URL")
+            .push(REVIEW_PATH);
+
+        let req = self.client.post(url).json(review);
+
+        self.do_request_with_meta("create_review", req).await
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The collection of reviews will be filtered on the parameters provided.
+pub struct ReviewFilter {
+    /// Only reviews where `data.assignment_id` matches one of the array values
+    /// are returned.
+    pub assignment_ids: Option<Vec<Id>>,
+    /// Only reviews where `data.id` matches one of the array values are
+    /// returned.
+    pub ids: Option<Vec<Id>>,
+    /// Only reviews where `data.subject_id` matches one of the array values
+    /// are returned.
+    pub subject_ids: Option<Vec<Id>>,
+    /// Only reviews updated after this time are returned.
+    pub updated_after: Option<Timestamp>,
+}
+
+impl Filter for ReviewFilter {
+    fn apply_filters(&self, url: &mut Url) {
+        let mut query = url.query_pairs_mut();
+        if let Some(ref ids) = self.assignment_ids {
+            query.append_pair(
+                "assignment_ids",
+                ids.iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",")
+                    .as_str(),
+            );
+        }
+        if let Some(ref ids) = self.ids {
+            query.append_pair(
+                "ids",
+                ids.iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",")
+                    .as_str(),
+            );
+        }
+        if let Some(ref ids) = self.subject_ids {
+            query.append_pair(
+                "subject_ids",
+                ids.iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",")
+                    .as_str(),
+            );
+        }
+        if let Some(updated_after) = self.updated_after {
+            query.append_pair("updated_after", updated_after.to_rfc3339().as_str());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::{
+        matchers::{body_json_string, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use crate::client::{create_cassette_client, create_mock_client, init_tests};
+
+    #[tokio::test]
+    async fn test_get_reviews() {
+        init_tests();
+
+        let Some(client) = create_cassette_client("review/get_reviews").await else {
+            return;
+        };
+
+        assert!(client.get_reviews(&Default::default()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_get_specific_review() {
+        init_tests();
+
+        let Some(client) = create_cassette_client("review/get_specific_review").await else {
+            return;
+        };
+
+        let reviews = client
+            .get_reviews(&Default::default())
+            .await
+            .expect("Get all reviews");
+
+        if let Some(review) = reviews.data.first() {
+            assert!(client.get_specific_review(review.id).await.is_ok());
+        } else {
+            log::warn!("No reviews detected, this test should not be considered reliable");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_review_created() {
+        use crate::review::CreateReview;
+
+        init_tests();
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/reviews"))
+            .and(body_json_string(include_str!(
+                "../../test_files/create_review.json"
+            )))
+            .respond_with(ResponseTemplate::new(201).set_body_raw(
+                include_str!("../../test_files/created_review.json"),
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = create_mock_client(&server);
+
+        let create: CreateReview =
+            serde_json::from_str(include_str!("../../test_files/create_review.json"))
+                .expect("Deserialize");
+
+        let created = client
+            .create_review(&create)
+            .await
+            .expect("201 Created is a success");
+
+        assert_eq!(created.review.id, 72);
+        assert_eq!(created.resources_updated.assignment.id, 1422);
+        assert_eq!(created.resources_updated.review_statistic.id, 342);
+    }
+}
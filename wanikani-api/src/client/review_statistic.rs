@@ -1,21 +1,36 @@
+use futures_util::Stream;
+
 use crate::{
     cross_feature::SubjectType, review_statistic::ReviewStatistic, Collection, Error, Id, Resource,
     Timestamp,
 };
 
-use super::{Filter, WKClient};
+use super::{Fetched, Filter, Response, Validators, WKClient};
 
 const STAT_PATH: &str = "review_statistics";
 
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 /// The collection of review statistics will be filtered on the parameters provided.
 pub struct ReviewStatisticFilter {
+    /// Return review statistics with a matching value in the `hidden`
+    /// attribute.
     pub hidden: Option<bool>,
+    /// Only review statistics where `data.id` matches one of the array values
+    /// are returned.
     pub ids: Option<Vec<Id>>,
+    /// Return review statistics with a `percentage_correct` greater than the
+    /// value.
     pub percentages_greater_than: Option<u32>,
+    /// Return review statistics with a `percentage_correct` less than the
+    /// value.
     pub percentages_less_than: Option<u32>,
+    /// Only review statistics where `data.subject_id` matches one of the array
+    /// values are returned.
     pub subject_ids: Option<Vec<Id>>,
+    /// Only review statistics where `data.subject_type` matches one of the
+    /// array values are returned.
     pub subject_types: Option<Vec<SubjectType>>,
+    /// Only review statistics updated after this time are returned.
     pub updated_after: Option<Timestamp>,
 }
 
@@ -75,6 +90,32 @@ impl WKClient {
         &self,
         filters: &ReviewStatisticFilter,
     ) -> Result<Collection<ReviewStatistic>, Error> {
+        Ok(self.get_review_statistics_with_meta(filters).await?.data)
+    }
+
+    /// Like [`get_review_statistics`](Self::get_review_statistics), but also
+    /// returns the response's [metadata](super::ResponseMeta).
+    pub async fn get_review_statistics_with_meta(
+        &self,
+        filters: &ReviewStatisticFilter,
+    ) -> Result<Response<Collection<ReviewStatistic>>, Error> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut().expect("Valid URL").push(STAT_PATH);
+
+        filters.apply_filters(&mut url);
+
+        let req = self.client.get(url);
+
+        self.do_request_with_meta("get_resets", req).await
+    }
+
+    /// Returns the collection of review statistics matching `filters`, unless it has not
+    /// changed since `validators` were issued.
+    pub async fn get_review_statistics_if_modified(
+        &self,
+        filters: &ReviewStatisticFilter,
+        validators: &Validators,
+    ) -> Result<Fetched<Collection<ReviewStatistic>>, Error> {
         let mut url = self.base_url.clone();
         url.path_segments_mut().expect("Valid URL").push(STAT_PATH);
 
@@ -82,7 +123,22 @@ impl WKClient {
 
         let req = self.client.get(url);
 
-        self.do_request("get_resets", req).await
+        self.do_conditional_request("get_review_statistics_if_modified", req, validators)
+            .await
+    }
+
+    /// Streams every review statistic matching `filters`, fetching each page of
+    /// the collection as it is needed.
+    pub fn stream_review_statistics(
+        &self,
+        filters: &ReviewStatisticFilter,
+    ) -> impl Stream<Item = Result<Resource<ReviewStatistic>, Error>> + '_ {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut().expect("Valid URL").push(STAT_PATH);
+
+        filters.apply_filters(&mut url);
+
+        self.stream_resources("stream_review_statistics".into(), url)
     }
 
     /// Retrieves a specific review statistic by its `id`.
@@ -90,6 +146,16 @@ impl WKClient {
         &self,
         id: Id,
     ) -> Result<Resource<ReviewStatistic>, Error> {
+        Ok(self.get_specific_review_statistic_with_meta(id).await?.data)
+    }
+
+    /// Like
+    /// [`get_specific_review_statistic`](Self::get_specific_review_statistic),
+    /// but also returns the response's [metadata](super::ResponseMeta).
+    pub async fn get_specific_review_statistic_with_meta(
+        &self,
+        id: Id,
+    ) -> Result<Response<Resource<ReviewStatistic>>, Error> {
         let mut url = self.base_url.clone();
         url.path_segments_mut()
             .expect("Valid URL")
@@ -98,19 +164,22 @@ impl WKClient {
 
         let req = self.client.get(url);
 
-        self.do_request("get_specific_reset", req).await
+        self.do_request_with_meta("get_specific_reset", req).await
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::client::{create_client, init_tests};
+    use crate::client::{create_cassette_client, init_tests};
 
     #[tokio::test]
     async fn test_get_review_statistics() {
         init_tests();
 
-        let client = create_client();
+        let Some(client) = create_cassette_client("review_statistic/get_review_statistics").await
+        else {
+            return;
+        };
 
         assert!(client
             .get_review_statistics(&Default::default())
@@ -122,14 +191,18 @@ mod tests {
     async fn test_get_specific_review_statistic() {
         init_tests();
 
-        let client = create_client();
+        let Some(client) =
+            create_cassette_client("review_statistic/get_specific_review_statistic").await
+        else {
+            return;
+        };
 
         let resets = client
             .get_review_statistics(&Default::default())
             .await
             .expect("Get all review_statistics");
 
-        if let Some(reset) = resets.data.get(0) {
+        if let Some(reset) = resets.data.first() {
             assert!(client.get_specific_review_statistic(reset.id).await.is_ok());
         } else {
             log::warn!(
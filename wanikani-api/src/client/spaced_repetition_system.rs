@@ -0,0 +1,144 @@
+use futures_util::Stream;
+
+use crate::{spaced_repetition_system::SpacedRepetitionSystem, Collection, Error, Id, Resource};
+
+use super::{Fetched, Filter, IdFilter, Response, Validators, WKClient};
+
+const SRS_PATH: &str = "spaced_repetition_systems";
+
+impl WKClient {
+    /// Returns a collection of all spaced repetition systems, ordered by
+    /// ascending `id`, 500 at a time.
+    pub async fn get_spaced_repetition_systems(
+        &self,
+        filters: &IdFilter,
+    ) -> Result<Collection<SpacedRepetitionSystem>, Error> {
+        Ok(self
+            .get_spaced_repetition_systems_with_meta(filters)
+            .await?
+            .data)
+    }
+
+    /// Like
+    /// [`get_spaced_repetition_systems`](Self::get_spaced_repetition_systems),
+    /// but also returns the response's [metadata](super::ResponseMeta).
+    pub async fn get_spaced_repetition_systems_with_meta(
+        &self,
+        filters: &IdFilter,
+    ) -> Result<Response<Collection<SpacedRepetitionSystem>>, Error> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut().expect("Valid URL").push(SRS_PATH);
+
+        filters.apply_filters(&mut url);
+
+        let req = self.client.get(url);
+
+        self.do_request_with_meta("get_spaced_repetition_systems", req)
+            .await
+    }
+
+    /// Returns the collection of spaced repetition systems matching `filters`, unless it has not
+    /// changed since `validators` were issued.
+    pub async fn get_spaced_repetition_systems_if_modified(
+        &self,
+        filters: &IdFilter,
+        validators: &Validators,
+    ) -> Result<Fetched<Collection<SpacedRepetitionSystem>>, Error> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut().expect("Valid URL").push(SRS_PATH);
+
+        filters.apply_filters(&mut url);
+
+        let req = self.client.get(url);
+
+        self.do_conditional_request("get_spaced_repetition_systems_if_modified", req, validators)
+            .await
+    }
+
+    /// Streams every spaced repetition system matching `filters`, fetching each
+    /// page of the collection as it is needed.
+    pub fn stream_spaced_repetition_systems(
+        &self,
+        filters: &IdFilter,
+    ) -> impl Stream<Item = Result<Resource<SpacedRepetitionSystem>, Error>> + '_ {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut().expect("Valid URL").push(SRS_PATH);
+
+        filters.apply_filters(&mut url);
+
+        self.stream_resources("stream_spaced_repetition_systems".into(), url)
+    }
+
+    /// Retrieves a specific spaced repetition system by its `id`.
+    pub async fn get_specific_spaced_repetition_system(
+        &self,
+        id: Id,
+    ) -> Result<Resource<SpacedRepetitionSystem>, Error> {
+        Ok(self
+            .get_specific_spaced_repetition_system_with_meta(id)
+            .await?
+            .data)
+    }
+
+    /// Like
+    /// [`get_specific_spaced_repetition_system`](Self::get_specific_spaced_repetition_system),
+    /// but also returns the response's [metadata](super::ResponseMeta).
+    pub async fn get_specific_spaced_repetition_system_with_meta(
+        &self,
+        id: Id,
+    ) -> Result<Response<Resource<SpacedRepetitionSystem>>, Error> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("Valid URL")
+            .push(SRS_PATH)
+            .push(&id.to_string());
+
+        let req = self.client.get(url);
+
+        self.do_request_with_meta("get_specific_spaced_repetition_system", req)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::{create_cassette_client, init_tests};
+
+    #[tokio::test]
+    async fn test_get_spaced_repetition_systems() {
+        init_tests();
+
+        let Some(client) =
+            create_cassette_client("spaced_repetition_system/get_spaced_repetition_systems").await
+        else {
+            return;
+        };
+
+        let systems = client
+            .get_spaced_repetition_systems(&Default::default())
+            .await
+            .expect("Get all spaced repetition systems");
+
+        assert!(!systems.data.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_specific_spaced_repetition_system() {
+        init_tests();
+
+        let Some(client) = create_cassette_client(
+            "spaced_repetition_system/get_specific_spaced_repetition_system",
+        )
+        .await
+        else {
+            return;
+        };
+
+        let system = client
+            .get_specific_spaced_repetition_system(1)
+            .await
+            .expect("Get spaced repetition system");
+
+        assert_eq!(system.data.passing_stage_position, 5);
+    }
+}
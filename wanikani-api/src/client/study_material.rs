@@ -1,3 +1,4 @@
+use futures_util::Stream;
 use url::Url;
 
 use crate::{
@@ -5,7 +6,7 @@ use crate::{
     Collection, Error, Id, Resource, Timestamp,
 };
 
-use super::{Filter, WKClient};
+use super::{Fetched, Filter, Response, Validators, WKClient};
 
 const STUDY_MATERIAL_PATH: &str = "study_materials";
 
@@ -16,6 +17,34 @@ impl WKClient {
         &self,
         filters: &StudyMaterialFilter,
     ) -> Result<Collection<StudyMaterial>, Error> {
+        Ok(self.get_study_materials_with_meta(filters).await?.data)
+    }
+
+    /// Like [`get_study_materials`](Self::get_study_materials), but also
+    /// returns the response's [metadata](super::ResponseMeta).
+    pub async fn get_study_materials_with_meta(
+        &self,
+        filters: &StudyMaterialFilter,
+    ) -> Result<Response<Collection<StudyMaterial>>, Error> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("Valid URL")
+            .push(STUDY_MATERIAL_PATH);
+
+        filters.apply_filters(&mut url);
+
+        let req = self.client.get(url);
+
+        self.do_request_with_meta("get_subjects", req).await
+    }
+
+    /// Returns the collection of study materials matching `filters`, unless it has not
+    /// changed since `validators` were issued.
+    pub async fn get_study_materials_if_modified(
+        &self,
+        filters: &StudyMaterialFilter,
+        validators: &Validators,
+    ) -> Result<Fetched<Collection<StudyMaterial>>, Error> {
         let mut url = self.base_url.clone();
         url.path_segments_mut()
             .expect("Valid URL")
@@ -25,7 +54,24 @@ impl WKClient {
 
         let req = self.client.get(url);
 
-        self.do_request("get_subjects", req).await
+        self.do_conditional_request("get_study_materials_if_modified", req, validators)
+            .await
+    }
+
+    /// Streams every study material matching `filters`, fetching each page of
+    /// the collection as it is needed.
+    pub fn stream_study_materials(
+        &self,
+        filters: &StudyMaterialFilter,
+    ) -> impl Stream<Item = Result<Resource<StudyMaterial>, Error>> + '_ {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("Valid URL")
+            .push(STUDY_MATERIAL_PATH);
+
+        filters.apply_filters(&mut url);
+
+        self.stream_resources("stream_study_materials".into(), url)
     }
 
     /// Retrieves a specific study material by its `id`.
@@ -33,6 +79,15 @@ impl WKClient {
         &self,
         id: Id,
     ) -> Result<Resource<StudyMaterial>, Error> {
+        Ok(self.get_specific_study_material_with_meta(id).await?.data)
+    }
+
+    /// Like [`get_specific_study_material`](Self::get_specific_study_material),
+    /// but also returns the response's [metadata](super::ResponseMeta).
+    pub async fn get_specific_study_material_with_meta(
+        &self,
+        id: Id,
+    ) -> Result<Response<Resource<StudyMaterial>>, Error> {
         let mut url = self.base_url.clone();
         url.path_segments_mut()
             .expect("Valid URL")
@@ -41,7 +96,7 @@ impl WKClient {
 
         let req = self.client.get(url);
 
-        self.do_request("get_specific_subject", req).await
+        self.do_request_with_meta("get_specific_subject", req).await
     }
 
     /// Creates a study material for a specific `subject_id`.
@@ -52,6 +107,15 @@ impl WKClient {
         &self,
         material: &CreateStudyMaterial,
     ) -> Result<Resource<StudyMaterial>, Error> {
+        Ok(self.create_study_material_with_meta(material).await?.data)
+    }
+
+    /// Like [`create_study_material`](Self::create_study_material), but also
+    /// returns the response's [metadata](super::ResponseMeta).
+    pub async fn create_study_material_with_meta(
+        &self,
+        material: &CreateStudyMaterial,
+    ) -> Result<Response<Resource<StudyMaterial>>, Error> {
         let mut url = self.base_url.clone();
         url.path_segments_mut()
             .expect("Valid URL")
@@ -59,7 +123,8 @@ impl WKClient {
 
         let req = self.client.post(url).json(material);
 
-        self.do_request("create_study_material", req).await
+        self.do_request_with_meta("create_study_material", req)
+            .await
     }
 
     /// Updates a study material for a specific `id`.
@@ -68,6 +133,19 @@ impl WKClient {
         id: Id,
         material: &UpdateStudyMaterial,
     ) -> Result<Resource<StudyMaterial>, Error> {
+        Ok(self
+            .update_study_material_with_meta(id, material)
+            .await?
+            .data)
+    }
+
+    /// Like [`update_study_material`](Self::update_study_material), but also
+    /// returns the response's [metadata](super::ResponseMeta).
+    pub async fn update_study_material_with_meta(
+        &self,
+        id: Id,
+        material: &UpdateStudyMaterial,
+    ) -> Result<Response<Resource<StudyMaterial>>, Error> {
         let mut url = self.base_url.clone();
         url.path_segments_mut()
             .expect("Valid URL")
@@ -76,7 +154,8 @@ impl WKClient {
 
         let req = self.client.put(url).json(material);
 
-        self.do_request("update_study_material", req).await
+        self.do_request_with_meta("update_study_material", req)
+            .await
     }
 }
 
@@ -146,13 +225,21 @@ impl Filter for StudyMaterialFilter {
 
 #[cfg(test)]
 mod tests {
-    use crate::client::{create_client, init_tests};
+    use wiremock::{
+        matchers::{body_json_string, header, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use crate::client::{create_cassette_client, create_mock_client, init_tests};
 
     #[tokio::test]
     async fn test_get_study_materials() {
         init_tests();
 
-        let client = create_client();
+        let Some(client) = create_cassette_client("study_material/get_study_materials").await
+        else {
+            return;
+        };
 
         assert!(client
             .get_study_materials(&Default::default())
@@ -165,14 +252,18 @@ mod tests {
     async fn test_get_specific_study_material() {
         init_tests();
 
-        let client = create_client();
+        let Some(client) =
+            create_cassette_client("study_material/get_specific_study_material").await
+        else {
+            return;
+        };
 
         let study_materials = client
             .get_study_materials(&Default::default())
             .await
             .expect("Get all study_materials");
 
-        if let Some(prog) = study_materials.data.get(0) {
+        if let Some(prog) = study_materials.data.first() {
             assert!(client.get_specific_study_material(prog.id).await.is_ok());
         } else {
             log::warn!("No study materials detected, this test should not be considered reliable");
@@ -185,14 +276,17 @@ mod tests {
 
         init_tests();
 
-        let client = create_client();
+        let Some(client) = create_cassette_client("study_material/update_study_material").await
+        else {
+            return;
+        };
 
         let study_materials = client
             .get_study_materials(&Default::default())
             .await
             .expect("Get all study_materials");
 
-        if let Some(prog) = study_materials.data.get(0) {
+        if let Some(prog) = study_materials.data.first() {
             let update = UpdateStudyMaterial {
                 meaning_note: prog.data.meaning_note.clone(),
                 ..Default::default()
@@ -207,4 +301,152 @@ mod tests {
             log::warn!("No study materials detected, this test should not be considered reliable");
         }
     }
+
+    #[tokio::test]
+    async fn test_create_study_material_created() {
+        use crate::study_material::CreateStudyMaterial;
+
+        init_tests();
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/study_materials"))
+            .and(header("Authorization", "Bearer MOCK_TOKEN"))
+            .and(body_json_string(include_str!(
+                "../../test_files/create_study_material.json"
+            )))
+            .respond_with(ResponseTemplate::new(201).set_body_raw(
+                include_str!("../../test_files/study_material.json"),
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = create_mock_client(&server);
+
+        let create: CreateStudyMaterial =
+            serde_json::from_str(include_str!("../../test_files/create_study_material.json"))
+                .expect("Deserialize");
+
+        let material = client
+            .create_study_material(&create)
+            .await
+            .expect("201 Created is a success");
+
+        assert_eq!(material.id, 65231);
+        assert_eq!(material.data.meaning_synonyms, ["burn", "sizzle"]);
+    }
+
+    #[tokio::test]
+    async fn test_update_study_material_ok() {
+        use crate::study_material::UpdateStudyMaterial;
+
+        init_tests();
+
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/study_materials/65231"))
+            .and(header("Authorization", "Bearer MOCK_TOKEN"))
+            .and(body_json_string(include_str!(
+                "../../test_files/update_study_material.json"
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                include_str!("../../test_files/study_material.json"),
+                "application/json",
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = create_mock_client(&server);
+
+        let update: UpdateStudyMaterial =
+            serde_json::from_str(include_str!("../../test_files/update_study_material.json"))
+                .expect("Deserialize");
+
+        let material = client
+            .update_study_material(65231, &update)
+            .await
+            .expect("200 OK is a success");
+
+        assert_eq!(material.id, 65231);
+    }
+
+    #[tokio::test]
+    async fn test_create_study_material_unprocessable() {
+        use crate::{study_material::CreateStudyMaterial, Error};
+
+        init_tests();
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/study_materials"))
+            .respond_with(ResponseTemplate::new(422).set_body_raw(
+                r#"{"error":"Subject has already been taken","code":422}"#,
+                "application/json",
+            ))
+            .mount(&server)
+            .await;
+
+        let client = create_mock_client(&server);
+
+        let error = client
+            .create_study_material(&CreateStudyMaterial {
+                subject_id: 2,
+                ..Default::default()
+            })
+            .await
+            .expect_err("422 is an error");
+
+        assert!(!error.is_retryable());
+        let Error::Unprocessable { errors, context } = error else {
+            panic!("Expected an unprocessable error");
+        };
+        assert_eq!(errors, ["Subject has already been taken"]);
+        assert_eq!(context.status, 422);
+        assert_eq!(context.method, "POST");
+        assert_eq!(context.url.path(), "/study_materials");
+    }
+
+    #[tokio::test]
+    async fn test_create_study_material_not_retried() {
+        use std::time::Duration;
+
+        use crate::{
+            client::{RetryPolicy, WKClientBuilder},
+            study_material::CreateStudyMaterial,
+            Error,
+        };
+
+        init_tests();
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/study_materials"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = WKClientBuilder::default()
+            .token("MOCK_TOKEN".into())
+            .base_url(server.uri().parse().expect("Mock server URL"))
+            .retry_policy(RetryPolicy {
+                initial_backoff: Duration::ZERO,
+                ..RetryPolicy::default()
+            })
+            .build()
+            .expect("Mock client");
+
+        let error = client
+            .create_study_material(&CreateStudyMaterial {
+                subject_id: 2,
+                ..Default::default()
+            })
+            .await
+            .expect_err("503 is an error");
+
+        assert!(matches!(error, Error::ServerError { .. }));
+    }
 }
@@ -1,3 +1,4 @@
+use futures_util::Stream;
 use url::Url;
 
 use crate::{
@@ -5,7 +6,7 @@ use crate::{
     Collection, Error, Id, Resource, Timestamp,
 };
 
-use super::{Filter, WKClient};
+use super::{Fetched, Filter, Response, Validators, WKClient};
 
 const SUBJECT_PATH: &str = "subjects";
 
@@ -16,6 +17,15 @@ impl WKClient {
         &self,
         filters: &SubjectFilter,
     ) -> Result<Collection<Subject>, Error> {
+        Ok(self.get_subjects_with_meta(filters).await?.data)
+    }
+
+    /// Like [`get_subjects`](Self::get_subjects), but also returns the
+    /// response's [metadata](super::ResponseMeta).
+    pub async fn get_subjects_with_meta(
+        &self,
+        filters: &SubjectFilter,
+    ) -> Result<Response<Collection<Subject>>, Error> {
         let mut url = self.base_url.clone();
         url.path_segments_mut()
             .expect("Valid URL")
@@ -25,7 +35,43 @@ impl WKClient {
 
         let req = self.client.get(url);
 
-        self.do_request("get_subjects", req).await
+        self.do_request_with_meta("get_subjects", req).await
+    }
+
+    /// Returns the collection of subjects matching `filters`, unless it has not
+    /// changed since `validators` were issued.
+    pub async fn get_subjects_if_modified(
+        &self,
+        filters: &SubjectFilter,
+        validators: &Validators,
+    ) -> Result<Fetched<Collection<Subject>>, Error> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("Valid URL")
+            .push(SUBJECT_PATH);
+
+        filters.apply_filters(&mut url);
+
+        let req = self.client.get(url);
+
+        self.do_conditional_request("get_subjects_if_modified", req, validators)
+            .await
+    }
+
+    /// Streams every subject matching `filters`, fetching each page of the
+    /// collection as it is needed.
+    pub fn stream_subjects(
+        &self,
+        filters: &SubjectFilter,
+    ) -> impl Stream<Item = Result<Resource<Subject>, Error>> + '_ {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("Valid URL")
+            .push(SUBJECT_PATH);
+
+        filters.apply_filters(&mut url);
+
+        self.stream_resources("stream_subjects".into(), url)
     }
 
     /// Retrieves a specific subject by its `id`. The structure of the
@@ -34,6 +80,15 @@ impl WKClient {
         &self,
         id: Id,
     ) -> Result<Resource<T>, Error> {
+        Ok(self.get_specific_subject_with_meta(id).await?.data)
+    }
+
+    /// Like [`get_specific_subject`](Self::get_specific_subject), but also
+    /// returns the response's [metadata](super::ResponseMeta).
+    pub async fn get_specific_subject_with_meta<T: WaniKaniSubject>(
+        &self,
+        id: Id,
+    ) -> Result<Response<Resource<T>>, Error> {
         let mut url = self.base_url.clone();
         url.path_segments_mut()
             .expect("Valid URL")
@@ -42,7 +97,7 @@ impl WKClient {
 
         let req = self.client.get(url);
 
-        self.do_request("get_specific_subject", req).await
+        self.do_request_with_meta("get_specific_subject", req).await
     }
 }
 
@@ -115,7 +170,7 @@ impl Filter for SubjectFilter {
 
 #[cfg(test)]
 mod tests {
-    use crate::client::{create_client, init_tests};
+    use crate::client::{create_cassette_client, init_tests};
 
     #[tokio::test]
     async fn test_get_subjects() {
@@ -123,7 +178,9 @@ mod tests {
 
         init_tests();
 
-        let client = create_client();
+        let Some(client) = create_cassette_client("subject/get_subjects").await else {
+            return;
+        };
         let filters = SubjectFilter {
             levels: Some(vec![2]),
             ..SubjectFilter::default()
@@ -131,6 +188,32 @@ mod tests {
         assert!(client.get_subjects(&filters).await.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_stream_subjects() {
+        use futures_util::TryStreamExt;
+
+        use super::SubjectFilter;
+
+        init_tests();
+
+        let Some(client) = create_cassette_client("subject/stream_subjects").await else {
+            return;
+        };
+        let filters = SubjectFilter {
+            levels: Some(vec![1, 2, 3]),
+            ..SubjectFilter::default()
+        };
+
+        let first_page = client.get_subjects(&filters).await.expect("First page");
+        let subjects: Vec<_> = client
+            .stream_subjects(&filters)
+            .try_collect()
+            .await
+            .expect("All subjects");
+
+        assert_eq!(subjects.len() as u64, first_page.total_count);
+    }
+
     #[cfg(feature = "subject")]
     #[tokio::test]
     async fn test_get_specific_subject() {
@@ -141,7 +224,9 @@ mod tests {
 
         init_tests();
 
-        let client = create_client();
+        let Some(client) = create_cassette_client("subject/get_specific_subject").await else {
+            return;
+        };
         let mut subject: Resource<Subject> =
             client.get_specific_subject(1).await.expect("Get subject");
         let radical: Resource<Radical> = client.get_specific_subject(1).await.expect("Get radical");
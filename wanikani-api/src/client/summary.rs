@@ -1,12 +1,34 @@
 use crate::{summary::Summary, Error};
 
-use super::WKClient;
+use super::{Fetched, Response, Validators, WKClient};
 
 const SUMMARY_PATH: &str = "summary";
 
 impl WKClient {
     /// Get a summary report of available and upcoming lessons and reviews.
     pub async fn get_summary(&self) -> Result<Summary, Error> {
+        Ok(self.get_summary_with_meta().await?.data)
+    }
+
+    /// Like [`get_summary`](Self::get_summary), but also returns the response's
+    /// [metadata](super::ResponseMeta).
+    pub async fn get_summary_with_meta(&self) -> Result<Response<Summary>, Error> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("Valid URL")
+            .push(SUMMARY_PATH);
+
+        let req = self.client.get(url);
+
+        self.do_request_with_meta("get_summary", req).await
+    }
+
+    /// Get a summary report of available and upcoming lessons and reviews,
+    /// unless it has not changed since `validators` were issued.
+    pub async fn get_summary_if_modified(
+        &self,
+        validators: &Validators,
+    ) -> Result<Fetched<Summary>, Error> {
         let mut url = self.base_url.clone();
         url.path_segments_mut()
             .expect("Valid URL")
@@ -14,19 +36,22 @@ impl WKClient {
 
         let req = self.client.get(url);
 
-        self.do_request("get_summary", req).await
+        self.do_conditional_request("get_summary_if_modified", req, validators)
+            .await
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::client::{create_client, init_tests};
+    use crate::client::{create_cassette_client, init_tests};
 
     #[tokio::test]
     async fn test_get_summary() {
         init_tests();
 
-        let client = create_client();
+        let Some(client) = create_cassette_client("summary/get_summary").await else {
+            return;
+        };
 
         assert!(client.get_summary().await.is_ok());
     }
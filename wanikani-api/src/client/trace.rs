@@ -0,0 +1,190 @@
+use std::{future::Future, time::Duration};
+
+use reqwest::{Request, Response};
+
+#[cfg(feature = "tracing")]
+use tracing::{field::Empty, Instrument};
+
+/// The span of a single endpoint call. With the `tracing` feature, this is a
+/// `wanikani.request` span whose fields are filled in as the request
+/// progresses. Without it, the same information is written to the log.
+///
+/// Headers are never recorded, so the API token cannot end up in a span.
+#[derive(Debug, Clone)]
+pub(super) struct RequestSpan {
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
+}
+
+impl RequestSpan {
+    pub(super) fn new(endpoint: &str) -> Self {
+        #[cfg(not(feature = "tracing"))]
+        let _ = endpoint;
+
+        Self {
+            #[cfg(feature = "tracing")]
+            span: tracing::debug_span!(
+                "wanikani.request",
+                endpoint,
+                method = Empty,
+                path = Empty,
+                query = Empty,
+                status = Empty,
+                latency_ms = Empty,
+                page_resources = Empty,
+                total_count = Empty,
+            ),
+        }
+    }
+
+    /// The span of the endpoint call currently being made.
+    pub(super) fn current() -> Self {
+        Self {
+            #[cfg(feature = "tracing")]
+            span: tracing::Span::current(),
+        }
+    }
+
+    pub(super) async fn instrument<F: Future>(&self, future: F) -> F::Output {
+        #[cfg(feature = "tracing")]
+        let future = future.instrument(self.span.clone());
+
+        future.await
+    }
+
+    pub(super) fn record_request(&self, endpoint: &str, request: &Request) {
+        #[cfg(feature = "tracing")]
+        {
+            let _ = endpoint;
+            let url = request.url();
+            self.span
+                .record("method", request.method().as_str())
+                .record("path", url.path());
+            if let Some(query) = url.query() {
+                self.span.record("query", query);
+            }
+            tracing::debug!(parent: &self.span, "Sending request");
+        }
+        #[cfg(not(feature = "tracing"))]
+        log::debug!("{endpoint} request: {request:?}");
+    }
+
+    pub(super) fn record_response(&self, endpoint: &str, response: &Response, latency: Duration) {
+        #[cfg(feature = "tracing")]
+        {
+            let _ = endpoint;
+            let latency_ms = u64::try_from(latency.as_millis()).unwrap_or(u64::MAX);
+            self.span
+                .record("status", response.status().as_u16())
+                .record("latency_ms", latency_ms);
+            tracing::debug!(parent: &self.span, "Received response");
+        }
+        #[cfg(not(feature = "tracing"))]
+        log::debug!("{endpoint} response after {latency:?}: {response:?}");
+    }
+
+    pub(super) fn record_page(&self, endpoint: &str, resources: usize, total_count: u64) {
+        #[cfg(feature = "tracing")]
+        {
+            let _ = endpoint;
+            self.span
+                .record("page_resources", resources)
+                .record("total_count", total_count);
+        }
+        #[cfg(not(feature = "tracing"))]
+        log::debug!("{endpoint} received page of {resources} resources, {total_count} total");
+    }
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use tracing_subscriber::fmt::{format::FmtSpan, MakeWriter};
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    use crate::client::create_mock_client;
+
+    #[derive(Clone, Default)]
+    struct Output(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Output {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0
+                .lock()
+                .expect("Lock not poisoned")
+                .extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Output {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_span() {
+        let output = Output::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_ansi(false)
+            .with_writer(output.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/voice_actors"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                include_str!("../../test_files/voice_actors.json"),
+                "application/json",
+            ))
+            .mount(&server)
+            .await;
+
+        let client = create_mock_client(&server);
+        let url = format!("{}/voice_actors?ids=1,2", server.uri())
+            .parse()
+            .expect("URL");
+        let _: Vec<_> = futures_util::TryStreamExt::try_collect(
+            client.stream_resources_by_url::<serde_json::Value>(&url),
+        )
+        .await
+        .expect("Success");
+
+        let output = output.0.lock().expect("Lock not poisoned");
+        let output = String::from_utf8_lossy(&output);
+        let close = output
+            .lines()
+            .find(|line| line.contains("close"))
+            .expect("Span closed");
+        assert!(close.contains("wanikani.request"), "{close}");
+        assert!(
+            close.contains("endpoint=\"stream_resources_by_url<"),
+            "{close}"
+        );
+        assert!(close.contains("method=\"GET\""), "{close}");
+        assert!(close.contains("path=\"/voice_actors\""), "{close}");
+        assert!(close.contains("query=\"ids=1,2\""), "{close}");
+        assert!(close.contains("status=200"), "{close}");
+        assert!(close.contains("latency_ms="), "{close}");
+        assert!(close.contains("page_resources=2"), "{close}");
+        assert!(close.contains("total_count=2"), "{close}");
+        assert!(!output.contains("MOCK_TOKEN"), "{output}");
+    }
+}
@@ -3,41 +3,74 @@ use crate::{
     Error,
 };
 
-use super::WKClient;
+use super::{Fetched, Response, Validators, WKClient};
 
 const USER_PATH: &str = "user";
 
 impl WKClient {
     /// Returns a summary of user information.
     pub async fn get_user_information(&self) -> Result<User, Error> {
+        Ok(self.get_user_information_with_meta().await?.data)
+    }
+
+    /// Like [`get_user_information`](Self::get_user_information), but also
+    /// returns the response's [metadata](super::ResponseMeta).
+    pub async fn get_user_information_with_meta(&self) -> Result<Response<User>, Error> {
         let mut url = self.base_url.clone();
         url.path_segments_mut().expect("Valid URL").push(USER_PATH);
 
         let req = self.client.get(url);
 
-        self.do_request("get_user_information", req).await
+        self.do_request_with_meta("get_user_information", req).await
+    }
+
+    /// Returns a summary of user information, unless it has not changed since
+    /// `validators` were issued.
+    pub async fn get_user_information_if_modified(
+        &self,
+        validators: &Validators,
+    ) -> Result<Fetched<User>, Error> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut().expect("Valid URL").push(USER_PATH);
+
+        let req = self.client.get(url);
+
+        self.do_conditional_request("get_user_information_if_modified", req, validators)
+            .await
     }
 
     /// Returns an updated summary of user information.
     pub async fn update_user_information(&self, user: &UpdateUser) -> Result<User, Error> {
+        Ok(self.update_user_information_with_meta(user).await?.data)
+    }
+
+    /// Like [`update_user_information`](Self::update_user_information), but
+    /// also returns the response's [metadata](super::ResponseMeta).
+    pub async fn update_user_information_with_meta(
+        &self,
+        user: &UpdateUser,
+    ) -> Result<Response<User>, Error> {
         let mut url = self.base_url.clone();
         url.path_segments_mut().expect("Valid URL").push(USER_PATH);
 
         let req = self.client.put(url).json(user);
 
-        self.do_request("update_user_information", req).await
+        self.do_request_with_meta("update_user_information", req)
+            .await
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::client::{create_client, init_tests};
+    use crate::client::{create_cassette_client, init_tests};
 
     #[tokio::test]
     async fn test_get_user_information() {
         init_tests();
 
-        let client = create_client();
+        let Some(client) = create_cassette_client("user/get_user_information").await else {
+            return;
+        };
 
         assert!(client.get_user_information().await.is_ok());
     }
@@ -48,7 +81,9 @@ mod tests {
 
         init_tests();
 
-        let client = create_client();
+        let Some(client) = create_cassette_client("user/update_user_information").await else {
+            return;
+        };
 
         let user = client.get_user_information().await.expect("Success");
 
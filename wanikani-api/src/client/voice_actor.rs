@@ -1,6 +1,8 @@
+use futures_util::Stream;
+
 use crate::{voice_actor::VoiceActor, Collection, Error, Id, Resource};
 
-use super::{Filter, IdFilter, WKClient};
+use super::{Fetched, Filter, IdFilter, Response, Validators, WKClient};
 
 const VO_PATH: &str = "voice_actors";
 
@@ -11,6 +13,32 @@ impl WKClient {
         &self,
         filters: &IdFilter,
     ) -> Result<Collection<VoiceActor>, Error> {
+        Ok(self.get_voice_actors_with_meta(filters).await?.data)
+    }
+
+    /// Like [`get_voice_actors`](Self::get_voice_actors), but also returns the
+    /// response's [metadata](super::ResponseMeta).
+    pub async fn get_voice_actors_with_meta(
+        &self,
+        filters: &IdFilter,
+    ) -> Result<Response<Collection<VoiceActor>>, Error> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut().expect("Valid URL").push(VO_PATH);
+
+        filters.apply_filters(&mut url);
+
+        let req = self.client.get(url);
+
+        self.do_request_with_meta("get_voice_actors", req).await
+    }
+
+    /// Returns the collection of voice actors matching `filters`, unless it has not
+    /// changed since `validators` were issued.
+    pub async fn get_voice_actors_if_modified(
+        &self,
+        filters: &IdFilter,
+        validators: &Validators,
+    ) -> Result<Fetched<Collection<VoiceActor>>, Error> {
         let mut url = self.base_url.clone();
         url.path_segments_mut().expect("Valid URL").push(VO_PATH);
 
@@ -18,11 +46,35 @@ impl WKClient {
 
         let req = self.client.get(url);
 
-        self.do_request("get_voice_actors", req).await
+        self.do_conditional_request("get_voice_actors_if_modified", req, validators)
+            .await
+    }
+
+    /// Streams every voice actor matching `filters`, fetching each page of the
+    /// collection as it is needed.
+    pub fn stream_voice_actors(
+        &self,
+        filters: &IdFilter,
+    ) -> impl Stream<Item = Result<Resource<VoiceActor>, Error>> + '_ {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut().expect("Valid URL").push(VO_PATH);
+
+        filters.apply_filters(&mut url);
+
+        self.stream_resources("stream_voice_actors".into(), url)
     }
 
     /// Retrieves a specific voice_actor by its `id`.
     pub async fn get_specific_voice_actor(&self, id: Id) -> Result<Resource<VoiceActor>, Error> {
+        Ok(self.get_specific_voice_actor_with_meta(id).await?.data)
+    }
+
+    /// Like [`get_specific_voice_actor`](Self::get_specific_voice_actor), but
+    /// also returns the response's [metadata](super::ResponseMeta).
+    pub async fn get_specific_voice_actor_with_meta(
+        &self,
+        id: Id,
+    ) -> Result<Response<Resource<VoiceActor>>, Error> {
         let mut url = self.base_url.clone();
         url.path_segments_mut()
             .expect("Valid URL")
@@ -31,7 +83,8 @@ impl WKClient {
 
         let req = self.client.get(url);
 
-        self.do_request("get_specific_voice_actor", req).await
+        self.do_request_with_meta("get_specific_voice_actor", req)
+            .await
     }
 }
 
@@ -40,7 +93,7 @@ mod tests {
     use chrono::{Duration, Utc};
 
     use crate::{
-        client::{create_client, init_tests, IdFilter},
+        client::{create_cassette_client, init_tests, IdFilter},
         Timestamp,
     };
 
@@ -52,7 +105,9 @@ mod tests {
     async fn test_get_voice_actors() {
         init_tests();
 
-        let client = create_client();
+        let Some(client) = create_cassette_client("voice_actor/get_voice_actors").await else {
+            return;
+        };
 
         let mut voice_actors = client
             .get_voice_actors(&IdFilter::default())
@@ -84,11 +139,70 @@ mod tests {
         assert_eq!(voice_actors.total_count, 0);
         assert!(voice_actors.data.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_stream_voice_actors() {
+        use futures_util::TryStreamExt;
+
+        init_tests();
+
+        let Some(client) = create_cassette_client("voice_actor/stream_voice_actors").await else {
+            return;
+        };
+
+        let voice_actors: Vec<_> = client
+            .stream_voice_actors(&IdFilter::default())
+            .try_collect()
+            .await
+            .expect("VOs returned");
+
+        assert_eq!(voice_actors.len(), 2);
+
+        let first_page = client
+            .get_voice_actors(&IdFilter::default())
+            .await
+            .expect("VOs returned");
+        let collected = client.collect_all(first_page).await.expect("All VOs");
+
+        assert_eq!(collected, voice_actors);
+    }
+
+    #[tokio::test]
+    async fn test_get_voice_actors_if_modified() {
+        use crate::client::{Fetched, Validators};
+
+        init_tests();
+
+        let Some(client) = create_cassette_client("voice_actor/get_voice_actors_if_modified").await
+        else {
+            return;
+        };
+
+        let (voice_actors, validators) = client
+            .get_voice_actors_if_modified(&IdFilter::default(), &Validators::default())
+            .await
+            .expect("VOs returned")
+            .into_modified()
+            .expect("Unconditional request is always modified");
+
+        assert_eq!(voice_actors.total_count, 2);
+
+        let refetched = client
+            .get_voice_actors_if_modified(&IdFilter::default(), &validators)
+            .await
+            .expect("Conditional request");
+
+        assert_eq!(refetched, Fetched::NotModified);
+    }
+
     #[tokio::test]
     async fn test_get_specific_voice_actor() {
         init_tests();
 
-        let client = create_client();
+        let Some(client) = create_cassette_client("voice_actor/get_specific_voice_actor").await
+        else {
+            return;
+        };
 
         assert!(client.get_specific_voice_actor(1).await.is_ok());
     }
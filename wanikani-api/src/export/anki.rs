@@ -0,0 +1,524 @@
+//! Export of subjects and study materials as notes for
+//! [Anki](https://apps.ankiweb.net/).
+//!
+//! [`AnkiExport`] writes a tab-separated file in the format read by Anki's
+//! *Import File* dialog (Anki 2.1.55 or later). The file starts with headers
+//! that tell Anki the note type, deck, and which columns hold the note's GUID
+//! and tags, so the file can be imported without any further setup once a
+//! note type with the fields in [`FIELDS`] exists. Notes are identified by
+//! their subject, so importing a newer export updates the existing notes.
+//!
+//! Each note contains the subject's meanings and readings, its mnemonics and
+//! hints with their markup rendered as HTML, context sentences, the subjects
+//! it is made of and used in, and the user's own notes and synonyms from their
+//! study materials.
+//!
+//! Pronunciation audio is included as `[sound:...]` references for files that
+//! have already been downloaded to a local directory, given by
+//! [`AnkiExport::with_media_dir`]. With the `media` feature, the client's
+//! `download_media` method fills such a directory. Those files should be
+//! copied into Anki's `collection.media` folder.
+//!
+//! ### Example
+//! ```rust
+//! # use wanikani_api::{export::anki::AnkiExport, subject::SubjectType};
+//! # let subjects = vec![
+//! #     serde_json::from_str(include_str!("../../test_files/kanji.json")).unwrap(),
+//! # ];
+//! let mut tsv = Vec::new();
+//! let written = AnkiExport::new(&subjects)
+//!     .with_levels(vec![1, 2])
+//!     .with_types(vec![SubjectType::Kanji])
+//!     .write_tsv(&mut tsv)
+//!     .expect("Exported");
+//! assert_eq!(written, 1);
+//! ```
+
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    mnemonic::{escape_html, HtmlClasses, Mnemonic},
+    study_material::StudyMaterial,
+    subject::{ContextSentence, PronunciationAudio, Subject, SubjectType},
+    Id, Resource,
+};
+
+/// The fields of the note type used by [`AnkiExport`], in order.
+pub const FIELDS: &[&str] = &[
+    "Subject ID",
+    "Type",
+    "Level",
+    "Characters",
+    "Meanings",
+    "Readings",
+    "Meaning Mnemonic",
+    "Meaning Hint",
+    "Reading Mnemonic",
+    "Reading Hint",
+    "Context Sentences",
+    "Components",
+    "Used In",
+    "Meaning Note",
+    "Reading Note",
+    "Synonyms",
+    "Audio",
+    "Document URL",
+];
+
+#[derive(Debug, Clone)]
+/// Writes subjects as Anki notes. See the [module documentation](self) for
+/// the format.
+pub struct AnkiExport<'a> {
+    subjects: &'a [Resource<Subject>],
+    study_materials: HashMap<Id, &'a StudyMaterial>,
+    levels: Option<Vec<u32>>,
+    types: Option<Vec<SubjectType>>,
+    media_dir: Option<PathBuf>,
+    deck: String,
+    note_type: String,
+    classes: HtmlClasses,
+}
+
+impl<'a> AnkiExport<'a> {
+    /// Export `subjects`, into a deck and note type both named `WaniKani`.
+    ///
+    /// Subjects that are filtered out are still used to show the components
+    /// and uses of the subjects that are exported.
+    pub fn new(subjects: &'a [Resource<Subject>]) -> Self {
+        Self {
+            subjects,
+            study_materials: HashMap::new(),
+            levels: None,
+            types: None,
+            media_dir: None,
+            deck: "WaniKani".into(),
+            note_type: "WaniKani".into(),
+            classes: HtmlClasses::default(),
+        }
+    }
+
+    /// Include the user's notes and synonyms from their study materials.
+    pub fn with_study_materials(self, materials: &'a [Resource<StudyMaterial>]) -> Self {
+        Self {
+            study_materials: materials
+                .iter()
+                .filter(|m| !m.data.hidden)
+                .map(|m| (m.data.subject_id, &m.data))
+                .collect(),
+            ..self
+        }
+    }
+
+    /// Only export subjects at these levels.
+    pub fn with_levels(self, levels: Vec<u32>) -> Self {
+        Self {
+            levels: Some(levels),
+            ..self
+        }
+    }
+
+    /// Only export subjects of these types.
+    pub fn with_types(self, types: Vec<SubjectType>) -> Self {
+        Self {
+            types: Some(types),
+            ..self
+        }
+    }
+
+    /// Reference pronunciation audio files found in `dir`. A file is found if
+    /// it is named by [`PronunciationAudio::file_name`], as the client's
+    /// `download_media` method names it.
+    pub fn with_media_dir(self, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            media_dir: Some(dir.into()),
+            ..self
+        }
+    }
+
+    /// Import the notes into the deck named `deck`.
+    pub fn with_deck(self, deck: impl Into<String>) -> Self {
+        Self {
+            deck: deck.into(),
+            ..self
+        }
+    }
+
+    /// Import the notes using the note type named `note_type`, which must
+    /// have the fields in [`FIELDS`].
+    pub fn with_note_type(self, note_type: impl Into<String>) -> Self {
+        Self {
+            note_type: note_type.into(),
+            ..self
+        }
+    }
+
+    /// Use `classes` when rendering mnemonic markup.
+    pub fn with_html_classes(self, classes: HtmlClasses) -> Self {
+        Self { classes, ..self }
+    }
+
+    /// Write the notes as an Anki-importable tab-separated file, returning
+    /// the number of notes written.
+    pub fn write_tsv(&self, mut writer: impl Write) -> io::Result<usize> {
+        writeln!(writer, "#separator:tab")?;
+        writeln!(writer, "#html:true")?;
+        writeln!(writer, "#notetype:{}", self.note_type)?;
+        writeln!(writer, "#deck:{}", self.deck)?;
+        writeln!(writer, "#guid column:1")?;
+        writeln!(writer, "#tags column:{}", FIELDS.len() + 2)?;
+
+        let characters: HashMap<Id, &str> = self
+            .subjects
+            .iter()
+            .map(|s| (s.id, characters(&s.data)))
+            .collect();
+
+        let mut written = 0;
+        for subject in self.subjects.iter().filter(|s| self.is_exported(&s.data)) {
+            let row = self.row(subject, &characters);
+            let line: Vec<_> = row.iter().map(|field| tsv_field(field)).collect();
+            writeln!(writer, "{}", line.join("\t"))?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    fn is_exported(&self, subject: &Subject) -> bool {
+        let common = subject.common();
+        common.hidden_at.is_none()
+            && self
+                .levels
+                .as_ref()
+                .map_or(true, |levels| levels.contains(&common.level))
+            && self
+                .types
+                .as_ref()
+                .map_or(true, |types| types.contains(&subject.subject_type()))
+    }
+
+    /// The GUID, the fields, and the tags of a subject's note.
+    fn row(&self, subject: &Resource<Subject>, related: &HashMap<Id, &str>) -> Vec<String> {
+        let data = &subject.data;
+        let common = data.common();
+        let material = self.study_materials.get(&subject.id);
+
+        let mut meanings: Vec<_> = common
+            .meanings
+            .iter()
+            .filter(|m| m.accepted_answer)
+            .collect();
+        meanings.sort_by_key(|m| !m.primary);
+
+        let (readings, meaning_hint, reading_mnemonic, reading_hint) = match data {
+            Subject::Kanji(kanji) => {
+                let mut readings: Vec<_> = kanji
+                    .readings
+                    .iter()
+                    .filter(|r| r.accepted_answer)
+                    .collect();
+                readings.sort_by_key(|r| !r.primary);
+                (
+                    readings.iter().map(|r| r.reading.as_str()).collect(),
+                    kanji.meaning_hint.as_deref(),
+                    Some(kanji.reading_mnemonic.as_str()),
+                    kanji.reading_hint.as_deref(),
+                )
+            }
+            Subject::Vocabulary(vocabulary) => {
+                let mut readings: Vec<_> = vocabulary
+                    .readings
+                    .iter()
+                    .filter(|r| r.accepted_answer)
+                    .collect();
+                readings.sort_by_key(|r| !r.primary);
+                (
+                    readings.iter().map(|r| r.reading.as_str()).collect(),
+                    None,
+                    Some(vocabulary.reading_mnemonic.as_str()),
+                    None,
+                )
+            }
+            _ => (Vec::new(), None, None, None),
+        };
+
+        let (components, used_in): (&[Id], &[Id]) = match data {
+            Subject::Radical(radical) => (&[], &radical.amalgamation_subject_ids),
+            Subject::Kanji(kanji) => (
+                &kanji.component_subject_ids,
+                &kanji.amalgamation_subject_ids,
+            ),
+            Subject::Vocabulary(vocabulary) => (&vocabulary.component_subject_ids, &[]),
+            Subject::KanaVocabulary(_) => (&[], &[]),
+        };
+        let related = |ids: &[Id]| {
+            let related: Vec<_> = ids
+                .iter()
+                .filter_map(|id| related.get(id).copied())
+                .collect();
+            escape(&related.join(", "))
+        };
+
+        let (sentences, audios): (&[ContextSentence], &[PronunciationAudio]) = match data {
+            Subject::Vocabulary(v) => (&v.context_sentences, &v.pronunciation_audios),
+            Subject::KanaVocabulary(v) => (&v.context_sentences, &v.pronunciation_audios),
+            _ => (&[], &[]),
+        };
+
+        let mut tags = vec![
+            "wanikani".to_string(),
+            format!("level_{}", common.level),
+            data.subject_type().to_string(),
+        ];
+        if material.is_some() {
+            tags.push("study_material".into());
+        }
+
+        let mut row = vec![format!("wanikani-{}", subject.id)];
+        row.extend([
+            subject.id.to_string(),
+            data.subject_type().to_string(),
+            common.level.to_string(),
+            escape(characters(data)),
+            escape(
+                &meanings
+                    .iter()
+                    .map(|m| m.meaning.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+            escape(&readings.join(", ")),
+            self.markup(Some(&common.meaning_mnemonic)),
+            self.markup(meaning_hint),
+            self.markup(reading_mnemonic),
+            self.markup(reading_hint),
+            context_sentences(sentences),
+            related(components),
+            related(used_in),
+            escape(
+                material
+                    .and_then(|m| m.meaning_note.as_deref())
+                    .unwrap_or(""),
+            ),
+            escape(
+                material
+                    .and_then(|m| m.reading_note.as_deref())
+                    .unwrap_or(""),
+            ),
+            escape(&material.map_or(String::new(), |m| m.meaning_synonyms.join(", "))),
+            self.sounds(audios),
+            common.document_url.to_string(),
+            tags.join(" "),
+        ]);
+        row
+    }
+
+    /// Render mnemonic markup as HTML. Markup that fails to parse is included
+    /// as escaped text instead.
+    fn markup(&self, markup: Option<&str>) -> String {
+        let markup = markup.unwrap_or("");
+        match Mnemonic::parse(markup) {
+            Ok(mnemonic) => mnemonic.to_html(&self.classes),
+            Err(_) => escape(markup),
+        }
+    }
+
+    /// `[sound:...]` references for the downloaded audio files, one per voice
+    /// actor.
+    fn sounds(&self, audios: &[PronunciationAudio]) -> String {
+        let dir = match &self.media_dir {
+            Some(dir) => dir,
+            None => return String::new(),
+        };
+
+        let mut voice_actors = Vec::new();
+        let mut sounds = String::new();
+        for audio in audios {
+            if voice_actors.contains(&audio.metadata.voice_actor_id) {
+                continue;
+            }
+            if let Some(name) = downloaded_file_name(dir, audio) {
+                voice_actors.push(audio.metadata.voice_actor_id);
+                sounds.push_str(&format!("[sound:{name}]"));
+            }
+        }
+        sounds
+    }
+}
+
+/// The characters of a subject, or its slug for radicals that are only
+/// available as images.
+fn characters(subject: &Subject) -> &str {
+    match subject {
+        Subject::Radical(radical) => radical
+            .characters
+            .as_deref()
+            .unwrap_or(&radical.common.slug),
+        Subject::Kanji(kanji) => &kanji.characters,
+        Subject::Vocabulary(vocabulary) => &vocabulary.characters,
+        Subject::KanaVocabulary(vocabulary) => &vocabulary.characters,
+    }
+}
+
+fn downloaded_file_name(dir: &Path, audio: &PronunciationAudio) -> Option<String> {
+    let name = audio.file_name()?;
+    dir.join(&name).is_file().then_some(name)
+}
+
+fn context_sentences(sentences: &[ContextSentence]) -> String {
+    sentences
+        .iter()
+        .map(|s| format!("{}<br>{}", escape(&s.ja), escape(&s.en)))
+        .collect::<Vec<_>>()
+        .join("<br><br>")
+}
+
+fn escape(text: &str) -> String {
+    let mut output = String::new();
+    escape_html(text, &mut output);
+    output
+}
+
+/// Make a field safe to put in a tab-separated line. Line breaks become
+/// `<br>`, and fields starting with a quote, which Anki would read as a quoted
+/// field, are quoted.
+fn tsv_field(field: &str) -> String {
+    let field = field
+        .replace("\r\n", "<br>")
+        .replace(['\n', '\r'], "<br>")
+        .replace('\t', " ");
+    if field.starts_with('"') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        study_material::StudyMaterial,
+        subject::{Subject, SubjectType, Vocabulary},
+        Resource,
+    };
+
+    use super::{AnkiExport, FIELDS};
+
+    fn subjects() -> Vec<Resource<Subject>> {
+        [
+            include_str!("../../test_files/radical.json"),
+            include_str!("../../test_files/kanji.json"),
+            include_str!("../../test_files/vocabulary.json"),
+            include_str!("../../test_files/kana_vocabulary.json"),
+        ]
+        .iter()
+        .map(|json| serde_json::from_str(json).expect("Deserialize"))
+        .collect()
+    }
+
+    fn export(export: &AnkiExport) -> (usize, Vec<Vec<String>>) {
+        let mut output = Vec::new();
+        let written = export.write_tsv(&mut output).expect("Exported");
+        let output = String::from_utf8(output).expect("UTF-8");
+        let rows = output
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .map(|line| line.split('\t').map(String::from).collect())
+            .collect();
+        (written, rows)
+    }
+
+    #[test]
+    fn test_export() {
+        let subjects = subjects();
+        let materials: Vec<Resource<StudyMaterial>> =
+            vec![
+                serde_json::from_str(include_str!("../../test_files/study_material.json"))
+                    .expect("Deserialize"),
+            ];
+        let mut material_subject = subjects[1].clone();
+        material_subject.id = materials[0].data.subject_id;
+        let subjects = [subjects, vec![material_subject]].concat();
+
+        let mut output = Vec::new();
+        AnkiExport::new(&subjects)
+            .with_study_materials(&materials)
+            .with_deck("Japanese::WaniKani")
+            .write_tsv(&mut output)
+            .expect("Exported");
+        let output = String::from_utf8(output).expect("UTF-8");
+        assert!(output.starts_with("#separator:tab\n#html:true\n#notetype:WaniKani\n"));
+        assert!(output.contains("#deck:Japanese::WaniKani\n"));
+        assert!(output.contains(&format!("#tags column:{}\n", FIELDS.len() + 2)));
+
+        let (written, rows) = export(&AnkiExport::new(&subjects).with_study_materials(&materials));
+        assert_eq!(written, 5);
+        assert!(rows.iter().all(|row| row.len() == FIELDS.len() + 2));
+
+        let kanji = &rows[1];
+        assert_eq!(kanji[0], "wanikani-440");
+        assert_eq!(kanji[2], "kanji");
+        assert_eq!(kanji[4], "一");
+        assert_eq!(kanji[5], "One");
+        assert!(kanji[6].starts_with("いち"));
+        assert!(kanji[7].contains("<span class=\"radical-highlight\">ground</span>"));
+        assert_eq!(kanji[12], "一");
+        assert_eq!(kanji[19], "wanikani level_1 kanji");
+
+        let vocabulary = &rows[2];
+        assert_eq!(vocabulary[12], "一");
+        assert!(vocabulary[11].contains("<br>"));
+        assert!(vocabulary[17].is_empty());
+
+        let with_material = &rows[4];
+        let material = &materials[0].data;
+        assert_eq!(
+            with_material[14],
+            material.meaning_note.clone().unwrap_or_default()
+        );
+        assert_eq!(with_material[16], material.meaning_synonyms.join(", "));
+        assert!(with_material[19].ends_with(" study_material"));
+    }
+
+    #[test]
+    fn test_filters() {
+        let subjects = subjects();
+
+        let (written, rows) =
+            export(&AnkiExport::new(&subjects).with_types(vec![SubjectType::Kanji]));
+        assert_eq!(written, 1);
+        // The radical is still shown as a component of the kanji.
+        assert_eq!(rows[0][12], "一");
+
+        let (written, _) = export(&AnkiExport::new(&subjects).with_levels(vec![2, 3]));
+        assert_eq!(written, 0);
+    }
+
+    #[test]
+    fn test_audio() {
+        let subjects = subjects();
+        let dir = std::env::temp_dir().join(format!("wanikani-anki-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("Media directory");
+
+        let vocabulary: Vocabulary = subjects[2].data.clone().try_into().expect("Vocabulary");
+        let downloaded = vocabulary
+            .pronunciation_audios
+            .iter()
+            .find(|audio| audio.content_type.essence_str() == "audio/mpeg")
+            .expect("MP3 audio");
+        let name = downloaded.file_name().expect("File name");
+        std::fs::write(dir.join(&name), b"ID3").expect("Audio file");
+
+        let (_, rows) = export(
+            &AnkiExport::new(&subjects)
+                .with_types(vec![SubjectType::Vocabulary])
+                .with_media_dir(&dir),
+        );
+        assert_eq!(rows[0][17], format!("[sound:{name}]"));
+
+        std::fs::remove_dir_all(dir).expect("Cleaned up");
+    }
+}
@@ -0,0 +1,5 @@
+//! Exporting WaniKani data to other applications.
+//!
+//! - [`anki`]: Anki-importable text files of subjects and study materials.
+
+pub mod anki;
@@ -0,0 +1,415 @@
+//! Projection of upcoming reviews from a user's assignments.
+//!
+//! The summary report only covers the next 24 hours of reviews. A
+//! [`Forecast`] is computed locally from [`Assignment`]s and the intervals of
+//! their [`SpacedRepetitionSystem`], so it can cover any horizon up to
+//! [`MAX_HORIZON_DAYS`] without further requests.
+//!
+//! Reviews are counted in hourly and daily buckets, each split by subject type
+//! and by the SRS stage the assignment is at when it is reviewed. Reviews that
+//! are already available are counted in the first bucket.
+//!
+//! By default, each assignment is counted once, at its `available_at`. With
+//! [`ForecastOptions::simulate_correct`] set, every review is assumed to be
+//! answered correctly as soon as it becomes available, so the reviews that
+//! follow within the horizon are counted too. This gives an upper bound on the
+//! workload of the coming days.
+//!
+//! ### Example
+//! ```rust
+//! # use wanikani_api::{
+//! #     assignment::Assignment,
+//! #     forecast::{Forecast, ForecastOptions},
+//! #     spaced_repetition_system::SpacedRepetitionSystem,
+//! #     Resource,
+//! # };
+//! # use chrono::{Duration, TimeZone, Utc};
+//! # let srs: Resource<SpacedRepetitionSystem> = serde_json::from_str(
+//! #     include_str!("../test_files/spaced_repetition_system.json"),
+//! # ).unwrap();
+//! # let mut assignment: Resource<Assignment> =
+//! #     serde_json::from_str(include_str!("../test_files/assignment.json")).unwrap();
+//! # assignment.data.srs_stage = wanikani_api::assignment::SrsStage::Apprentice1;
+//! let start = Utc.with_ymd_and_hms(2018, 2, 26, 0, 0, 0).unwrap();
+//! let mut options = ForecastOptions::new(start, Duration::days(30));
+//! options.simulate_correct = true;
+//!
+//! let forecast = Forecast::new([&assignment.data], &srs.data, &options);
+//! assert_eq!(forecast.daily.len(), 30);
+//! assert_eq!(forecast.total(), 6);
+//! ```
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, DurationRound, FixedOffset, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assignment::Assignment, cross_feature::SubjectType,
+    spaced_repetition_system::SpacedRepetitionSystem, Timestamp,
+};
+
+/// The longest horizon a forecast covers, in days. Longer horizons are
+/// shortened to this, which bounds the number of buckets.
+pub const MAX_HORIZON_DAYS: i64 = 366;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Settings for computing a [`Forecast`].
+pub struct ForecastOptions {
+    /// The time the forecast starts at, usually the current time.
+    pub start: Timestamp,
+    /// How far past `start` the forecast extends, up to
+    /// [`MAX_HORIZON_DAYS`].
+    pub horizon: Duration,
+    /// The user's offset from UTC, used to decide where days begin. Defaults
+    /// to UTC.
+    pub utc_offset: FixedOffset,
+    /// Whether to assume every review is answered correctly, and count the
+    /// reviews that follow. Defaults to `false`.
+    pub simulate_correct: bool,
+}
+
+impl ForecastOptions {
+    /// Options for a forecast of `horizon` from `start`, in UTC, without
+    /// simulating reviews.
+    pub fn new(start: Timestamp, horizon: Duration) -> Self {
+        Self {
+            start,
+            horizon,
+            utc_offset: FixedOffset::east_opt(0).expect("Valid offset"),
+            simulate_correct: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq)]
+/// The reviews becoming available during one hour or day.
+pub struct ForecastBucket {
+    /// When the bucket starts.
+    pub starts_at: Timestamp,
+    /// The total number of reviews.
+    pub total: u32,
+    /// The number of reviews for each type of subject.
+    pub by_subject_type: BTreeMap<SubjectType, u32>,
+    /// The number of reviews at each SRS stage `position`.
+    pub by_srs_stage: BTreeMap<u32, u32>,
+}
+
+impl ForecastBucket {
+    fn new(starts_at: Timestamp) -> Self {
+        Self {
+            starts_at,
+            ..Self::default()
+        }
+    }
+
+    fn add(&mut self, subject_type: SubjectType, srs_stage: u32) {
+        self.total += 1;
+        *self.by_subject_type.entry(subject_type).or_default() += 1;
+        *self.by_srs_stage.entry(srs_stage).or_default() += 1;
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+/// Upcoming reviews, grouped by hour and by day.
+///
+/// See the [module documentation](self) for how reviews are counted.
+pub struct Forecast {
+    /// When the forecast starts.
+    pub start: Timestamp,
+    /// When the forecast ends. Reviews from this time on are not counted.
+    pub end: Timestamp,
+    /// Reviews by hour, starting at the top of the hour containing `start`.
+    pub hourly: Vec<ForecastBucket>,
+    /// Reviews by day, starting at the beginning of the day containing
+    /// `start`.
+    pub daily: Vec<ForecastBucket>,
+    #[serde(skip)]
+    simulate_correct: bool,
+}
+
+impl Forecast {
+    /// Forecast the reviews of `assignments`, which all use `system`.
+    pub fn new<'a>(
+        assignments: impl IntoIterator<Item = &'a Assignment>,
+        system: &SpacedRepetitionSystem,
+        options: &ForecastOptions,
+    ) -> Self {
+        let mut forecast = Self::empty(options);
+        for assignment in assignments {
+            forecast.add(assignment, system);
+        }
+        forecast
+    }
+
+    /// A forecast without any reviews, to which assignments can be added with
+    /// [`add`](Self::add). This is useful when assignments use different
+    /// spaced repetition systems.
+    pub fn empty(options: &ForecastOptions) -> Self {
+        let start = options.start;
+        let horizon = options
+            .horizon
+            .clamp(Duration::zero(), Duration::days(MAX_HORIZON_DAYS));
+        let end = start
+            .checked_add_signed(horizon)
+            .unwrap_or(DateTime::<Utc>::MAX_UTC);
+
+        let first_hour = top_of_hour(start);
+        let hours = buckets_between(first_hour, end, Duration::hours(1));
+        let hourly = (0..hours)
+            .map(|h| ForecastBucket::new(first_hour + Duration::hours(h)))
+            .collect();
+
+        let first_day = start_of_day(start, options.utc_offset);
+        let days = buckets_between(first_day, end, Duration::days(1));
+        let daily = (0..days)
+            .map(|d| ForecastBucket::new(first_day + Duration::days(d)))
+            .collect();
+
+        Self {
+            start,
+            end,
+            hourly,
+            daily,
+            simulate_correct: options.simulate_correct,
+        }
+    }
+
+    /// Add the reviews of `assignment`, which uses `system`.
+    ///
+    /// Assignments that are hidden, burned, or have not been started are
+    /// skipped, as they have no reviews.
+    pub fn add(&mut self, assignment: &Assignment, system: &SpacedRepetitionSystem) {
+        if assignment.hidden
+            || system.is_burned(assignment.srs_stage.position())
+            || assignment.srs_stage.position() <= system.unlocking_stage_position
+        {
+            return;
+        }
+        let available_at = match assignment.available_at {
+            Some(available_at) => available_at.max(self.start),
+            None => return,
+        };
+
+        let mut srs_stage = assignment.srs_stage.position();
+        let mut at = available_at;
+        while at < self.end {
+            self.record(at, assignment.subject_type, srs_stage);
+            if !self.simulate_correct {
+                break;
+            }
+
+            srs_stage += 1;
+            if system.is_burned(srs_stage) {
+                break;
+            }
+            match system
+                .stage(srs_stage)
+                .and_then(|stage| stage.interval_duration())
+                .and_then(|interval| at.checked_add_signed(interval))
+            {
+                Some(next) => at = top_of_hour(next),
+                None => break,
+            }
+        }
+    }
+
+    /// The total number of reviews in the forecast.
+    pub fn total(&self) -> u32 {
+        self.daily.iter().map(|bucket| bucket.total).sum()
+    }
+
+    fn record(&mut self, at: Timestamp, subject_type: SubjectType, srs_stage: u32) {
+        for buckets in [&mut self.hourly, &mut self.daily] {
+            // Buckets are sorted, so the review belongs in the last one
+            // starting at or before it.
+            let index = buckets.partition_point(|bucket| bucket.starts_at <= at);
+            if let Some(bucket) = index.checked_sub(1).and_then(|i| buckets.get_mut(i)) {
+                bucket.add(subject_type, srs_stage);
+            }
+        }
+    }
+}
+
+fn top_of_hour(time: Timestamp) -> Timestamp {
+    time.duration_trunc(Duration::hours(1)).unwrap_or(time)
+}
+
+fn start_of_day(time: Timestamp, offset: FixedOffset) -> Timestamp {
+    let midnight = time
+        .with_timezone(&offset)
+        .date_naive()
+        .and_time(NaiveTime::MIN);
+    offset
+        .from_local_datetime(&midnight)
+        .single()
+        .map_or(time, |start| start.with_timezone(&Utc))
+}
+
+/// The number of buckets of length `size`, starting at `first`, needed to
+/// cover everything before `end`.
+fn buckets_between(first: DateTime<Utc>, end: DateTime<Utc>, size: Duration) -> i64 {
+    let span = (end - first).num_seconds().max(0);
+    let size = size.num_seconds();
+    (span + size - 1) / size
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, FixedOffset, TimeZone, Utc};
+
+    use crate::{
+        assignment::Assignment, cross_feature::SubjectType,
+        spaced_repetition_system::SpacedRepetitionSystem, Resource, Timestamp,
+    };
+
+    use super::{Forecast, ForecastOptions, MAX_HORIZON_DAYS};
+
+    fn system() -> SpacedRepetitionSystem {
+        let json = include_str!("../test_files/spaced_repetition_system.json");
+        let srs: Resource<SpacedRepetitionSystem> =
+            serde_json::from_str(json).expect("Deserialize");
+        srs.data
+    }
+
+    fn assignment(
+        subject_type: SubjectType,
+        srs_stage: u32,
+        available_at: Option<Timestamp>,
+    ) -> Assignment {
+        let created_at = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        Assignment {
+            available_at,
+            burned_at: None,
+            created_at,
+            hidden: false,
+            passed_at: None,
+            resurrected_at: None,
+            srs_stage: srs_stage.try_into().expect("SRS stage"),
+            started_at: Some(created_at),
+            subject_id: 1,
+            subject_type,
+            unlocked_at: Some(created_at),
+        }
+    }
+
+    fn start() -> Timestamp {
+        Utc.with_ymd_and_hms(2023, 6, 1, 10, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn test_buckets() {
+        let start = start();
+        let assignments = [
+            // Already available, so counted in the first bucket.
+            assignment(SubjectType::Radical, 1, Some(start - Duration::days(2))),
+            assignment(SubjectType::Kanji, 2, Some(start + Duration::minutes(90))),
+            assignment(SubjectType::Kanji, 4, Some(start + Duration::hours(20))),
+            // Outside the horizon.
+            assignment(SubjectType::Vocabulary, 5, Some(start + Duration::days(3))),
+            // Not started, burned and hidden.
+            assignment(SubjectType::Vocabulary, 0, None),
+            assignment(SubjectType::Vocabulary, 9, None),
+            Assignment {
+                hidden: true,
+                ..assignment(SubjectType::Vocabulary, 3, Some(start))
+            },
+        ];
+
+        let forecast = Forecast::new(
+            &assignments,
+            &system(),
+            &ForecastOptions::new(start, Duration::days(2)),
+        );
+
+        assert_eq!(forecast.hourly.len(), 49);
+        assert_eq!(
+            forecast.hourly[0].starts_at,
+            Utc.with_ymd_and_hms(2023, 6, 1, 10, 0, 0).unwrap()
+        );
+        assert_eq!(forecast.hourly[0].total, 1);
+        assert_eq!(forecast.hourly[1].total, 0);
+        assert_eq!(forecast.hourly[2].total, 1);
+        assert_eq!(forecast.hourly[20].by_srs_stage[&4], 1);
+
+        assert_eq!(forecast.daily.len(), 3);
+        assert_eq!(forecast.daily[0].total, 2);
+        assert_eq!(forecast.daily[0].by_subject_type[&SubjectType::Kanji], 1);
+        assert_eq!(forecast.daily[1].total, 1);
+        assert_eq!(forecast.total(), 3);
+    }
+
+    #[test]
+    fn test_simulate_correct() {
+        let start = start();
+        let mut options = ForecastOptions::new(start, Duration::days(30));
+        options.simulate_correct = true;
+
+        let forecast = Forecast::new(
+            &[assignment(SubjectType::Kanji, 1, Some(start))],
+            &system(),
+            &options,
+        );
+
+        // Apprentice I through Guru II fit within 30 days, each reviewed at
+        // the top of the hour after its interval has passed.
+        let reviews: Vec<_> = forecast
+            .hourly
+            .iter()
+            .filter(|bucket| bucket.total > 0)
+            .map(|bucket| {
+                let stage = *bucket.by_srs_stage.keys().next().expect("Stage");
+                ((bucket.starts_at - start).num_hours(), stage)
+            })
+            .collect();
+        assert_eq!(
+            reviews,
+            [(0, 1), (7, 2), (30, 3), (77, 4), (244, 5), (579, 6)]
+        );
+        assert_eq!(forecast.total(), 6);
+
+        let serialized = serde_json::to_value(&forecast).expect("Serialize");
+        assert_eq!(serialized["daily"][0]["by_subject_type"]["kanji"], 2);
+    }
+
+    #[test]
+    fn test_utc_offset() {
+        let start = start();
+        let mut options = ForecastOptions::new(start, Duration::days(1));
+        options.utc_offset = FixedOffset::east_opt(9 * 3600).expect("Offset");
+
+        let forecast = Forecast::new(
+            &[assignment(
+                SubjectType::Radical,
+                1,
+                Some(start + Duration::hours(5)),
+            )],
+            &system(),
+            &options,
+        );
+
+        // 10:30 UTC is 19:30 in Japan, so the review five hours later falls
+        // on the next local day.
+        assert_eq!(
+            forecast.daily[0].starts_at,
+            Utc.with_ymd_and_hms(2023, 5, 31, 15, 0, 0).unwrap()
+        );
+        assert_eq!(forecast.daily.len(), 2);
+        assert_eq!(forecast.daily[0].total, 0);
+        assert_eq!(forecast.daily[1].total, 1);
+    }
+
+    #[test]
+    fn test_max_horizon() {
+        let start = start();
+        let forecast = Forecast::empty(&ForecastOptions::new(start, Duration::MAX));
+        assert_eq!(forecast.end, start + Duration::days(MAX_HORIZON_DAYS));
+        assert_eq!(forecast.daily.len() as i64, MAX_HORIZON_DAYS + 1);
+        assert_eq!(forecast.hourly.len() as i64, MAX_HORIZON_DAYS * 24 + 1);
+
+        let latest = Timestamp::MAX_UTC;
+        let forecast = Forecast::empty(&ForecastOptions::new(latest, Duration::days(1)));
+        assert_eq!(forecast.end, latest);
+        assert!(forecast.hourly.is_empty());
+    }
+}
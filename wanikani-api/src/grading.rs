@@ -0,0 +1,444 @@
+//! Checking answers to meaning and reading questions, the way WaniKani does
+//! during lessons and reviews.
+//!
+//! An [`AnswerChecker`] grades answers for one subject. Meaning answers are
+//! compared against the subject's accepted meanings, its whitelisted
+//! auxiliary meanings, and any synonyms the user has added in their study
+//! materials. Small typos are tolerated, with longer meanings allowing more
+//! mistakes. Blacklisted auxiliary meanings, which are common wrong answers,
+//! are always rejected.
+//!
+//! Reading answers must match an accepted reading exactly, after romaji and
+//! katakana are converted to hiragana with [`to_hiragana`]. Answering a kanji
+//! with one of its readings that is not accepted, such as its on'yomi when
+//! the kun'yomi is being asked for, gives
+//! [`AnswerResult::WrongReadingType`]. WaniKani does not count this as a
+//! wrong answer, and asks the user to try again instead.
+//!
+//! ### Example
+//! ```rust
+//! # use wanikani_api::{grading::{AnswerChecker, AnswerResult}, subject::Subject, Resource};
+//! # let kanji: Resource<Subject> =
+//! #     serde_json::from_str(include_str!("../test_files/kanji.json")).unwrap();
+//! # let subject = kanji.data;
+//! let synonyms = vec!["single".to_string()];
+//! let checker = AnswerChecker::new(&subject).with_synonyms(&synonyms);
+//!
+//! assert_eq!(checker.check_meaning("One"), AnswerResult::Correct);
+//! assert_eq!(checker.check_meaning("single"), AnswerResult::Correct);
+//! assert_eq!(checker.check_reading("いち"), AnswerResult::Correct);
+//! assert_eq!(checker.check_reading("に"), AnswerResult::Incorrect);
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "study_material")]
+use crate::study_material::StudyMaterial;
+use crate::{
+    kana::to_hiragana,
+    subject::{KanjiReadingType, MeaningType, Subject},
+};
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+/// The kind of question being asked about a subject.
+pub enum QuestionType {
+    /// The user is asked for the subject's meaning, in English.
+    Meaning,
+    /// The user is asked for the subject's reading, in hiragana.
+    Reading,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The result of checking an answer.
+pub enum AnswerResult {
+    /// The answer matched an accepted answer exactly.
+    Correct,
+    /// The answer is close enough to an accepted meaning to be counted as
+    /// correct, but contains a typo. Contains the meaning that was matched,
+    /// which is typically shown to the user.
+    CloseEnough(String),
+    /// The answer is wrong.
+    Incorrect,
+    /// The answer is a reading of the kanji, but not one that is accepted.
+    /// This should not be counted as a wrong answer; the user should be asked
+    /// to answer again.
+    WrongReadingType {
+        /// The type of reading that was given.
+        given: KanjiReadingType,
+        /// The type of the kanji's primary reading, which is the type being
+        /// asked for.
+        expected: KanjiReadingType,
+    },
+}
+
+impl AnswerResult {
+    /// Whether the answer should be counted as correct.
+    pub fn is_correct(&self) -> bool {
+        matches!(self, Self::Correct | Self::CloseEnough(_))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+/// Checks answers for a single subject.
+///
+/// See the [module documentation](self) for the rules used.
+pub struct AnswerChecker<'a> {
+    subject: &'a Subject,
+    synonyms: &'a [String],
+}
+
+impl<'a> AnswerChecker<'a> {
+    /// Create a checker for `subject`, with no user synonyms.
+    pub fn new(subject: &'a Subject) -> Self {
+        Self {
+            subject,
+            synonyms: &[],
+        }
+    }
+
+    /// Also accept the user's own meaning synonyms.
+    pub fn with_synonyms(self, synonyms: &'a [String]) -> Self {
+        Self { synonyms, ..self }
+    }
+
+    /// Also accept the meaning synonyms from the user's study material for
+    /// this subject.
+    #[cfg(feature = "study_material")]
+    pub fn with_study_material(self, material: &'a StudyMaterial) -> Self {
+        self.with_synonyms(&material.meaning_synonyms)
+    }
+
+    /// Whether the subject has reading questions. Radicals and kana-only
+    /// vocabulary are only quizzed on their meaning.
+    pub fn has_reading_question(&self) -> bool {
+        matches!(self.subject, Subject::Kanji(_) | Subject::Vocabulary(_))
+    }
+
+    /// Check an answer to a question of the given type.
+    pub fn check(&self, question: QuestionType, answer: &str) -> AnswerResult {
+        match question {
+            QuestionType::Meaning => self.check_meaning(answer),
+            QuestionType::Reading => self.check_reading(answer),
+        }
+    }
+
+    /// Check an answer to a meaning question.
+    pub fn check_meaning(&self, answer: &str) -> AnswerResult {
+        let answer = normalize_meaning(answer);
+        if answer.is_empty() {
+            return AnswerResult::Incorrect;
+        }
+
+        let common = self.subject.common();
+        let accepted: Vec<&str> = common
+            .meanings
+            .iter()
+            .filter(|m| m.accepted_answer)
+            .map(|m| m.meaning.as_str())
+            .chain(
+                common
+                    .auxiliary_meanings
+                    .iter()
+                    .filter(|m| m.meaning_type == MeaningType::Whitelist)
+                    .map(|m| m.meaning.as_str()),
+            )
+            .chain(self.synonyms.iter().map(String::as_str))
+            .collect();
+
+        if accepted.iter().any(|m| normalize_meaning(m) == answer) {
+            return AnswerResult::Correct;
+        }
+
+        let rejected: Vec<&str> = common
+            .meanings
+            .iter()
+            .filter(|m| !m.accepted_answer)
+            .map(|m| m.meaning.as_str())
+            .chain(
+                common
+                    .auxiliary_meanings
+                    .iter()
+                    .filter(|m| m.meaning_type == MeaningType::Blacklist)
+                    .map(|m| m.meaning.as_str()),
+            )
+            .collect();
+
+        let closest_accepted = closest_meaning(&accepted, &answer);
+        let closest_rejected = closest_meaning(&rejected, &answer).map(|(_, distance)| distance);
+
+        // An answer at least as close to a rejected meaning as to any accepted
+        // one is a misspelling of the rejected meaning, not a typo.
+        match (closest_accepted, closest_rejected) {
+            (Some((_, accepted)), Some(rejected)) if rejected <= accepted => {
+                AnswerResult::Incorrect
+            }
+            (Some((meaning, _)), _) => AnswerResult::CloseEnough(meaning.to_string()),
+            (None, _) => AnswerResult::Incorrect,
+        }
+    }
+
+    /// Check an answer to a reading question.
+    ///
+    /// Subjects without a reading question, as reported by
+    /// [`has_reading_question`](Self::has_reading_question), never accept a
+    /// reading.
+    pub fn check_reading(&self, answer: &str) -> AnswerResult {
+        let answer = normalize_reading(answer);
+        if answer.is_empty() {
+            return AnswerResult::Incorrect;
+        }
+
+        match self.subject {
+            Subject::Kanji(kanji) => {
+                let matching = kanji
+                    .readings
+                    .iter()
+                    .filter(|r| normalize_reading(&r.reading) == answer);
+
+                let mut wrong_type = None;
+                for reading in matching {
+                    if reading.accepted_answer {
+                        return AnswerResult::Correct;
+                    }
+                    wrong_type.get_or_insert(reading.reading_type);
+                }
+
+                let expected = kanji
+                    .readings
+                    .iter()
+                    .find(|r| r.primary)
+                    .or_else(|| kanji.readings.iter().find(|r| r.accepted_answer))
+                    .map(|r| r.reading_type);
+
+                match (wrong_type, expected) {
+                    (Some(given), Some(expected)) if given != expected => {
+                        AnswerResult::WrongReadingType { given, expected }
+                    }
+                    _ => AnswerResult::Incorrect,
+                }
+            }
+            Subject::Vocabulary(vocabulary) => {
+                let correct = vocabulary
+                    .readings
+                    .iter()
+                    .filter(|r| r.accepted_answer)
+                    .any(|r| normalize_reading(&r.reading) == answer);
+
+                if correct {
+                    AnswerResult::Correct
+                } else {
+                    AnswerResult::Incorrect
+                }
+            }
+            Subject::Radical(_) | Subject::KanaVocabulary(_) => AnswerResult::Incorrect,
+        }
+    }
+}
+
+/// The meaning in `meanings` closest to the normalized `answer`, and its edit
+/// distance, if any is within the typo tolerance.
+fn closest_meaning<'m>(meanings: &[&'m str], answer: &str) -> Option<(&'m str, usize)> {
+    meanings
+        .iter()
+        .map(|meaning| {
+            let normalized = normalize_meaning(meaning);
+            (*meaning, edit_distance(&normalized, answer), normalized)
+        })
+        .filter(|(_, distance, normalized)| *distance <= typo_tolerance(normalized.chars().count()))
+        .min_by_key(|(_, distance, _)| *distance)
+        .map(|(meaning, distance, _)| (meaning, distance))
+}
+
+/// The number of typos allowed in an answer to a meaning of `length`
+/// characters.
+fn typo_tolerance(length: usize) -> usize {
+    match length {
+        0..=3 => 0,
+        4..=5 => 1,
+        6..=7 => 2,
+        _ => 2 + length / 7,
+    }
+}
+
+fn normalize_meaning(meaning: &str) -> String {
+    meaning
+        .chars()
+        .map(|c| if c == '-' { ' ' } else { c })
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn normalize_reading(reading: &str) -> String {
+    let reading: String = reading.chars().filter(|c| !c.is_whitespace()).collect();
+    to_hiragana(&reading)
+}
+
+/// The Levenshtein distance between `a` and `b`, in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        subject::{AuxilliaryMeaning, KanjiReadingType, Meaning, MeaningType, Subject},
+        Resource,
+    };
+
+    use super::{edit_distance, typo_tolerance, AnswerChecker, AnswerResult, QuestionType};
+
+    fn subject(json: &str) -> Subject {
+        let resource: Resource<Subject> = serde_json::from_str(json).expect("Deserialize");
+        resource.data
+    }
+
+    #[test]
+    fn test_meaning() {
+        let mut kanji = subject(include_str!("../test_files/kanji.json"));
+        if let Subject::Kanji(ref mut kanji) = kanji {
+            kanji.common.meanings.push(Meaning {
+                meaning: "Singular Thing".into(),
+                primary: false,
+                accepted_answer: true,
+            });
+            kanji.common.meanings.push(Meaning {
+                meaning: "Uno".into(),
+                primary: false,
+                accepted_answer: false,
+            });
+            kanji.common.auxiliary_meanings.push(AuxilliaryMeaning {
+                meaning: "Singular Think".into(),
+                meaning_type: MeaningType::Blacklist,
+            });
+        }
+        let synonyms = vec!["Lone".to_string()];
+        let checker = AnswerChecker::new(&kanji).with_synonyms(&synonyms);
+
+        assert_eq!(checker.check_meaning("one"), AnswerResult::Correct);
+        assert_eq!(checker.check_meaning("  ONE "), AnswerResult::Correct);
+        assert_eq!(checker.check_meaning("flat"), AnswerResult::Correct);
+        assert_eq!(checker.check_meaning("lone"), AnswerResult::Correct);
+        assert_eq!(
+            checker.check_meaning("singular-thing"),
+            AnswerResult::Correct
+        );
+        assert_eq!(checker.check_meaning("uno"), AnswerResult::Incorrect);
+        assert_eq!(checker.check_meaning(""), AnswerResult::Incorrect);
+
+        // Too short for a typo to be tolerated
+        assert_eq!(checker.check_meaning("onr"), AnswerResult::Incorrect);
+        assert_eq!(
+            checker.check_meaning("lonr"),
+            AnswerResult::CloseEnough("Lone".into())
+        );
+        assert_eq!(
+            checker.check_meaning("singlar thing"),
+            AnswerResult::CloseEnough("Singular Thing".into())
+        );
+        // Within the tolerance, but blacklisted
+        assert_eq!(
+            checker.check_meaning("singular think"),
+            AnswerResult::Incorrect
+        );
+        // A misspelling of a blacklisted meaning, closer to it than to the
+        // accepted one
+        assert_eq!(
+            checker.check_meaning("singular thnk"),
+            AnswerResult::Incorrect
+        );
+        // As close to the blacklisted meaning as to the accepted one
+        assert_eq!(
+            checker.check_meaning("singlar thinh"),
+            AnswerResult::Incorrect
+        );
+        assert_eq!(checker.check_meaning("plural"), AnswerResult::Incorrect);
+
+        assert!(AnswerResult::CloseEnough("One".into()).is_correct());
+        assert!(!AnswerResult::Incorrect.is_correct());
+    }
+
+    #[test]
+    fn test_kanji_reading() {
+        let kanji = subject(include_str!("../test_files/kanji.json"));
+        let checker = AnswerChecker::new(&kanji);
+
+        assert!(checker.has_reading_question());
+        assert_eq!(
+            checker.check(QuestionType::Reading, "いち"),
+            AnswerResult::Correct
+        );
+        assert_eq!(checker.check_reading("イチ"), AnswerResult::Correct);
+        assert_eq!(
+            checker.check_reading("ひと"),
+            AnswerResult::WrongReadingType {
+                given: KanjiReadingType::Kunyomi,
+                expected: KanjiReadingType::Onyomi,
+            }
+        );
+        assert_eq!(checker.check_reading("いつ"), AnswerResult::Incorrect);
+        assert_eq!(checker.check_reading("ichi"), AnswerResult::Correct);
+        assert_eq!(checker.check_reading("ｲﾁ"), AnswerResult::Correct);
+        assert_eq!(
+            checker.check_reading("kazu"),
+            AnswerResult::WrongReadingType {
+                given: KanjiReadingType::Nanori,
+                expected: KanjiReadingType::Onyomi,
+            }
+        );
+    }
+
+    #[test]
+    fn test_vocabulary_reading() {
+        let vocabulary = subject(include_str!("../test_files/vocabulary.json"));
+        let checker = AnswerChecker::new(&vocabulary);
+
+        assert!(checker.has_reading_question());
+        assert_eq!(checker.check_reading("いち"), AnswerResult::Correct);
+        assert_eq!(checker.check_reading("ひと"), AnswerResult::Incorrect);
+        assert_eq!(checker.check_meaning("1"), AnswerResult::Correct);
+
+        let radical = subject(include_str!("../test_files/radical.json"));
+        let checker = AnswerChecker::new(&radical);
+        assert!(!checker.has_reading_question());
+        assert_eq!(checker.check_reading("いち"), AnswerResult::Incorrect);
+        assert_eq!(checker.check_meaning("ground"), AnswerResult::Correct);
+
+        let kana = subject(include_str!("../test_files/kana_vocabulary.json"));
+        let checker = AnswerChecker::new(&kana);
+        assert!(!checker.has_reading_question());
+        assert_eq!(
+            checker.check_meaning("snacc"),
+            AnswerResult::CloseEnough("Snack".into())
+        );
+    }
+
+    #[test]
+    fn test_typo_helpers() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("一つ", "一"), 1);
+
+        assert_eq!(typo_tolerance(3), 0);
+        assert_eq!(typo_tolerance(5), 1);
+        assert_eq!(typo_tolerance(7), 2);
+        assert_eq!(typo_tolerance(14), 4);
+    }
+}
@@ -0,0 +1,542 @@
+//! Conversion between romaji, hiragana and katakana, as done by WaniKani's
+//! answer input field.
+//!
+//! Users type reading answers in romaji, which WaniKani converts to kana as
+//! they type. This module performs the same conversion, so readings can be
+//! compared regardless of how they were entered:
+//!
+//! - `nn` and `n'` become ん, as does `n` before a consonant or at the end of
+//!   the answer.
+//! - A doubled consonant becomes っ, e.g. `kitte` is きって, and `tch` becomes
+//!   っち.
+//! - `-` becomes the long vowel mark ー.
+//! - Full-width ASCII is read as regular ASCII, and half-width katakana is
+//!   read as full-width katakana.
+//!
+//! ### Example
+//! ```rust
+//! # use wanikani_api::kana::{to_hiragana, to_katakana};
+//! assert_eq!(to_hiragana("konnichiha"), "こんにちは");
+//! assert_eq!(to_hiragana("kitte"), "きって");
+//! assert_eq!(to_hiragana("ｶﾀｶﾅ"), "かたかな");
+//! assert_eq!(to_katakana("ro-maji"), "ローマジ");
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+/// A Japanese syllabary.
+pub enum Script {
+    /// ひらがな
+    Hiragana,
+    /// カタカナ
+    Katakana,
+}
+
+/// Romaji sequences and the hiragana they convert to. Longer sequences are
+/// matched first.
+const ROMAJI: &[(&str, &str)] = &[
+    ("a", "あ"),
+    ("i", "い"),
+    ("u", "う"),
+    ("e", "え"),
+    ("o", "お"),
+    ("ka", "か"),
+    ("ki", "き"),
+    ("ku", "く"),
+    ("ke", "け"),
+    ("ko", "こ"),
+    ("kya", "きゃ"),
+    ("kyi", "きぃ"),
+    ("kyu", "きゅ"),
+    ("kye", "きぇ"),
+    ("kyo", "きょ"),
+    ("kwa", "くぁ"),
+    ("ca", "か"),
+    ("cu", "く"),
+    ("co", "こ"),
+    ("qa", "くぁ"),
+    ("qi", "くぃ"),
+    ("qu", "く"),
+    ("qe", "くぇ"),
+    ("qo", "くぉ"),
+    ("ga", "が"),
+    ("gi", "ぎ"),
+    ("gu", "ぐ"),
+    ("ge", "げ"),
+    ("go", "ご"),
+    ("gya", "ぎゃ"),
+    ("gyi", "ぎぃ"),
+    ("gyu", "ぎゅ"),
+    ("gye", "ぎぇ"),
+    ("gyo", "ぎょ"),
+    ("gwa", "ぐぁ"),
+    ("sa", "さ"),
+    ("si", "し"),
+    ("shi", "し"),
+    ("su", "す"),
+    ("se", "せ"),
+    ("so", "そ"),
+    ("sya", "しゃ"),
+    ("syu", "しゅ"),
+    ("sye", "しぇ"),
+    ("syo", "しょ"),
+    ("sha", "しゃ"),
+    ("shu", "しゅ"),
+    ("she", "しぇ"),
+    ("sho", "しょ"),
+    ("ci", "し"),
+    ("ce", "せ"),
+    ("za", "ざ"),
+    ("zi", "じ"),
+    ("zu", "ず"),
+    ("ze", "ぜ"),
+    ("zo", "ぞ"),
+    ("zya", "じゃ"),
+    ("zyu", "じゅ"),
+    ("zye", "じぇ"),
+    ("zyo", "じょ"),
+    ("ja", "じゃ"),
+    ("ji", "じ"),
+    ("ju", "じゅ"),
+    ("je", "じぇ"),
+    ("jo", "じょ"),
+    ("jya", "じゃ"),
+    ("jyu", "じゅ"),
+    ("jye", "じぇ"),
+    ("jyo", "じょ"),
+    ("ta", "た"),
+    ("ti", "ち"),
+    ("tu", "つ"),
+    ("te", "て"),
+    ("to", "と"),
+    ("chi", "ち"),
+    ("tsu", "つ"),
+    ("tya", "ちゃ"),
+    ("tyu", "ちゅ"),
+    ("tye", "ちぇ"),
+    ("tyo", "ちょ"),
+    ("cha", "ちゃ"),
+    ("chu", "ちゅ"),
+    ("che", "ちぇ"),
+    ("cho", "ちょ"),
+    ("cya", "ちゃ"),
+    ("cyu", "ちゅ"),
+    ("cyo", "ちょ"),
+    ("tsa", "つぁ"),
+    ("tsi", "つぃ"),
+    ("tse", "つぇ"),
+    ("tso", "つぉ"),
+    ("tha", "てゃ"),
+    ("thi", "てぃ"),
+    ("thu", "てゅ"),
+    ("the", "てぇ"),
+    ("tho", "てょ"),
+    ("twu", "とぅ"),
+    ("da", "だ"),
+    ("di", "ぢ"),
+    ("du", "づ"),
+    ("de", "で"),
+    ("do", "ど"),
+    ("dya", "ぢゃ"),
+    ("dyu", "ぢゅ"),
+    ("dye", "ぢぇ"),
+    ("dyo", "ぢょ"),
+    ("dha", "でゃ"),
+    ("dhi", "でぃ"),
+    ("dhu", "でゅ"),
+    ("dhe", "でぇ"),
+    ("dho", "でょ"),
+    ("dwu", "どぅ"),
+    ("na", "な"),
+    ("ni", "に"),
+    ("nu", "ぬ"),
+    ("ne", "ね"),
+    ("no", "の"),
+    ("nya", "にゃ"),
+    ("nyi", "にぃ"),
+    ("nyu", "にゅ"),
+    ("nye", "にぇ"),
+    ("nyo", "にょ"),
+    ("ha", "は"),
+    ("hi", "ひ"),
+    ("hu", "ふ"),
+    ("he", "へ"),
+    ("ho", "ほ"),
+    ("hya", "ひゃ"),
+    ("hyi", "ひぃ"),
+    ("hyu", "ひゅ"),
+    ("hye", "ひぇ"),
+    ("hyo", "ひょ"),
+    ("fa", "ふぁ"),
+    ("fi", "ふぃ"),
+    ("fu", "ふ"),
+    ("fe", "ふぇ"),
+    ("fo", "ふぉ"),
+    ("fya", "ふゃ"),
+    ("fyu", "ふゅ"),
+    ("fyo", "ふょ"),
+    ("ba", "ば"),
+    ("bi", "び"),
+    ("bu", "ぶ"),
+    ("be", "べ"),
+    ("bo", "ぼ"),
+    ("bya", "びゃ"),
+    ("byi", "びぃ"),
+    ("byu", "びゅ"),
+    ("bye", "びぇ"),
+    ("byo", "びょ"),
+    ("pa", "ぱ"),
+    ("pi", "ぴ"),
+    ("pu", "ぷ"),
+    ("pe", "ぺ"),
+    ("po", "ぽ"),
+    ("pya", "ぴゃ"),
+    ("pyi", "ぴぃ"),
+    ("pyu", "ぴゅ"),
+    ("pye", "ぴぇ"),
+    ("pyo", "ぴょ"),
+    ("ma", "ま"),
+    ("mi", "み"),
+    ("mu", "む"),
+    ("me", "め"),
+    ("mo", "も"),
+    ("mya", "みゃ"),
+    ("myi", "みぃ"),
+    ("myu", "みゅ"),
+    ("mye", "みぇ"),
+    ("myo", "みょ"),
+    ("ya", "や"),
+    ("yu", "ゆ"),
+    ("ye", "いぇ"),
+    ("yo", "よ"),
+    ("ra", "ら"),
+    ("ri", "り"),
+    ("ru", "る"),
+    ("re", "れ"),
+    ("ro", "ろ"),
+    ("rya", "りゃ"),
+    ("ryi", "りぃ"),
+    ("ryu", "りゅ"),
+    ("rye", "りぇ"),
+    ("ryo", "りょ"),
+    ("la", "ら"),
+    ("li", "り"),
+    ("lu", "る"),
+    ("le", "れ"),
+    ("lo", "ろ"),
+    ("lya", "りゃ"),
+    ("lyu", "りゅ"),
+    ("lyo", "りょ"),
+    ("wa", "わ"),
+    ("wi", "うぃ"),
+    ("wu", "う"),
+    ("we", "うぇ"),
+    ("wo", "を"),
+    ("wha", "うぁ"),
+    ("whi", "うぃ"),
+    ("whu", "う"),
+    ("whe", "うぇ"),
+    ("who", "うぉ"),
+    ("va", "ゔぁ"),
+    ("vi", "ゔぃ"),
+    ("vu", "ゔ"),
+    ("ve", "ゔぇ"),
+    ("vo", "ゔぉ"),
+    ("vya", "ゔゃ"),
+    ("vyu", "ゔゅ"),
+    ("vyo", "ゔょ"),
+    ("xa", "ぁ"),
+    ("xi", "ぃ"),
+    ("xu", "ぅ"),
+    ("xe", "ぇ"),
+    ("xo", "ぉ"),
+    ("xya", "ゃ"),
+    ("xyu", "ゅ"),
+    ("xyo", "ょ"),
+    ("xtu", "っ"),
+    ("xtsu", "っ"),
+    ("xwa", "ゎ"),
+    ("xka", "ゕ"),
+    ("xke", "ゖ"),
+    ("lyi", "ぃ"),
+    ("lye", "ぇ"),
+    ("ltu", "っ"),
+    ("ltsu", "っ"),
+    ("lwa", "ゎ"),
+    ("n'", "ん"),
+    ("nn", "ん"),
+    ("-", "ー"),
+];
+
+/// The longest romaji sequence in [`ROMAJI`].
+const MAX_ROMAJI_LEN: usize = 4;
+
+/// Half-width katakana, from U+FF66 to U+FF9D, as full-width katakana.
+const HALF_WIDTH_KATAKANA: &str =
+    "ヲァィゥェォャュョッーアイウエオカキクケコサシスセソタチツテトナニヌネノハヒフヘホマミムメモヤユヨラリルレロワン";
+
+/// Convert romaji, katakana and half-width text to hiragana.
+///
+/// This is the conversion WaniKani applies to a finished answer, so a
+/// trailing `n` becomes ん. Anything that is not romaji or kana, such as
+/// kanji or punctuation, is left unchanged.
+pub fn to_hiragana(input: &str) -> String {
+    katakana_to_hiragana(&romaji_to_hiragana(&normalize_width(input), true))
+}
+
+/// Convert romaji, hiragana and half-width text to katakana.
+///
+/// This behaves like [`to_hiragana`], but produces katakana.
+pub fn to_katakana(input: &str) -> String {
+    hiragana_to_katakana(&romaji_to_hiragana(&normalize_width(input), true))
+}
+
+/// Convert romaji to kana as it is being typed.
+///
+/// Unlike [`to_hiragana`] and [`to_katakana`], a trailing `n` is left as
+/// romaji, since it may be the start of a syllable such as `na`. Incomplete
+/// syllables are always left as romaji. Existing kana is converted to
+/// `script`.
+///
+/// ```rust
+/// # use wanikani_api::kana::{convert_as_typed, Script};
+/// assert_eq!(convert_as_typed("kan", Script::Hiragana), "かn");
+/// assert_eq!(convert_as_typed("kann", Script::Hiragana), "かん");
+/// assert_eq!(convert_as_typed("kanj", Script::Katakana), "カンj");
+/// ```
+pub fn convert_as_typed(input: &str, script: Script) -> String {
+    let hiragana = romaji_to_hiragana(&normalize_width(input), false);
+    match script {
+        Script::Hiragana => katakana_to_hiragana(&hiragana),
+        Script::Katakana => hiragana_to_katakana(&hiragana),
+    }
+}
+
+/// Convert katakana to hiragana, leaving everything else unchanged.
+///
+/// Katakana with no hiragana equivalent, such as ヷ, and the long vowel mark
+/// ー are left as they are.
+pub fn katakana_to_hiragana(input: &str) -> String {
+    input
+        .chars()
+        .map(|c| match c {
+            'ァ'..='ヶ' | 'ヽ' | 'ヾ' => shift(c, -0x60),
+            _ => c,
+        })
+        .collect()
+}
+
+/// Convert hiragana to katakana, leaving everything else unchanged.
+pub fn hiragana_to_katakana(input: &str) -> String {
+    input
+        .chars()
+        .map(|c| match c {
+            'ぁ'..='ゖ' | 'ゝ' | 'ゞ' => shift(c, 0x60),
+            _ => c,
+        })
+        .collect()
+}
+
+/// Convert full-width ASCII characters to regular ASCII, and half-width
+/// katakana to full-width katakana.
+///
+/// Half-width voicing marks are combined with the preceding kana where
+/// possible, so `ｶﾞ` becomes ガ.
+pub fn normalize_width(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+
+    for c in input.chars() {
+        match c {
+            '！'..='～' => output.push(shift(c, -0xFEE0)),
+            '\u{3000}' => output.push(' '),
+            'ｦ'..='ﾝ' => output.push(
+                HALF_WIDTH_KATAKANA
+                    .chars()
+                    .nth(c as usize - 'ｦ' as usize)
+                    .unwrap_or(c),
+            ),
+            'ﾞ' => match output.pop() {
+                Some(prev) if "カキクケコサシスセソタチツテトハヒフヘホ".contains(prev) => {
+                    output.push(shift(prev, 1))
+                }
+                Some('ウ') => output.push('ヴ'),
+                prev => {
+                    output.extend(prev);
+                    output.push('゛');
+                }
+            },
+            'ﾟ' => match output.pop() {
+                Some(prev) if "ハヒフヘホ".contains(prev) => output.push(shift(prev, 2)),
+                prev => {
+                    output.extend(prev);
+                    output.push('゜');
+                }
+            },
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+/// Whether `c` is hiragana, including the small kana and iteration marks.
+pub fn is_hiragana(c: char) -> bool {
+    matches!(c, 'ぁ'..='ゖ' | 'ゝ' | 'ゞ')
+}
+
+/// Whether `c` is full-width katakana, including the small kana, iteration
+/// marks and the long vowel mark ー.
+pub fn is_katakana(c: char) -> bool {
+    matches!(c, 'ァ'..='ヺ' | 'ー' | 'ヽ' | 'ヾ')
+}
+
+/// Whether `text` is made up entirely of hiragana and katakana.
+pub fn is_kana(text: &str) -> bool {
+    text.chars().all(|c| is_hiragana(c) || is_katakana(c))
+}
+
+fn shift(c: char, offset: i32) -> char {
+    u32::try_from(i64::from(u32::from(c)) + i64::from(offset))
+        .ok()
+        .and_then(char::from_u32)
+        .unwrap_or(c)
+}
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'i' | 'u' | 'e' | 'o')
+}
+
+fn romaji_to_hiragana(input: &str, finished: bool) -> String {
+    let chars: Vec<char> = input.chars().flat_map(char::to_lowercase).collect();
+    let mut output = String::with_capacity(input.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        // ん before anything that cannot continue the syllable, or at the end
+        // of a finished answer. A doubled n is handled here too, so that the
+        // second n can still start a syllable as in "konnichiha".
+        if c == 'n' {
+            match next {
+                None if finished => {
+                    output.push('ん');
+                    i += 1;
+                    continue;
+                }
+                Some('n') => {
+                    output.push('ん');
+                    let after = chars.get(i + 2).copied();
+                    let starts_syllable = after.map_or(false, |a| is_vowel(a) || a == 'y');
+                    i += if starts_syllable { 1 } else { 2 };
+                    continue;
+                }
+                Some(n) if !is_vowel(n) && n != 'y' && n != '\'' => {
+                    output.push('ん');
+                    i += 1;
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        // っ for a doubled consonant, or "tch".
+        if c.is_ascii_alphabetic() && !is_vowel(c) && c != 'n' {
+            let doubled = next == Some(c);
+            let tch = c == 't' && next == Some('c') && chars.get(i + 2) == Some(&'h');
+            if doubled || tch {
+                output.push('っ');
+                i += 1;
+                continue;
+            }
+        }
+
+        let matched = (1..=MAX_ROMAJI_LEN.min(chars.len() - i))
+            .rev()
+            .find_map(|len| {
+                let candidate: String = chars[i..i + len].iter().collect();
+                ROMAJI
+                    .iter()
+                    .find(|(romaji, _)| *romaji == candidate)
+                    .map(|(_, kana)| (len, *kana))
+            });
+
+        match matched {
+            Some((len, kana)) => {
+                output.push_str(kana);
+                i += len;
+            }
+            None => {
+                output.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        convert_as_typed, hiragana_to_katakana, is_kana, katakana_to_hiragana, normalize_width,
+        to_hiragana, to_katakana, Script,
+    };
+
+    #[test]
+    fn test_romaji() {
+        assert_eq!(to_hiragana("ichi"), "いち");
+        assert_eq!(to_hiragana("shinbun"), "しんぶん");
+        assert_eq!(to_hiragana("konnichiha"), "こんにちは");
+        assert_eq!(to_hiragana("kon'ya"), "こんや");
+        assert_eq!(to_hiragana("konya"), "こにゃ");
+        assert_eq!(to_hiragana("onnna"), "おんな");
+        assert_eq!(to_hiragana("hon"), "ほん");
+        assert_eq!(to_hiragana("hon desu"), "ほん です");
+        assert_eq!(to_hiragana("gakkou"), "がっこう");
+        assert_eq!(to_hiragana("matcha"), "まっちゃ");
+        assert_eq!(to_hiragana("zasshi"), "ざっし");
+        assert_eq!(to_hiragana("jyuu"), "じゅう");
+        assert_eq!(to_hiragana("tsukue"), "つくえ");
+        assert_eq!(to_hiragana("Kyouto"), "きょうと");
+        assert_eq!(to_hiragana("xtsu"), "っ");
+        assert_eq!(to_hiragana("ra-men"), "らーめん");
+        assert_eq!(to_katakana("ra-men"), "ラーメン");
+        assert_eq!(to_katakana("vaiorin"), "ヴァイオリン");
+        assert_eq!(to_hiragana("一つ"), "一つ");
+    }
+
+    #[test]
+    fn test_as_typed() {
+        assert_eq!(convert_as_typed("n", Script::Hiragana), "n");
+        assert_eq!(convert_as_typed("ny", Script::Hiragana), "ny");
+        assert_eq!(convert_as_typed("nyo", Script::Hiragana), "にょ");
+        assert_eq!(convert_as_typed("kk", Script::Hiragana), "っk");
+        assert_eq!(convert_as_typed("カタn", Script::Hiragana), "かたn");
+    }
+
+    #[test]
+    fn test_scripts() {
+        assert_eq!(katakana_to_hiragana("カタカナ ヴ ー"), "かたかな ゔ ー");
+        assert_eq!(hiragana_to_katakana("ひらがな ゝ"), "ヒラガナ ヽ");
+        assert_eq!(to_hiragana("トウキョウ"), "とうきょう");
+        assert_eq!(to_katakana("とうきょう"), "トウキョウ");
+
+        assert!(is_kana("ひらがなカタカナー"));
+        assert!(!is_kana("ひらがなa"));
+        assert!(!is_kana("一"));
+    }
+
+    #[test]
+    fn test_width() {
+        assert_eq!(normalize_width("ＡＢＣ　１２３"), "ABC 123");
+        assert_eq!(normalize_width("ｶﾀｶﾅ"), "カタカナ");
+        assert_eq!(normalize_width("ｶﾞｯｺｳ ﾊﾟﾝ ｳﾞ"), "ガッコウ パン ヴ");
+        assert_eq!(normalize_width("ｱﾞ"), "ア゛");
+        assert_eq!(to_hiragana("ｉｃｈｉ"), "いち");
+        assert_eq!(to_hiragana("ｲﾁ"), "いち");
+    }
+}
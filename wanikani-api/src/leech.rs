@@ -0,0 +1,229 @@
+//! Detection of leeches: subjects the user keeps getting wrong.
+//!
+//! Each [`ReviewStatistic`] is scored by a [`LeechFormula`], separately for
+//! its meaning and its reading. The default formula is the one popularised by
+//! the WaniKani community:
+//!
+//! `score = incorrect / current_streak ^ 1.5`
+//!
+//! so subjects that have been answered wrongly many times, and not answered
+//! correctly many times in a row since, score highest. A subject is a leech
+//! when either score reaches the formula's threshold.
+//!
+//! ### Example
+//! ```rust
+//! # use wanikani_api::{leech::{find_leeches, LeechFormula, WeakComponent}, Resource};
+//! # let mut statistic: Resource<wanikani_api::review_statistic::ReviewStatistic> =
+//! #     serde_json::from_str(include_str!("../test_files/review_statistic.json")).unwrap();
+//! # statistic.data.subject_id = 440;
+//! # statistic.data.reading_incorrect = 8;
+//! # statistic.data.reading_current_streak = 1;
+//! # let statistics = vec![statistic];
+//! # let subjects = vec![
+//! #     serde_json::from_str(include_str!("../test_files/kanji.json")).unwrap(),
+//! # ];
+//! let leeches = find_leeches(&statistics, &subjects, &LeechFormula::default());
+//!
+//! assert_eq!(leeches[0].subject_id, 440);
+//! assert_eq!(leeches[0].weak, WeakComponent::Reading);
+//! assert!(leeches[0].subject.is_some());
+//! ```
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{review_statistic::ReviewStatistic, subject::Subject, Id, Resource};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// How leech scores are computed.
+///
+/// The score of a meaning or reading is
+/// `incorrect / max(current_streak, 1) ^ exponent`.
+pub struct LeechFormula {
+    /// The power the current streak is raised to. Higher values let a streak
+    /// of correct answers clear a subject's leech status sooner.
+    pub exponent: f64,
+    /// The score at or above which a subject is a leech.
+    pub threshold: f64,
+}
+
+impl LeechFormula {
+    /// Score a meaning or reading with the given answer statistics.
+    pub fn score(&self, incorrect: u32, current_streak: u32) -> f64 {
+        f64::from(incorrect) / f64::from(current_streak.max(1)).powf(self.exponent)
+    }
+}
+
+impl Default for LeechFormula {
+    fn default() -> Self {
+        Self {
+            exponent: 1.5,
+            threshold: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+/// The part of a subject the user is struggling with.
+pub enum WeakComponent {
+    /// The meaning.
+    Meaning,
+    /// The reading.
+    Reading,
+    /// Both the meaning and the reading.
+    Both,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// A subject the user is struggling with.
+pub struct Leech<'a> {
+    /// The subject's ID.
+    pub subject_id: Id,
+    /// The subject, if it was given to [`find_leeches`].
+    pub subject: Option<&'a Subject>,
+    /// The subject's review statistic.
+    pub statistic: &'a ReviewStatistic,
+    /// The score of the subject's meaning.
+    pub meaning_score: f64,
+    /// The score of the subject's reading. This is always zero for subjects
+    /// without readings.
+    pub reading_score: f64,
+    /// Which of the meaning and reading scored at or above the threshold.
+    pub weak: WeakComponent,
+}
+
+impl Leech<'_> {
+    /// The subject's overall score, which is the higher of its meaning and
+    /// reading scores.
+    pub fn score(&self) -> f64 {
+        self.meaning_score.max(self.reading_score)
+    }
+}
+
+/// Find the leeches among `statistics`, joined with their subjects from
+/// `subjects`, sorted from the highest score to the lowest.
+///
+/// Statistics of hidden subjects are skipped.
+pub fn find_leeches<'a>(
+    statistics: &'a [Resource<ReviewStatistic>],
+    subjects: &'a [Resource<Subject>],
+    formula: &LeechFormula,
+) -> Vec<Leech<'a>> {
+    let subjects: HashMap<Id, &Subject> = subjects.iter().map(|s| (s.id, &s.data)).collect();
+
+    let mut leeches: Vec<_> = statistics
+        .iter()
+        .map(|statistic| &statistic.data)
+        .filter(|statistic| !statistic.hidden)
+        .filter_map(|statistic| {
+            let meaning_score = formula.score(
+                statistic.meaning_incorrect,
+                statistic.meaning_current_streak,
+            );
+            let reading_score = formula.score(
+                statistic.reading_incorrect,
+                statistic.reading_current_streak,
+            );
+
+            let weak = match (
+                meaning_score >= formula.threshold,
+                reading_score >= formula.threshold,
+            ) {
+                (true, true) => WeakComponent::Both,
+                (true, false) => WeakComponent::Meaning,
+                (false, true) => WeakComponent::Reading,
+                (false, false) => return None,
+            };
+
+            Some(Leech {
+                subject_id: statistic.subject_id,
+                subject: subjects.get(&statistic.subject_id).copied(),
+                statistic,
+                meaning_score,
+                reading_score,
+                weak,
+            })
+        })
+        .collect();
+
+    leeches.sort_by(|a, b| {
+        b.score()
+            .total_cmp(&a.score())
+            .then(a.subject_id.cmp(&b.subject_id))
+    });
+    leeches
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{review_statistic::ReviewStatistic, Id, Resource};
+
+    use super::{find_leeches, LeechFormula, WeakComponent};
+
+    fn statistic(
+        subject_id: Id,
+        meaning: (u32, u32),
+        reading: (u32, u32),
+    ) -> Resource<ReviewStatistic> {
+        let json = include_str!("../test_files/review_statistic.json");
+        let mut statistic: Resource<ReviewStatistic> =
+            serde_json::from_str(json).expect("Deserialize");
+        statistic.data.subject_id = subject_id;
+        (
+            statistic.data.meaning_incorrect,
+            statistic.data.meaning_current_streak,
+        ) = meaning;
+        (
+            statistic.data.reading_incorrect,
+            statistic.data.reading_current_streak,
+        ) = reading;
+        statistic
+    }
+
+    #[test]
+    fn test_score() {
+        let formula = LeechFormula::default();
+        assert_eq!(formula.score(8, 4), 1.0);
+        assert_eq!(formula.score(3, 0), 3.0);
+        assert_eq!(formula.score(0, 1), 0.0);
+
+        let linear = LeechFormula {
+            exponent: 1.0,
+            ..formula
+        };
+        assert_eq!(linear.score(8, 4), 2.0);
+    }
+
+    #[test]
+    fn test_find_leeches() {
+        let mut hidden = statistic(4, (20, 1), (20, 1));
+        hidden.data.hidden = true;
+        let statistics = vec![
+            statistic(1, (8, 4), (0, 1)),
+            statistic(2, (1, 1), (9, 1)),
+            statistic(3, (4, 1), (2, 1)),
+            hidden,
+            statistic(5, (1, 3), (1, 3)),
+        ];
+        let subjects = vec![
+            serde_json::from_str(include_str!("../test_files/radical.json")).expect("Deserialize"),
+        ];
+
+        let leeches = find_leeches(&statistics, &subjects, &LeechFormula::default());
+
+        let ranked: Vec<_> = leeches.iter().map(|l| (l.subject_id, l.weak)).collect();
+        assert_eq!(
+            ranked,
+            [
+                (2, WeakComponent::Both),
+                (3, WeakComponent::Both),
+                (1, WeakComponent::Meaning),
+            ]
+        );
+        assert_eq!(leeches[0].score(), 9.0);
+        assert!(leeches[2].subject.is_some());
+        assert!(leeches[0].subject.is_none());
+    }
+}
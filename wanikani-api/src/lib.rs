@@ -86,7 +86,7 @@
 //! - `reset`: [`Reset`](reset::Reset)
 //! - `review_statistic`: [`ReviewStatistic`](review_statistic::ReviewStatistic)
 //! - `review`: [`Review`](review::Review)
-//! - `spaced_repetition_system`: [`SpacedRepetitionSystem`](srs::SpacedRepetitionSystem)
+//! - `spaced_repetition_system`: [`SpacedRepetitionSystem`](spaced_repetition_system::SpacedRepetitionSystem)
 //! - `study_material`: [`StudyMaterial`](study_material::StudyMaterial)
 //! - `user`: [`User`](user::User)
 //! - `vocabulary`: [`Vocabulary`](subject::Vocabulary)
@@ -484,21 +484,54 @@ pub type Timestamp = DateTime<Utc>;
 /// Expressive type for IDs
 pub type Id = u64;
 
+#[cfg(feature = "analytics")]
+pub mod analytics;
+
 #[cfg(feature = "assignment")]
 pub mod assignment;
 
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
 #[cfg(feature = "client")]
 pub mod client;
 
+#[cfg(feature = "export")]
+pub mod export;
+
+#[cfg(feature = "forecast")]
+pub mod forecast;
+
+#[cfg(feature = "grading")]
+pub mod grading;
+
+#[cfg(feature = "kana")]
+pub mod kana;
+
+#[cfg(feature = "leech")]
+pub mod leech;
+
 #[cfg(feature = "level_progression")]
 pub mod level_progression;
 
+#[cfg(feature = "mnemonic")]
+pub mod mnemonic;
+
 #[cfg(feature = "reset")]
 pub mod reset;
 
+#[cfg(feature = "review")]
+pub mod review;
+
 #[cfg(feature = "review_statistic")]
 pub mod review_statistic;
 
+#[cfg(feature = "spaced_repetition_system")]
+pub mod spaced_repetition_system;
+
+#[cfg(feature = "store")]
+pub mod store;
+
 #[cfg(feature = "study_material")]
 pub mod study_material;
 
@@ -517,6 +550,12 @@ pub mod subject {
 #[cfg(feature = "summary")]
 pub mod summary;
 
+#[cfg(any(
+    feature = "test-util",
+    all(test, feature = "client", feature = "model")
+))]
+pub mod test_util;
+
 #[cfg(feature = "user")]
 pub mod user;
 #[cfg(not(feature = "user"))]
@@ -674,9 +713,15 @@ pub enum ResourceType {
     #[cfg(feature = "reset")]
     /// A `reset`
     Reset,
+    #[cfg(feature = "review")]
+    /// A `review`
+    Review,
     #[cfg(feature = "review_statistic")]
     /// A `review_statistic`
     ReviewStatistic,
+    #[cfg(feature = "spaced_repetition_system")]
+    /// A `spaced_repetition_system`
+    SpacedRepetitionSystem,
     #[cfg(feature = "study_material")]
     /// A `study_material`
     StudyMaterial,
@@ -781,13 +826,102 @@ impl Display for WanikaniError {
 
 impl std::error::Error for WanikaniError {}
 
+#[cfg(feature = "client")]
+#[derive(Debug, Clone)]
+/// The request and response an [`Error`] was returned for.
+pub struct ErrorContext {
+    /// The HTTP status of the response.
+    pub status: reqwest::StatusCode,
+    /// The HTTP method of the request.
+    pub method: reqwest::Method,
+    /// The URL of the request.
+    pub url: Url,
+    /// The headers of the response.
+    pub headers: reqwest::header::HeaderMap,
+}
+
+#[cfg(feature = "client")]
+impl ErrorContext {
+    pub(crate) fn new(method: reqwest::Method, response: &reqwest::Response) -> Self {
+        Self {
+            status: response.status(),
+            method,
+            url: response.url().clone(),
+            headers: response.headers().clone(),
+        }
+    }
+}
+
 #[derive(Debug, E)]
 /// Possible error conditions
 pub enum Error {
     #[error("WaniKani error: {0}")]
-    /// An error was returned by WaniKani.
+    /// An error was returned by WaniKani with a status that has no more
+    /// specific variant.
     WaniKaniError(#[from] WanikaniError),
     #[cfg(feature = "client")]
+    #[error("Unauthorized ({} {}): {error}", .context.method, .context.url)]
+    /// The API token is missing, invalid or has been revoked (`401`).
+    Unauthorized {
+        /// The error returned by WaniKani
+        error: WanikaniError,
+        /// The request and response
+        context: Box<ErrorContext>,
+    },
+    #[cfg(feature = "client")]
+    #[error("Not found ({} {}): {error}", .context.method, .context.url)]
+    /// The requested resource does not exist (`404`).
+    NotFound {
+        /// The error returned by WaniKani
+        error: WanikaniError,
+        /// The request and response
+        context: Box<ErrorContext>,
+    },
+    #[cfg(feature = "client")]
+    #[error("Unprocessable request ({} {}): {}", .context.method, .context.url, .errors.join("; "))]
+    /// The request was understood, but failed validation (`422`), for example
+    /// when creating a study material for a subject that already has one.
+    Unprocessable {
+        /// The validation errors returned by WaniKani
+        errors: Vec<String>,
+        /// The request and response
+        context: Box<ErrorContext>,
+    },
+    #[cfg(feature = "client")]
+    #[error("Server error {} ({} {})", .context.status, .context.method, .context.url)]
+    /// WaniKani, or a proxy in front of it, failed to handle the request
+    /// (`5xx`). These errors are usually temporary.
+    ServerError {
+        /// The error returned by WaniKani, if the response body contained one
+        error: Option<WanikaniError>,
+        /// The start of the response body, if it did not contain a WaniKani
+        /// error
+        body_snippet: Option<String>,
+        /// The request and response
+        context: Box<ErrorContext>,
+    },
+    #[cfg(feature = "client")]
+    #[error("HTTP error {} ({} {}): {error}", .context.status, .context.method, .context.url)]
+    /// An error was returned by WaniKani with a status that has no more
+    /// specific variant, such as `400 Bad Request` or `403 Forbidden`.
+    Http {
+        /// The error returned by WaniKani
+        error: WanikaniError,
+        /// The request and response
+        context: Box<ErrorContext>,
+    },
+    #[cfg(feature = "client")]
+    #[error("Invalid response body ({} {} {}): {source}", .context.status, .context.method, .context.url)]
+    /// The response body could not be deserialized.
+    Decode {
+        /// Why the body could not be deserialized
+        source: serde_json::Error,
+        /// The start of the response body
+        body_snippet: String,
+        /// The request and response
+        context: Box<ErrorContext>,
+    },
+    #[cfg(feature = "client")]
     #[error("HTTP client error: {0}")]
     /// There was some error in the HTTP client.
     Client(#[from] reqwest::Error),
@@ -801,14 +935,10 @@ pub enum Error {
     /// ### Example:
     ///
     /// ```rust
-    /// # use wanikani_api::{Error, WanikaniError};
-    /// # fn do_things() {}
-    /// # let error = Error::RateLimit {
-    /// #     error: WanikaniError {code: 429, error: None},
-    /// #     reset_time: chrono::Utc::now() + chrono::Duration::seconds(10)};
-    /// # async {
+    /// # use wanikani_api::Error;
+    /// # async fn handle(error: Error) {
     /// match error {
-    ///     Error::RateLimit{error, reset_time} => {
+    ///     Error::RateLimit { reset_time, .. } => {
     ///         let duration = (reset_time - chrono::Utc::now())
     ///             .to_std()
     ///             .expect("Reset time should be relatively short");
@@ -819,17 +949,71 @@ pub enum Error {
     /// // ...
     /// #   error => {}
     /// # }
-    /// # };
-    /// # Ok::<(), Error>(())
+    /// # }
     /// ```
     RateLimit {
         /// The error struct returned by WaniKani
         error: WanikaniError,
         /// The time when the rate limit should reset
         reset_time: Timestamp,
+        #[cfg(feature = "client")]
+        /// The request and response
+        context: Box<ErrorContext>,
+    },
+    #[error("Expected {expected} resources in collection, received {received}")]
+    /// A collection was fetched in full, but the number of resources received
+    /// did not match its `total_count`. This usually means the collection was
+    /// modified while it was being paged through.
+    IncompleteCollection {
+        /// The `total_count` reported by the collection
+        expected: u64,
+        /// The number of resources actually received
+        received: u64,
     },
 }
 
+impl Error {
+    /// Whether the request may succeed if it is sent again: the rate limit
+    /// was exceeded, the server failed, or the connection failed or timed
+    /// out.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RateLimit { .. } => true,
+            Self::WaniKaniError(error) => error.code >= 500,
+            #[cfg(feature = "client")]
+            Self::ServerError { .. } => true,
+            #[cfg(feature = "client")]
+            Self::Client(error) => error.is_connect() || error.is_timeout(),
+            _ => false,
+        }
+    }
+
+    /// The request and response the error was returned for, if it was
+    /// returned for a response.
+    #[cfg(feature = "client")]
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Self::Unauthorized { context, .. }
+            | Self::NotFound { context, .. }
+            | Self::RateLimit { context, .. }
+            | Self::Http { context, .. }
+            | Self::Unprocessable { context, .. }
+            | Self::ServerError { context, .. }
+            | Self::Decode { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// The HTTP status of the response the error was returned for.
+    #[cfg(feature = "client")]
+    pub fn status(&self) -> Option<reqwest::StatusCode> {
+        match self {
+            Self::Client(error) => error.status(),
+            error => error.context().map(|context| context.status),
+        }
+    }
+}
+
 /// The version of the API supported by this library
 pub const API_VERSION: &str = "20170710";
 
@@ -841,6 +1025,8 @@ pub mod prelude {
     #[cfg(feature = "client")]
     pub use crate::client::{WKClient, WKClientBuilder};
     #[cfg(feature = "client")]
+    pub use futures_util;
+    #[cfg(feature = "client")]
     pub use reqwest;
 
     pub use crate::{Collection, Error, Resource, ResourceType};
@@ -0,0 +1,579 @@
+//! Parsing and rendering of the markup used in subject mnemonics and hints.
+//!
+//! The `meaning_mnemonic`, `reading_mnemonic`, `meaning_hint` and
+//! `reading_hint` of a subject are plain text, with parts of the text
+//! highlighted by WaniKani's markup tags:
+//!
+//! - `<radical></radical>`
+//! - `<kanji></kanji>`
+//! - `<vocabulary></vocabulary>`
+//! - `<meaning></meaning>`
+//! - `<reading></reading>`
+//! - `<ja></ja>`, which marks Japanese text
+//!
+//! [`Mnemonic::parse`] turns such a string into a tree of [`Span`]s, which can
+//! then be rendered as HTML, as text coloured with ANSI escape codes for a
+//! terminal, or as plain text with the markup removed.
+//!
+//! A `<` that does not start a tag, such as in `a < b`, is treated as text.
+//! Tags that are never closed, closed in the wrong order, or not known to
+//! this module are reported as a [`MarkupError`].
+//!
+//! ### Example
+//! ```rust
+//! # use wanikani_api::mnemonic::{HtmlClasses, Mnemonic};
+//! let mnemonic = Mnemonic::parse("The <radical>ground</radical> is <reading>itchy</reading>")
+//!     .expect("Valid markup");
+//!
+//! assert_eq!(mnemonic.to_plain_text(), "The ground is itchy");
+//! assert_eq!(
+//!     mnemonic.to_html(&HtmlClasses::default()),
+//!     "The <span class=\"radical-highlight\">ground</span> is \
+//!      <span class=\"reading-highlight\">itchy</span>"
+//! );
+//! ```
+
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error as E;
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+/// A markup tag.
+pub enum Tag {
+    /// `<radical>`, highlighting the name of a radical.
+    Radical,
+    /// `<kanji>`, highlighting the meaning of a kanji.
+    Kanji,
+    /// `<vocabulary>`, highlighting the meaning of a vocabulary.
+    Vocabulary,
+    /// `<meaning>`, highlighting a meaning that is not one of the above.
+    Meaning,
+    /// `<reading>`, highlighting a reading.
+    Reading,
+    /// `<ja>`, marking Japanese text.
+    Japanese,
+}
+
+impl Tag {
+    /// The tag's name, as it appears in the markup.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Radical => "radical",
+            Self::Kanji => "kanji",
+            Self::Vocabulary => "vocabulary",
+            Self::Meaning => "meaning",
+            Self::Reading => "reading",
+            Self::Japanese => "ja",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "radical" => Self::Radical,
+            "kanji" => Self::Kanji,
+            "vocabulary" => Self::Vocabulary,
+            "meaning" => Self::Meaning,
+            "reading" => Self::Reading,
+            "ja" => Self::Japanese,
+            _ => return None,
+        })
+    }
+}
+
+impl Display for Tag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<{}>", self.name())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, E)]
+/// Errors in mnemonic markup. Positions are byte offsets into the markup.
+pub enum MarkupError {
+    #[error("{tag} opened at {position} is never closed")]
+    /// A tag was opened, but the markup ended before it was closed.
+    Unclosed {
+        /// The tag left open.
+        tag: Tag,
+        /// Where the tag was opened.
+        position: usize,
+    },
+    #[error("Closing tag for {found} at {position} does not match {expected}")]
+    /// A tag was closed while another tag, opened inside it, was still open.
+    Mismatched {
+        /// The innermost open tag, which should have been closed first.
+        expected: Tag,
+        /// The tag that was closed.
+        found: Tag,
+        /// Where the closing tag is.
+        position: usize,
+    },
+    #[error("Closing tag for {tag} at {position} was never opened")]
+    /// A tag was closed without being opened.
+    Unopened {
+        /// The tag that was closed.
+        tag: Tag,
+        /// Where the closing tag is.
+        position: usize,
+    },
+    #[error("Unknown tag <{name}> at {position}")]
+    /// A tag that is not part of WaniKani's markup.
+    Unknown {
+        /// The name of the tag.
+        name: String,
+        /// Where the tag is.
+        position: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A piece of a parsed mnemonic.
+pub enum Span {
+    /// Text without markup.
+    Text(String),
+    /// Spans enclosed in a tag.
+    Tagged {
+        /// The enclosing tag.
+        tag: Tag,
+        /// The enclosed spans.
+        children: Vec<Span>,
+    },
+}
+
+impl Span {
+    fn write_plain_text(&self, output: &mut String) {
+        match self {
+            Self::Text(text) => output.push_str(text),
+            Self::Tagged { children, .. } => {
+                children.iter().for_each(|c| c.write_plain_text(output))
+            }
+        }
+    }
+
+    fn write_html(&self, classes: &HtmlClasses, output: &mut String) {
+        match self {
+            Self::Text(text) => escape_html(text, output),
+            Self::Tagged { tag, children } => {
+                output.push_str("<span class=\"");
+                escape_html(classes.class(*tag), output);
+                output.push('"');
+                if *tag == Tag::Japanese {
+                    output.push_str(" lang=\"ja\"");
+                }
+                output.push('>');
+                children.iter().for_each(|c| c.write_html(classes, output));
+                output.push_str("</span>");
+            }
+        }
+    }
+
+    fn write_ansi<'a>(
+        &self,
+        styles: &'a AnsiStyles,
+        stack: &mut Vec<&'a str>,
+        output: &mut String,
+    ) {
+        match self {
+            Self::Text(text) => output.push_str(text),
+            Self::Tagged { tag, children } => {
+                let style = styles.style(*tag);
+                if style.is_empty() {
+                    children
+                        .iter()
+                        .for_each(|c| c.write_ansi(styles, stack, output));
+                    return;
+                }
+
+                output.push_str(&sgr(style));
+                stack.push(style);
+                children
+                    .iter()
+                    .for_each(|c| c.write_ansi(styles, stack, output));
+                stack.pop();
+
+                // Escape codes can't be popped, so reset and reapply the
+                // styles of the enclosing tags.
+                output.push_str(&sgr("0"));
+                stack.iter().for_each(|s| output.push_str(&sgr(s)));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+/// A parsed mnemonic or hint.
+pub struct Mnemonic {
+    /// The top level spans of the mnemonic.
+    pub spans: Vec<Span>,
+}
+
+impl Mnemonic {
+    /// Parse mnemonic markup.
+    pub fn parse(markup: &str) -> Result<Self, MarkupError> {
+        // Each open tag, with its position and the spans collected so far.
+        let mut open: Vec<(Tag, usize, Vec<Span>)> = Vec::new();
+        let mut spans = Vec::new();
+        let mut text = String::new();
+        let mut rest = markup;
+
+        while let Some(start) = rest.find('<') {
+            let position = markup.len() - rest.len() + start;
+            text.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            let (closing, name, len) = match parse_tag(rest) {
+                Some(tag) => tag,
+                None => {
+                    text.push('<');
+                    rest = &rest[1..];
+                    continue;
+                }
+            };
+            rest = &rest[len..];
+
+            let tag = Tag::from_name(name).ok_or_else(|| MarkupError::Unknown {
+                name: name.to_string(),
+                position,
+            })?;
+
+            let current = open.last_mut().map_or(&mut spans, |(_, _, c)| c);
+            if !text.is_empty() {
+                current.push(Span::Text(std::mem::take(&mut text)));
+            }
+
+            if !closing {
+                open.push((tag, position, Vec::new()));
+                continue;
+            }
+
+            match open.pop() {
+                Some((expected, _, children)) if expected == tag => {
+                    let current = open.last_mut().map_or(&mut spans, |(_, _, c)| c);
+                    current.push(Span::Tagged { tag, children });
+                }
+                Some((expected, _, _)) => {
+                    return Err(MarkupError::Mismatched {
+                        expected,
+                        found: tag,
+                        position,
+                    })
+                }
+                None => return Err(MarkupError::Unopened { tag, position }),
+            }
+        }
+
+        if let Some((tag, position, _)) = open.pop() {
+            return Err(MarkupError::Unclosed { tag, position });
+        }
+
+        text.push_str(rest);
+        if !text.is_empty() {
+            spans.push(Span::Text(text));
+        }
+        Ok(Self { spans })
+    }
+
+    /// The text of the mnemonic, with all markup removed.
+    pub fn to_plain_text(&self) -> String {
+        let mut output = String::new();
+        self.spans
+            .iter()
+            .for_each(|s| s.write_plain_text(&mut output));
+        output
+    }
+
+    /// Render the mnemonic as HTML. Each tag becomes a `<span>` with the
+    /// class given by `classes`, and text is escaped.
+    pub fn to_html(&self, classes: &HtmlClasses) -> String {
+        let mut output = String::new();
+        self.spans
+            .iter()
+            .for_each(|s| s.write_html(classes, &mut output));
+        output
+    }
+
+    /// Render the mnemonic for a terminal, styling each tag with the ANSI
+    /// escape codes given by `styles`.
+    pub fn to_ansi(&self, styles: &AnsiStyles) -> String {
+        let mut output = String::new();
+        let mut stack = Vec::new();
+        self.spans
+            .iter()
+            .for_each(|s| s.write_ansi(styles, &mut stack, &mut output));
+        output
+    }
+}
+
+impl FromStr for Mnemonic {
+    type Err = MarkupError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Display for Mnemonic {
+    /// Writes the plain text of the mnemonic.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_plain_text())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The CSS classes used by [`Mnemonic::to_html`].
+pub struct HtmlClasses {
+    /// Class for `<radical>`.
+    pub radical: String,
+    /// Class for `<kanji>`.
+    pub kanji: String,
+    /// Class for `<vocabulary>`.
+    pub vocabulary: String,
+    /// Class for `<meaning>`.
+    pub meaning: String,
+    /// Class for `<reading>`.
+    pub reading: String,
+    /// Class for `<ja>`.
+    pub japanese: String,
+}
+
+impl HtmlClasses {
+    /// The class for `tag`.
+    pub fn class(&self, tag: Tag) -> &str {
+        match tag {
+            Tag::Radical => &self.radical,
+            Tag::Kanji => &self.kanji,
+            Tag::Vocabulary => &self.vocabulary,
+            Tag::Meaning => &self.meaning,
+            Tag::Reading => &self.reading,
+            Tag::Japanese => &self.japanese,
+        }
+    }
+}
+
+impl Default for HtmlClasses {
+    /// The class names used by WaniKani's own pages.
+    fn default() -> Self {
+        Self {
+            radical: "radical-highlight".into(),
+            kanji: "kanji-highlight".into(),
+            vocabulary: "vocabulary-highlight".into(),
+            meaning: "meaning-highlight".into(),
+            reading: "reading-highlight".into(),
+            japanese: "japanese".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The styles used by [`Mnemonic::to_ansi`].
+///
+/// Each style is the parameter list of an SGR escape code, such as `"1;34"`
+/// for bold blue text. An empty style leaves the text unstyled.
+pub struct AnsiStyles {
+    /// Style for `<radical>`.
+    pub radical: String,
+    /// Style for `<kanji>`.
+    pub kanji: String,
+    /// Style for `<vocabulary>`.
+    pub vocabulary: String,
+    /// Style for `<meaning>`.
+    pub meaning: String,
+    /// Style for `<reading>`.
+    pub reading: String,
+    /// Style for `<ja>`.
+    pub japanese: String,
+}
+
+impl AnsiStyles {
+    /// The style for `tag`.
+    pub fn style(&self, tag: Tag) -> &str {
+        match tag {
+            Tag::Radical => &self.radical,
+            Tag::Kanji => &self.kanji,
+            Tag::Vocabulary => &self.vocabulary,
+            Tag::Meaning => &self.meaning,
+            Tag::Reading => &self.reading,
+            Tag::Japanese => &self.japanese,
+        }
+    }
+}
+
+impl Default for AnsiStyles {
+    /// Colours close to WaniKani's: blue radicals, pink kanji and purple
+    /// vocabulary. Meanings and readings are bold.
+    fn default() -> Self {
+        Self {
+            radical: "38;5;39".into(),
+            kanji: "38;5;199".into(),
+            vocabulary: "38;5;129".into(),
+            meaning: "1".into(),
+            reading: "1".into(),
+            japanese: String::new(),
+        }
+    }
+}
+
+/// Parse a tag at the start of `input`, returning whether it is a closing
+/// tag, its name, and its length.
+fn parse_tag(input: &str) -> Option<(bool, &str, usize)> {
+    let inner = input.strip_prefix('<')?;
+    let (closing, inner) = match inner.strip_prefix('/') {
+        Some(inner) => (true, inner),
+        None => (false, inner),
+    };
+    let end = inner.find('>')?;
+    let name = &inner[..end];
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    Some((closing, name, 1 + usize::from(closing) + end + 1))
+}
+
+fn sgr(parameters: &str) -> String {
+    format!("\x1b[{parameters}m")
+}
+
+pub(crate) fn escape_html(text: &str, output: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&#39;"),
+            _ => output.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AnsiStyles, HtmlClasses, MarkupError, Mnemonic, Span, Tag};
+
+    #[test]
+    fn test_parse() {
+        let mnemonic = Mnemonic::parse("<reading>Oh yah!</reading> (<ja>おやつ</ja>) a < b")
+            .expect("Valid markup");
+        assert_eq!(
+            mnemonic.spans,
+            [
+                Span::Tagged {
+                    tag: Tag::Reading,
+                    children: vec![Span::Text("Oh yah!".into())],
+                },
+                Span::Text(" (".into()),
+                Span::Tagged {
+                    tag: Tag::Japanese,
+                    children: vec![Span::Text("おやつ".into())],
+                },
+                Span::Text(") a < b".into()),
+            ]
+        );
+
+        let nested = Mnemonic::parse("<kanji>a <reading>b</reading></kanji>").expect("Nested");
+        assert_eq!(
+            nested.spans,
+            [Span::Tagged {
+                tag: Tag::Kanji,
+                children: vec![
+                    Span::Text("a ".into()),
+                    Span::Tagged {
+                        tag: Tag::Reading,
+                        children: vec![Span::Text("b".into())],
+                    },
+                ],
+            }]
+        );
+
+        assert_eq!(Mnemonic::parse("").expect("Empty"), Mnemonic::default());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Mnemonic::parse("a <kanji>b"),
+            Err(MarkupError::Unclosed {
+                tag: Tag::Kanji,
+                position: 2,
+            })
+        );
+        assert_eq!(
+            Mnemonic::parse("<kanji><reading>b</kanji></reading>"),
+            Err(MarkupError::Mismatched {
+                expected: Tag::Reading,
+                found: Tag::Kanji,
+                position: 17,
+            })
+        );
+        assert_eq!(
+            Mnemonic::parse("a</radical>"),
+            Err(MarkupError::Unopened {
+                tag: Tag::Radical,
+                position: 1,
+            })
+        );
+        assert_eq!(
+            Mnemonic::parse("<b>bold</b>"),
+            Err(MarkupError::Unknown {
+                name: "b".into(),
+                position: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let mnemonic: Mnemonic = "<kanji>One & <reading>itchy</reading></kanji> <ja>いち</ja>"
+            .parse()
+            .expect("Valid markup");
+
+        assert_eq!(mnemonic.to_string(), "One & itchy いち");
+
+        let classes = HtmlClasses {
+            kanji: "k".into(),
+            ..HtmlClasses::default()
+        };
+        assert_eq!(
+            mnemonic.to_html(&classes),
+            "<span class=\"k\">One &amp; <span class=\"reading-highlight\">itchy</span></span> \
+             <span class=\"japanese\" lang=\"ja\">いち</span>"
+        );
+
+        let styles = AnsiStyles {
+            kanji: "35".into(),
+            reading: "1".into(),
+            ..AnsiStyles::default()
+        };
+        assert_eq!(
+            mnemonic.to_ansi(&styles),
+            "\x1b[35mOne & \x1b[1mitchy\x1b[0m\x1b[35m\x1b[0m いち"
+        );
+    }
+
+    #[test]
+    fn test_fixtures() {
+        let fixtures = [
+            include_str!("../test_files/radical.json"),
+            include_str!("../test_files/kanji.json"),
+            include_str!("../test_files/vocabulary.json"),
+            include_str!("../test_files/kana_vocabulary.json"),
+        ];
+        let fields = [
+            "meaning_mnemonic",
+            "reading_mnemonic",
+            "meaning_hint",
+            "reading_hint",
+        ];
+
+        for fixture in fixtures {
+            let subject: serde_json::Value = serde_json::from_str(fixture).expect("Deserialize");
+            for field in fields {
+                if let Some(markup) = subject["data"][field].as_str() {
+                    let mnemonic = Mnemonic::parse(markup).expect("Valid markup");
+                    assert!(!mnemonic.to_plain_text().contains("</"));
+                }
+            }
+        }
+    }
+}
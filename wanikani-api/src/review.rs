@@ -0,0 +1,227 @@
+//! Reviews log all the correct and incorrect answers provided through the
+//! 'Reviews' section of WaniKani. Review records are created when a user
+//! answers all the parts of a subject correctly once; some subjects have both
+//! meaning or reading parts, and some only have one or the other. Note that
+//! reviews are not created for the quizzes in lessons.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{assignment::Assignment, review_statistic::ReviewStatistic, Id, Resource, Timestamp};
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+/// Reviews log all the correct and incorrect answers provided through the
+/// 'Reviews' section of WaniKani. Review records are created when a user
+/// answers all the parts of a subject correctly once; some subjects have both
+/// meaning or reading parts, and some only have one or the other. Note that
+/// reviews are not created for the quizzes in lessons.
+pub struct Review {
+    /// Unique identifier of the associated assignment.
+    pub assignment_id: Id,
+    /// Timestamp when the review was created.
+    pub created_at: Timestamp,
+    /// The SRS stage interval calculated from the number of correct and
+    /// incorrect answers, with valid values ranging from `1` to `9`.
+    pub ending_srs_stage: u32,
+    /// The number of times the user has answered the meaning incorrectly.
+    pub incorrect_meaning_answers: u32,
+    /// The number of times the user has answered the reading incorrectly.
+    pub incorrect_reading_answers: u32,
+    /// Unique identifier of the associated spaced repetition system.
+    pub spaced_repetition_system_id: Id,
+    /// The starting SRS stage interval, with valid values ranging from `1` to
+    /// `8`.
+    pub starting_srs_stage: u32,
+    /// Unique identifier of the associated subject.
+    pub subject_id: Id,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The item a new review is being recorded against.
+pub enum ReviewTarget {
+    /// Unique identifier of the assignment.
+    Assignment(Id),
+    /// Unique identifier of the subject.
+    Subject(Id),
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(
+    into = "crate::serde_helpers::review::CreateReviewWrapper",
+    try_from = "crate::serde_helpers::review::CreateReviewWrapper"
+)]
+/// Creates a review for a specific `assignment_id`. Using the related
+/// `subject_id` is also a valid alternative.
+pub struct CreateReview {
+    /// The assignment or subject being reviewed.
+    pub target: ReviewTarget,
+    /// Must be zero or a positive number. This is the number of times the
+    /// meaning was answered incorrectly.
+    pub incorrect_meaning_answers: u32,
+    /// Must be zero or a positive number. This is the number of times the
+    /// reading was answered incorrectly. Note that subjects with a type of
+    /// `radical` do not quiz on readings. Thus, set this value to `0`.
+    pub incorrect_reading_answers: u32,
+    /// Timestamp when the review was completed. Defaults to the time of the
+    /// request if omitted from the request body. Must be in the past, but
+    /// after `assignment.available_at`.
+    pub created_at: Option<Timestamp>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+/// The resources updated as a side effect of creating a review.
+pub struct ReviewResourcesUpdated {
+    /// The assignment after the review has been applied.
+    pub assignment: Resource<Assignment>,
+    /// The review statistic after the review has been applied.
+    pub review_statistic: Resource<ReviewStatistic>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+/// The response to a review creation. Along with the new review, the updated
+/// assignment and review statistic are returned.
+pub struct CreatedReview {
+    #[serde(flatten)]
+    /// The newly created review.
+    pub review: Resource<Review>,
+    /// The resources updated by the review.
+    pub resources_updated: ReviewResourcesUpdated,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    use crate::{
+        assignment::SrsStage, cross_feature::SubjectType, Resource, ResourceCommon, ResourceType,
+    };
+
+    use super::{CreateReview, CreatedReview, Review, ReviewTarget};
+
+    #[test]
+    fn test_deserialize_review() {
+        let json = include_str!("../test_files/review.json");
+
+        let review: Resource<Review> = serde_json::from_str(json).expect("Deserialize");
+
+        assert_eq!(review.id, 534342);
+
+        let common = review.common;
+        assert_eq!(common.object, ResourceType::Review);
+        assert_eq!(
+            common.url,
+            "https://api.wanikani.com/v2/reviews/534342"
+                .parse()
+                .expect("URL")
+        );
+        assert_eq!(
+            common.data_updated_at.expect("Timestamp"),
+            DateTime::parse_from_rfc3339("2017-12-20T01:00:59.255427Z").expect("Timestamp")
+        );
+
+        let data = review.data;
+        assert_eq!(
+            data.created_at,
+            DateTime::parse_from_rfc3339("2017-12-20T01:00:59.255427Z").expect("Timestamp")
+        );
+        assert_eq!(data.assignment_id, 32132);
+        assert_eq!(data.spaced_repetition_system_id, 1);
+        assert_eq!(data.subject_id, 8);
+        assert_eq!(data.starting_srs_stage, 4);
+        assert_eq!(data.ending_srs_stage, 2);
+        assert_eq!(data.incorrect_meaning_answers, 0);
+        assert_eq!(data.incorrect_reading_answers, 1);
+    }
+
+    #[test]
+    fn test_serialize_review() {
+        let data = Review {
+            assignment_id: 420,
+            created_at: Utc::now(),
+            ending_srs_stage: 5,
+            incorrect_meaning_answers: 0,
+            incorrect_reading_answers: 0,
+            spaced_repetition_system_id: 2,
+            starting_srs_stage: 4,
+            subject_id: 69,
+        };
+        let common = ResourceCommon {
+            data_updated_at: Some(Utc::now()),
+            object: ResourceType::Review,
+            url: "https://some.url/review".parse().expect("URL"),
+        };
+        let review = Resource {
+            common,
+            data,
+            id: 69420,
+        };
+
+        let json = serde_json::to_string(&review).expect("Serialize");
+
+        let new_review: Resource<Review> = serde_json::from_str(&json).expect("Deserialize");
+
+        assert_eq!(review, new_review);
+    }
+
+    #[test]
+    fn test_deserialize_created_review() {
+        let json = include_str!("../test_files/created_review.json");
+
+        let created: CreatedReview = serde_json::from_str(json).expect("Deserialize");
+
+        assert_eq!(created.review.id, 72);
+        assert_eq!(created.review.common.object, ResourceType::Review);
+        assert_eq!(created.review.data.assignment_id, 1422);
+        assert_eq!(created.review.data.incorrect_reading_answers, 2);
+
+        let assignment = created.resources_updated.assignment;
+        assert_eq!(assignment.id, 1422);
+        assert_eq!(assignment.common.object, ResourceType::Assignment);
+        assert_eq!(assignment.data.subject_type, SubjectType::Kanji);
+        assert_eq!(assignment.data.srs_stage, SrsStage::Apprentice1);
+
+        let stat = created.resources_updated.review_statistic;
+        assert_eq!(stat.id, 342);
+        assert_eq!(stat.common.object, ResourceType::ReviewStatistic);
+        assert_eq!(stat.data.meaning_incorrect, 1);
+        assert_eq!(stat.data.reading_incorrect, 2);
+    }
+
+    #[test]
+    fn test_deserialize_create_review() {
+        let json = include_str!("../test_files/create_review.json");
+
+        let create: CreateReview = serde_json::from_str(json).expect("Deserialize");
+        assert_eq!(create.target, ReviewTarget::Assignment(1422));
+        assert_eq!(create.incorrect_meaning_answers, 1);
+        assert_eq!(create.incorrect_reading_answers, 2);
+        assert_eq!(
+            create.created_at.expect("Timestamp"),
+            DateTime::parse_from_rfc3339("2017-09-30T01:42:13.453291Z").expect("Timestamp")
+        );
+
+        serde_json::from_str::<CreateReview>(
+            r#"{"review":{"incorrect_meaning_answers":0,"incorrect_reading_answers":0}}"#,
+        )
+        .expect_err("Missing target");
+        serde_json::from_str::<CreateReview>(
+            r#"{"review":{"assignment_id":1,"subject_id":2,"incorrect_meaning_answers":0,"incorrect_reading_answers":0}}"#,
+        )
+        .expect_err("Ambiguous target");
+    }
+
+    #[test]
+    fn test_serialize_create_review() {
+        let create = CreateReview {
+            target: ReviewTarget::Subject(440),
+            incorrect_meaning_answers: 0,
+            incorrect_reading_answers: 3,
+            created_at: None,
+        };
+
+        let json = serde_json::to_string(&create).expect("Serialize");
+        assert_eq!(
+            json,
+            r#"{"review":{"subject_id":440,"incorrect_meaning_answers":0,"incorrect_reading_answers":3}}"#
+        );
+    }
+}
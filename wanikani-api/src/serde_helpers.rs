@@ -106,6 +106,73 @@ pub mod study_material {
     }
 }
 
+#[cfg(feature = "review")]
+pub mod review {
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        review::{self, ReviewTarget},
+        Id, Timestamp,
+    };
+
+    #[derive(Debug, Deserialize, Serialize)]
+    struct CreateReview {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        assignment_id: Option<Id>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        subject_id: Option<Id>,
+        incorrect_meaning_answers: u32,
+        incorrect_reading_answers: u32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        created_at: Option<Timestamp>,
+    }
+
+    impl TryFrom<CreateReviewWrapper> for review::CreateReview {
+        type Error = &'static str;
+
+        fn try_from(value: CreateReviewWrapper) -> Result<Self, Self::Error> {
+            let value = value.review;
+            let target = match (value.assignment_id, value.subject_id) {
+                (Some(id), None) => ReviewTarget::Assignment(id),
+                (None, Some(id)) => ReviewTarget::Subject(id),
+                (None, None) => return Err("one of assignment_id or subject_id is required"),
+                (Some(_), Some(_)) => {
+                    return Err("only one of assignment_id or subject_id may be provided")
+                }
+            };
+            Ok(Self {
+                target,
+                incorrect_meaning_answers: value.incorrect_meaning_answers,
+                incorrect_reading_answers: value.incorrect_reading_answers,
+                created_at: value.created_at,
+            })
+        }
+    }
+
+    impl From<review::CreateReview> for CreateReviewWrapper {
+        fn from(value: review::CreateReview) -> Self {
+            let (assignment_id, subject_id) = match value.target {
+                ReviewTarget::Assignment(id) => (Some(id), None),
+                ReviewTarget::Subject(id) => (None, Some(id)),
+            };
+            Self {
+                review: CreateReview {
+                    assignment_id,
+                    subject_id,
+                    incorrect_meaning_answers: value.incorrect_meaning_answers,
+                    incorrect_reading_answers: value.incorrect_reading_answers,
+                    created_at: value.created_at,
+                },
+            }
+        }
+    }
+
+    #[derive(Debug, Deserialize, Serialize)]
+    pub struct CreateReviewWrapper {
+        review: CreateReview,
+    }
+}
+
 #[cfg(feature = "user")]
 pub mod update_prefs {
     use serde::{ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
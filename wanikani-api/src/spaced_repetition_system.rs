@@ -0,0 +1,295 @@
+//! Available spaced repetition systems used for calculating `srs_stage`
+//! changes to Assignments and Reviews. Has relationship with Subjects.
+//!
+//! See the [Spaced Repetition System](crate#spaced-repetition-system) section
+//! of the crate documentation for an explanation of how the stages work.
+
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::Timestamp;
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+/// Available spaced repetition systems used for calculating `srs_stage`
+/// changes to Assignments and Reviews. Has relationship with Subjects.
+pub struct SpacedRepetitionSystem {
+    /// `position` of the burning stage.
+    pub burning_stage_position: u32,
+    /// Timestamp when the `spaced_repetition_system` was created.
+    pub created_at: Timestamp,
+    /// Details about the spaced repetition system.
+    pub description: String,
+    /// The name of the spaced repetition system.
+    pub name: String,
+    /// `position` of the passing stage.
+    pub passing_stage_position: u32,
+    /// A collection of stages.
+    pub stages: Vec<Stage>,
+    /// `position` of the starting stage.
+    pub starting_stage_position: u32,
+    /// `position` of the unlocking stage.
+    pub unlocking_stage_position: u32,
+}
+
+impl SpacedRepetitionSystem {
+    /// Look up a stage by its `position`.
+    pub fn stage(&self, position: u32) -> Option<&Stage> {
+        self.stages.iter().find(|stage| stage.position == position)
+    }
+
+    /// Whether an assignment at `position` counts as passed, i.e. it is at or
+    /// above the passing stage.
+    pub fn is_passed(&self, position: u32) -> bool {
+        position >= self.passing_stage_position
+    }
+
+    /// Whether an assignment at `position` has been burned.
+    pub fn is_burned(&self, position: u32) -> bool {
+        position >= self.burning_stage_position
+    }
+
+    /// The human-readable name WaniKani gives to the stage at `position`,
+    /// e.g. `"Apprentice III"` or `"Burned"`.
+    ///
+    /// The names are derived from the system's stage positions: stages from
+    /// the starting stage up to the passing stage are "Apprentice", the two
+    /// stages before the burning stage are "Master" and "Enlightened", and
+    /// everything from the passing stage up to "Master" is "Guru". Returns
+    /// `None` if `position` is not a stage of this system.
+    pub fn stage_name(&self, position: u32) -> Option<String> {
+        self.stage(position)?;
+
+        let name = if position <= self.unlocking_stage_position {
+            "Initiate".to_string()
+        } else if position >= self.burning_stage_position {
+            "Burned".to_string()
+        } else if position + 1 == self.burning_stage_position {
+            "Enlightened".to_string()
+        } else if position + 2 == self.burning_stage_position {
+            "Master".to_string()
+        } else if position >= self.passing_stage_position {
+            numbered("Guru", position - self.passing_stage_position + 1)
+        } else {
+            numbered(
+                "Apprentice",
+                position.saturating_sub(self.starting_stage_position) + 1,
+            )
+        };
+
+        Some(name)
+    }
+}
+
+fn numbered(group: &str, number: u32) -> String {
+    const NUMERALS: [&str; 10] = ["I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX", "X"];
+
+    match NUMERALS.get(number as usize - 1) {
+        Some(numeral) => format!("{group} {numeral}"),
+        None => format!("{group} {number}"),
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+/// A single stage of a spaced repetition system.
+pub struct Stage {
+    /// The length of time added to the time of review registration, adjusted to
+    /// the beginning of the hour.
+    pub interval: Option<u64>,
+    /// Unit of time.
+    pub interval_unit: Option<IntervalUnit>,
+    /// The `position` of the stage within the continuous order.
+    pub position: u32,
+}
+
+impl Stage {
+    /// The stage's interval as a [`Duration`]. Returns `None` for stages which
+    /// are not scheduled for review, such as the unlocking and burning stages.
+    pub fn interval_duration(&self) -> Option<Duration> {
+        Some(self.interval_unit?.to_duration(self.interval?))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+/// The unit of a stage's `interval`.
+pub enum IntervalUnit {
+    /// Milliseconds
+    Milliseconds,
+    /// Seconds
+    Seconds,
+    /// Minutes
+    Minutes,
+    /// Hours
+    Hours,
+    /// Days
+    Days,
+    /// Weeks
+    Weeks,
+}
+
+impl IntervalUnit {
+    /// Convert an interval expressed in this unit into a [`Duration`].
+    /// Intervals too long to be represented saturate to [`Duration::MAX`].
+    pub fn to_duration(self, interval: u64) -> Duration {
+        let interval = i64::try_from(interval).unwrap_or(i64::MAX);
+        match self {
+            Self::Milliseconds => Duration::try_milliseconds(interval),
+            Self::Seconds => Duration::try_seconds(interval),
+            Self::Minutes => Duration::try_minutes(interval),
+            Self::Hours => Duration::try_hours(interval),
+            Self::Days => Duration::try_days(interval),
+            Self::Weeks => Duration::try_weeks(interval),
+        }
+        .unwrap_or(Duration::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration, Utc};
+
+    use crate::{Resource, ResourceCommon, ResourceType};
+
+    use super::{IntervalUnit, SpacedRepetitionSystem, Stage};
+
+    #[test]
+    fn test_deserialize_spaced_repetition_system() {
+        let json = include_str!("../test_files/spaced_repetition_system.json");
+
+        let srs: Resource<SpacedRepetitionSystem> =
+            serde_json::from_str(json).expect("Deserialize");
+
+        assert_eq!(srs.id, 1);
+
+        let common = srs.common;
+        assert_eq!(common.object, ResourceType::SpacedRepetitionSystem);
+        assert_eq!(
+            common.url,
+            "https://api.wanikani.com/v2/spaced_repetition_systems/1"
+                .parse()
+                .expect("URL")
+        );
+        assert_eq!(
+            common.data_updated_at.expect("Timestamp"),
+            DateTime::parse_from_rfc3339("2020-06-09T03:36:51.134752Z").expect("Timestamp")
+        );
+
+        let data = srs.data;
+        assert_eq!(
+            data.created_at,
+            DateTime::parse_from_rfc3339("2020-05-21T20:46:06.464460Z").expect("Timestamp")
+        );
+        assert_eq!(data.name, "Default system for dictionary subjects");
+        assert_eq!(data.unlocking_stage_position, 0);
+        assert_eq!(data.starting_stage_position, 1);
+        assert_eq!(data.passing_stage_position, 5);
+        assert_eq!(data.burning_stage_position, 9);
+        assert_eq!(data.stages.len(), 10);
+        assert_eq!(
+            data.stages[0],
+            Stage {
+                interval: None,
+                interval_unit: None,
+                position: 0
+            }
+        );
+        assert_eq!(
+            data.stages[1],
+            Stage {
+                interval: Some(14400),
+                interval_unit: Some(IntervalUnit::Seconds),
+                position: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_serialize_spaced_repetition_system() {
+        let data = SpacedRepetitionSystem {
+            burning_stage_position: 2,
+            created_at: Utc::now(),
+            description: "A short one".into(),
+            name: "Tiny".into(),
+            passing_stage_position: 1,
+            stages: vec![
+                Stage {
+                    interval: None,
+                    interval_unit: None,
+                    position: 0,
+                },
+                Stage {
+                    interval: Some(3),
+                    interval_unit: Some(IntervalUnit::Days),
+                    position: 1,
+                },
+                Stage {
+                    interval: None,
+                    interval_unit: None,
+                    position: 2,
+                },
+            ],
+            starting_stage_position: 1,
+            unlocking_stage_position: 0,
+        };
+        let common = ResourceCommon {
+            data_updated_at: Some(Utc::now()),
+            object: ResourceType::SpacedRepetitionSystem,
+            url: "https://some.url/srs".parse().expect("URL"),
+        };
+        let srs = Resource {
+            common,
+            data,
+            id: 69,
+        };
+
+        let json = serde_json::to_string(&srs).expect("Serialize");
+
+        let new_srs: Resource<SpacedRepetitionSystem> =
+            serde_json::from_str(&json).expect("Deserialize");
+
+        assert_eq!(srs, new_srs);
+    }
+
+    #[test]
+    fn test_stage_helpers() {
+        let json = include_str!("../test_files/spaced_repetition_system.json");
+
+        let srs: Resource<SpacedRepetitionSystem> =
+            serde_json::from_str(json).expect("Deserialize");
+        let srs = srs.data;
+
+        let names = (0..=9)
+            .map(|position| srs.stage_name(position).expect("Stage exists"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "Initiate",
+                "Apprentice I",
+                "Apprentice II",
+                "Apprentice III",
+                "Apprentice IV",
+                "Guru I",
+                "Guru II",
+                "Master",
+                "Enlightened",
+                "Burned"
+            ]
+        );
+        assert!(srs.stage_name(10).is_none());
+
+        assert!(!srs.is_passed(4));
+        assert!(srs.is_passed(5));
+        assert!(!srs.is_burned(8));
+        assert!(srs.is_burned(9));
+
+        assert_eq!(
+            srs.stage(1).expect("Stage").interval_duration(),
+            Some(Duration::hours(4))
+        );
+        assert!(srs.stage(9).expect("Stage").interval_duration().is_none());
+        assert_eq!(IntervalUnit::Weeks.to_duration(2), Duration::days(14));
+        assert_eq!(IntervalUnit::Weeks.to_duration(u64::MAX), Duration::MAX);
+        assert_eq!(IntervalUnit::Seconds.to_duration(u64::MAX), Duration::MAX);
+    }
+}
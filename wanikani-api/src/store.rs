@@ -0,0 +1,552 @@
+//! A local SQLite cache of WaniKani data, kept up to date with incremental
+//! syncs.
+//!
+//! A [`Store`] keeps a copy of the user's subjects, assignments, review
+//! statistics, study materials, level progressions, resets and voice actors,
+//! along with the time each collection was last synced. [`Store::sync`] asks
+//! the API only for resources updated since then, so after the first sync,
+//! starting up an application costs a handful of small requests instead of
+//! re-downloading every subject.
+//!
+//! ### Example
+//! ```rust,no_run
+//! # use wanikani_api::{client::WKClient, store::Store};
+//! # let client = WKClient::new("MY_TOKEN".to_string(), reqwest::Client::default());
+//! # async move {
+//! let mut store = Store::open("wanikani.sqlite3").unwrap();
+//! let report = store.sync(&client).await.unwrap();
+//! println!("{} subjects changed", report.subjects);
+//!
+//! let subjects = store.subjects().unwrap();
+//! # };
+//! ```
+
+use std::{future::Future, path::Path};
+
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::{de::DeserializeOwned, Serialize};
+use thiserror::Error as E;
+
+use crate::{
+    assignment::Assignment,
+    client::{
+        AssignmentFilter, IdFilter, ReviewStatisticFilter, StudyMaterialFilter, SubjectFilter,
+        WKClient,
+    },
+    level_progression::LevelProgression,
+    reset::Reset,
+    review_statistic::ReviewStatistic,
+    study_material::StudyMaterial,
+    subject::Subject,
+    voice_actor::VoiceActor,
+    Collection, Error, Id, Resource, Timestamp,
+};
+
+/// The version of the database schema created by this module, stored in the
+/// database's `user_version`.
+const SCHEMA_VERSION: i32 = 1;
+
+#[derive(Debug, E)]
+/// Possible error conditions when using a [`Store`].
+pub enum StoreError {
+    #[error("Database error: {0}")]
+    /// The database could not be read or written.
+    Database(#[from] rusqlite::Error),
+    #[error("API error: {0}")]
+    /// Fetching updates from WaniKani failed. Nothing from the failed
+    /// collection was written to the database.
+    Api(#[from] Error),
+    #[error("Invalid stored resource: {0}")]
+    /// A resource in the database could not be deserialized.
+    Serialization(#[from] serde_json::Error),
+    #[error("Unsupported schema version {0}")]
+    /// The database was created by a newer version of this crate.
+    UnsupportedSchema(i32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The collections kept by a [`Store`].
+pub enum StoredCollection {
+    /// Assignments.
+    Assignments,
+    /// Level progressions.
+    LevelProgressions,
+    /// Resets.
+    Resets,
+    /// Review statistics.
+    ReviewStatistics,
+    /// Study materials.
+    StudyMaterials,
+    /// Subjects.
+    Subjects,
+    /// Voice actors.
+    VoiceActors,
+}
+
+impl StoredCollection {
+    /// Every stored collection.
+    pub const ALL: [StoredCollection; 7] = [
+        Self::Assignments,
+        Self::LevelProgressions,
+        Self::Resets,
+        Self::ReviewStatistics,
+        Self::StudyMaterials,
+        Self::Subjects,
+        Self::VoiceActors,
+    ];
+
+    /// The name of the table holding this collection, which matches the name
+    /// of its API endpoint.
+    pub fn table(self) -> &'static str {
+        match self {
+            Self::Assignments => "assignments",
+            Self::LevelProgressions => "level_progressions",
+            Self::Resets => "resets",
+            Self::ReviewStatistics => "review_statistics",
+            Self::StudyMaterials => "study_materials",
+            Self::Subjects => "subjects",
+            Self::VoiceActors => "voice_actors",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/// The number of resources added or updated in each collection by a call to
+/// [`Store::sync`].
+pub struct SyncReport {
+    /// Assignments added or updated.
+    pub assignments: usize,
+    /// Level progressions added or updated.
+    pub level_progressions: usize,
+    /// Resets added or updated.
+    pub resets: usize,
+    /// Review statistics added or updated.
+    pub review_statistics: usize,
+    /// Study materials added or updated.
+    pub study_materials: usize,
+    /// Subjects added or updated.
+    pub subjects: usize,
+    /// Voice actors added or updated.
+    pub voice_actors: usize,
+}
+
+impl SyncReport {
+    /// The total number of resources added or updated.
+    pub fn total(&self) -> usize {
+        self.assignments
+            + self.level_progressions
+            + self.resets
+            + self.review_statistics
+            + self.study_materials
+            + self.subjects
+            + self.voice_actors
+    }
+}
+
+#[derive(Debug)]
+/// A local cache of WaniKani data backed by an SQLite database.
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    /// Open the database at `path`, creating it if it does not exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        Self::from_connection(Connection::open(path)?)
+    }
+
+    /// Open a database held entirely in memory. Nothing is persisted once
+    /// the store is dropped.
+    pub fn open_in_memory() -> Result<Self, StoreError> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self, StoreError> {
+        let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > SCHEMA_VERSION {
+            return Err(StoreError::UnsupportedSchema(version));
+        }
+
+        if version < SCHEMA_VERSION {
+            let mut schema = String::from(
+                "CREATE TABLE IF NOT EXISTS sync_state (
+                    collection TEXT PRIMARY KEY,
+                    updated_after TEXT NOT NULL
+                );",
+            );
+            for collection in StoredCollection::ALL {
+                schema.push_str(&format!(
+                    "CREATE TABLE IF NOT EXISTS {} (
+                        id INTEGER PRIMARY KEY,
+                        data_updated_at TEXT,
+                        data TEXT NOT NULL
+                    );",
+                    collection.table()
+                ));
+            }
+            schema.push_str(&format!("PRAGMA user_version = {SCHEMA_VERSION};"));
+            conn.execute_batch(&schema)?;
+        }
+
+        Ok(Self { conn })
+    }
+
+    /// Fetch everything that has changed since the last sync and save it.
+    ///
+    /// Each collection is requested with its `updated_after` filter set to
+    /// the `data_updated_at` of the previous sync, so the first sync
+    /// downloads everything and later syncs only download the changes. Each
+    /// collection is written in its own transaction; if a request fails, the
+    /// collections already synced are kept and the failed one is retried in
+    /// full on the next call.
+    pub async fn sync(&mut self, client: &WKClient) -> Result<SyncReport, StoreError> {
+        Ok(SyncReport {
+            subjects: self
+                .sync_collection(
+                    client,
+                    StoredCollection::Subjects,
+                    |updated_after| async move {
+                        client
+                            .get_subjects(&SubjectFilter {
+                                updated_after,
+                                ..SubjectFilter::default()
+                            })
+                            .await
+                    },
+                )
+                .await?,
+            assignments: self
+                .sync_collection(
+                    client,
+                    StoredCollection::Assignments,
+                    |updated_after| async move {
+                        client
+                            .get_assignments(&AssignmentFilter {
+                                updated_after,
+                                ..AssignmentFilter::default()
+                            })
+                            .await
+                    },
+                )
+                .await?,
+            review_statistics: self
+                .sync_collection(
+                    client,
+                    StoredCollection::ReviewStatistics,
+                    |updated_after| async move {
+                        client
+                            .get_review_statistics(&ReviewStatisticFilter {
+                                updated_after,
+                                ..ReviewStatisticFilter::default()
+                            })
+                            .await
+                    },
+                )
+                .await?,
+            study_materials: self
+                .sync_collection(
+                    client,
+                    StoredCollection::StudyMaterials,
+                    |updated_after| async move {
+                        client
+                            .get_study_materials(&StudyMaterialFilter {
+                                updated_after,
+                                ..StudyMaterialFilter::default()
+                            })
+                            .await
+                    },
+                )
+                .await?,
+            level_progressions: self
+                .sync_collection(
+                    client,
+                    StoredCollection::LevelProgressions,
+                    |updated_after| async move {
+                        client
+                            .get_level_progressions(&IdFilter {
+                                updated_after,
+                                ..IdFilter::default()
+                            })
+                            .await
+                    },
+                )
+                .await?,
+            resets: self
+                .sync_collection(
+                    client,
+                    StoredCollection::Resets,
+                    |updated_after| async move {
+                        client
+                            .get_resets(&IdFilter {
+                                updated_after,
+                                ..IdFilter::default()
+                            })
+                            .await
+                    },
+                )
+                .await?,
+            voice_actors: self
+                .sync_collection(
+                    client,
+                    StoredCollection::VoiceActors,
+                    |updated_after| async move {
+                        client
+                            .get_voice_actors(&IdFilter {
+                                updated_after,
+                                ..IdFilter::default()
+                            })
+                            .await
+                    },
+                )
+                .await?,
+        })
+    }
+
+    async fn sync_collection<T, F, Fut>(
+        &mut self,
+        client: &WKClient,
+        collection: StoredCollection,
+        fetch: F,
+    ) -> Result<usize, StoreError>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce(Option<Timestamp>) -> Fut,
+        Fut: Future<Output = Result<Collection<T>, Error>>,
+    {
+        let first_page = fetch(self.last_synced(collection)?).await?;
+        let synced_at = first_page.common.data_updated_at;
+        let resources = client.collect_all(first_page).await?;
+
+        let tx = self.conn.transaction()?;
+        save(&tx, collection, &resources)?;
+        if let Some(synced_at) = synced_at {
+            tx.execute(
+                "INSERT INTO sync_state (collection, updated_after) VALUES (?1, ?2)
+                 ON CONFLICT (collection) DO UPDATE SET updated_after = excluded.updated_after",
+                params![collection.table(), synced_at],
+            )?;
+        }
+        tx.commit()?;
+
+        Ok(resources.len())
+    }
+
+    /// The `data_updated_at` of `collection` as of the last sync, or `None`
+    /// if it has never been synced.
+    pub fn last_synced(
+        &self,
+        collection: StoredCollection,
+    ) -> Result<Option<Timestamp>, StoreError> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT updated_after FROM sync_state WHERE collection = ?1",
+                [collection.table()],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Delete every stored resource and sync timestamp, so the next sync
+    /// downloads everything again.
+    pub fn clear(&mut self) -> Result<(), StoreError> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM sync_state", [])?;
+        for collection in StoredCollection::ALL {
+            tx.execute(&format!("DELETE FROM {}", collection.table()), [])?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// All stored assignments, in ascending `id` order.
+    pub fn assignments(&self) -> Result<Vec<Resource<Assignment>>, StoreError> {
+        self.load_all(StoredCollection::Assignments)
+    }
+
+    /// The stored assignment with the given `id`.
+    pub fn assignment(&self, id: Id) -> Result<Option<Resource<Assignment>>, StoreError> {
+        self.load(StoredCollection::Assignments, id)
+    }
+
+    /// All stored level progressions, in ascending `id` order.
+    pub fn level_progressions(&self) -> Result<Vec<Resource<LevelProgression>>, StoreError> {
+        self.load_all(StoredCollection::LevelProgressions)
+    }
+
+    /// All stored resets, in ascending `id` order.
+    pub fn resets(&self) -> Result<Vec<Resource<Reset>>, StoreError> {
+        self.load_all(StoredCollection::Resets)
+    }
+
+    /// All stored review statistics, in ascending `id` order.
+    pub fn review_statistics(&self) -> Result<Vec<Resource<ReviewStatistic>>, StoreError> {
+        self.load_all(StoredCollection::ReviewStatistics)
+    }
+
+    /// All stored study materials, in ascending `id` order.
+    pub fn study_materials(&self) -> Result<Vec<Resource<StudyMaterial>>, StoreError> {
+        self.load_all(StoredCollection::StudyMaterials)
+    }
+
+    /// All stored subjects, in ascending `id` order.
+    pub fn subjects(&self) -> Result<Vec<Resource<Subject>>, StoreError> {
+        self.load_all(StoredCollection::Subjects)
+    }
+
+    /// The stored subject with the given `id`.
+    pub fn subject(&self, id: Id) -> Result<Option<Resource<Subject>>, StoreError> {
+        self.load(StoredCollection::Subjects, id)
+    }
+
+    /// All stored voice actors, in ascending `id` order.
+    pub fn voice_actors(&self) -> Result<Vec<Resource<VoiceActor>>, StoreError> {
+        self.load_all(StoredCollection::VoiceActors)
+    }
+
+    fn load_all<T: DeserializeOwned>(
+        &self,
+        collection: StoredCollection,
+    ) -> Result<Vec<Resource<T>>, StoreError> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT data FROM {} ORDER BY id",
+            collection.table()
+        ))?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+        rows.map(|json| Ok(serde_json::from_str(&json?)?)).collect()
+    }
+
+    fn load<T: DeserializeOwned>(
+        &self,
+        collection: StoredCollection,
+        id: Id,
+    ) -> Result<Option<Resource<T>>, StoreError> {
+        let json: Option<String> = self
+            .conn
+            .query_row(
+                &format!("SELECT data FROM {} WHERE id = ?1", collection.table()),
+                [id],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        json.map(|json| serde_json::from_str(&json))
+            .transpose()
+            .map_err(Into::into)
+    }
+}
+
+fn save<T: Serialize>(
+    tx: &Transaction<'_>,
+    collection: StoredCollection,
+    resources: &[Resource<T>],
+) -> Result<(), StoreError> {
+    let mut stmt = tx.prepare(&format!(
+        "INSERT INTO {} (id, data_updated_at, data) VALUES (?1, ?2, ?3)
+         ON CONFLICT (id) DO UPDATE SET
+            data_updated_at = excluded.data_updated_at,
+            data = excluded.data",
+        collection.table()
+    ))?;
+
+    for resource in resources {
+        stmt.execute(params![
+            resource.id,
+            resource.common.data_updated_at,
+            serde_json::to_string(resource)?
+        ])?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use crate::test_util::MockWaniKani;
+
+    use super::{Store, StoredCollection, SyncReport};
+
+    #[tokio::test]
+    async fn test_sync() {
+        let server = MockWaniKani::start().await;
+        let client = server.client();
+        let mut store = Store::open_in_memory().expect("Store");
+
+        assert!(store
+            .last_synced(StoredCollection::Subjects)
+            .expect("Last synced")
+            .is_none());
+
+        let report = store.sync(&client).await.expect("Sync");
+        assert_eq!(
+            report,
+            SyncReport {
+                assignments: 1,
+                level_progressions: 1,
+                resets: 1,
+                review_statistics: 1,
+                study_materials: 1,
+                subjects: 4,
+                voice_actors: 2,
+            }
+        );
+        assert_eq!(store.subjects().expect("Subjects").len(), 4);
+        assert_eq!(
+            store.subject(440).expect("Subject").expect("Exists"),
+            server.dataset().subjects[1]
+        );
+        assert!(store.subject(1234).expect("Subject").is_none());
+        assert!(store
+            .last_synced(StoredCollection::Subjects)
+            .expect("Last synced")
+            .is_some());
+
+        let report = store.sync(&client).await.expect("Sync");
+        assert_eq!(report.total(), 0);
+
+        server.update_dataset(|dataset| {
+            let material = &mut dataset.study_materials[0];
+            material.data.meaning_note = Some("Updated".into());
+            material.common.data_updated_at = Some(Utc::now());
+        });
+
+        let report = store.sync(&client).await.expect("Sync");
+        assert_eq!(
+            report,
+            SyncReport {
+                study_materials: 1,
+                ..SyncReport::default()
+            }
+        );
+        let materials = store.study_materials().expect("Study materials");
+        assert_eq!(materials.len(), 1);
+        assert_eq!(materials[0].data.meaning_note.as_deref(), Some("Updated"));
+
+        store.clear().expect("Clear");
+        assert!(store.assignments().expect("Assignments").is_empty());
+        assert_eq!(store.sync(&client).await.expect("Sync").subjects, 4);
+    }
+
+    #[tokio::test]
+    async fn test_persistence() {
+        let server = MockWaniKani::start().await;
+        let dir = std::env::temp_dir().join(format!("wanikani-store-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("Temp dir");
+        let path = dir.join("store.sqlite3");
+
+        let mut store = Store::open(&path).expect("Store");
+        store.sync(&server.client()).await.expect("Sync");
+        drop(store);
+
+        let mut store = Store::open(&path).expect("Reopened store");
+        assert_eq!(store.voice_actors().expect("Voice actors").len(), 2);
+        let report = store.sync(&server.client()).await.expect("Sync");
+        assert_eq!(report.total(), 0);
+        drop(store);
+
+        std::fs::remove_dir_all(dir).expect("Cleanup");
+    }
+}
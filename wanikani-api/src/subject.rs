@@ -21,13 +21,16 @@
 //! - `<vocabulary></vocabulary>`
 //! - `<meaning></meaning>`
 //! - `<reading></reading>`
+//! - `<ja></ja>`
+//!
+//! The `mnemonic` feature provides a parser and renderers for this markup.
 
 use mime::Mime;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
 pub use crate::cross_feature::*;
-use crate::{voice_actor::Gender, Id, Timestamp};
+use crate::{Id, Timestamp};
 
 /// The `WaniKaniSubject` trait exists to help avoid footguns when requesting
 /// specific subjects with the API client.
@@ -77,6 +80,16 @@ impl Subject {
             Self::KanaVocabulary(_) => SubjectType::KanaVocabulary,
         }
     }
+
+    /// Return the attributes common to all subject types
+    pub fn common(&self) -> &SubjectCommon {
+        match self {
+            Self::Radical(subject) => &subject.common,
+            Self::Kanji(subject) => &subject.common,
+            Self::Vocabulary(subject) => &subject.common,
+            Self::KanaVocabulary(subject) => &subject.common,
+        }
+    }
 }
 
 impl From<Radical> for Subject {
@@ -294,6 +307,14 @@ pub struct CharacterImage {
     pub metadata: ImageMetadata,
 }
 
+impl CharacterImage {
+    /// The name the image is saved under in a media directory. See
+    /// [`PronunciationAudio::file_name`].
+    pub fn file_name(&self) -> Option<String> {
+        file_name(&self.url)
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(untagged)]
 /// Details about an image.
@@ -414,6 +435,32 @@ pub struct PronunciationAudio {
     pub metadata: AudioMetadata,
 }
 
+impl PronunciationAudio {
+    /// The name the audio is saved under in a media directory: the last
+    /// segment of its URL, prefixed with a hash of the whole URL. The query
+    /// is part of the hash, so a file updated in place on the CDN, or one
+    /// sharing its last segment with another, is saved under a different name.
+    pub fn file_name(&self) -> Option<String> {
+        file_name(&self.url)
+    }
+}
+
+fn file_name(url: &Url) -> Option<String> {
+    let name = url
+        .path_segments()?
+        .next_back()
+        .filter(|name| !name.is_empty())?;
+    // FNV-1a, which unlike the standard library's hasher gives the same
+    // result in every Rust release, so names stay valid between runs
+    let hash = url
+        .as_str()
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    Some(format!("{hash:016x}-{name}"))
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 /// A selected reading for the vocabulary.
 pub struct VocabularyReading {
@@ -0,0 +1,658 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    fs, io,
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use futures_util::future::BoxFuture;
+use reqwest::{
+    header::{AUTHORIZATION, CONTENT_TYPE},
+    RequestBuilder, Response as HttpResponse, ResponseBuilderExt,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error as E;
+use url::Url;
+use wiremock::{matchers::any, Mock, MockServer, Request, Respond, ResponseTemplate};
+
+use super::{MockConfig, MockDataset, MockWaniKani};
+use crate::{
+    client::{Middleware, WKClient, WKClientBuilder},
+    Error, URL_BASE,
+};
+
+/// Replaces the API token wherever it appears in a recorded cassette.
+const SCRUBBED_TOKEN: &str = "SCRUBBED_TOKEN";
+
+/// Set to record cassettes from the real API instead of replaying them. See
+/// [`CassetteClient::from_env`].
+pub const RECORD_VAR: &str = "WK_RECORD";
+
+/// The API token used when recording. See [`CassetteClient::from_env`].
+pub const TOKEN_VAR: &str = "API_KEY";
+
+/// The page size of the [`MockWaniKani`] recorded from, small enough that the
+/// fixtures span several pages.
+const MOCK_PAGE_SIZE: u32 = 2;
+
+/// Response headers that describe the connection or encoding rather than the
+/// response, and are not recorded.
+const SKIPPED_HEADERS: [&str; 5] = [
+    "connection",
+    "content-length",
+    "set-cookie",
+    "transfer-encoding",
+    "content-encoding",
+];
+
+#[derive(Debug, E)]
+/// An error reading or writing a cassette.
+pub enum CassetteError {
+    #[error("Cassette I/O error: {0}")]
+    /// The cassette file could not be read or written.
+    Io(#[from] io::Error),
+    #[error("Invalid cassette: {0}")]
+    /// The cassette file is not a valid cassette.
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+/// A sequence of requests made to the API, and the responses received.
+///
+/// Cassettes are stored as JSON. They are created by a [`Recorder`] and
+/// served by a [`CassettePlayer`].
+pub struct Cassette {
+    /// The base URL the cassette was recorded against. Occurrences of it in
+    /// response bodies are replaced with the player's URL on replay, so links
+    /// such as `pages.next_url` keep working.
+    pub base_url: Url,
+    /// The recorded interactions, in the order they happened.
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Read a cassette from a JSON file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CassetteError> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    /// Write the cassette to a JSON file, creating its parent directories if
+    /// needed.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), CassetteError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut json = serde_json::to_string_pretty(self)?;
+        json.push('\n');
+        fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+/// A single request and the response it received.
+pub struct Interaction {
+    /// The request.
+    pub request: RecordedRequest,
+    /// The response.
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+/// A recorded request. Headers are not recorded, so the API token is never
+/// stored.
+pub struct RecordedRequest {
+    /// The HTTP method.
+    pub method: String,
+    /// The path, relative to the cassette's base URL.
+    pub path: String,
+    /// The query string, if there was one.
+    pub query: Option<String>,
+    /// The JSON body, if there was one.
+    pub body: Option<Value>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+/// A recorded response.
+pub struct RecordedResponse {
+    /// The HTTP status code.
+    pub status: u16,
+    /// The response headers, with their names in lowercase.
+    pub headers: BTreeMap<String, String>,
+    /// The body. JSON bodies are stored as JSON, anything else as a string.
+    pub body: Option<Value>,
+}
+
+impl RecordedResponse {
+    fn content_type(&self) -> &str {
+        self.headers
+            .get(CONTENT_TYPE.as_str())
+            .map_or("application/json", String::as_str)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Recording {
+    /// Requests that have been sent but not answered yet.
+    pending: Vec<(Url, RecordedRequest)>,
+    interactions: Vec<Interaction>,
+    tokens: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+/// A [`Middleware`] that records the traffic of a [`WKClient`] into a
+/// [`Cassette`].
+///
+/// ### Example
+/// ```rust,no_run
+/// # use wanikani_api::{client::WKClientBuilder, test_util::Recorder, URL_BASE};
+/// # async {
+/// let recorder = Recorder::new(URL_BASE.parse().unwrap());
+/// let client = WKClientBuilder::default()
+///     .token("MY_TOKEN".into())
+///     .middleware(recorder.clone())
+///     .build()
+///     .unwrap();
+///
+/// client.get_summary().await.unwrap();
+///
+/// recorder.cassette().save("summary.json").unwrap();
+/// # };
+/// ```
+pub struct Recorder {
+    base_url: Url,
+    recording: Arc<Mutex<Recording>>,
+}
+
+impl Recorder {
+    /// Create a recorder for a client using `base_url`.
+    pub fn new(base_url: Url) -> Self {
+        Self {
+            base_url,
+            recording: Arc::default(),
+        }
+    }
+
+    /// The traffic recorded so far. Any API token the client sent is replaced
+    /// with a placeholder, wherever it appears.
+    pub fn cassette(&self) -> Cassette {
+        let recording = self.lock();
+        let cassette = Cassette {
+            base_url: self.base_url.clone(),
+            interactions: recording.interactions.clone(),
+        };
+
+        let mut json = serde_json::to_string(&cassette).expect("Cassette serializes");
+        for token in recording.tokens.iter().filter(|token| !token.is_empty()) {
+            json = json.replace(token.as_str(), SCRUBBED_TOKEN);
+        }
+        serde_json::from_str(&json).expect("Scrubbed cassette deserializes")
+    }
+
+    fn relative_path<'a>(&self, url: &'a Url) -> &'a str {
+        let base = self.base_url.path().trim_end_matches('/');
+        url.path().strip_prefix(base).unwrap_or_else(|| url.path())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Recording> {
+        self.recording.lock().expect("Lock not poisoned")
+    }
+}
+
+impl Middleware for Recorder {
+    fn on_request(&self, _endpoint: &str, request: RequestBuilder) -> RequestBuilder {
+        let Some(Ok(built)) = request.try_clone().map(RequestBuilder::build) else {
+            log::warn!("Request cannot be cloned, and will not be recorded");
+            return request;
+        };
+
+        let url = built.url().clone();
+        let recorded = RecordedRequest {
+            method: built.method().to_string(),
+            path: self.relative_path(&url).to_owned(),
+            query: url.query().map(ToString::to_string),
+            body: built
+                .body()
+                .and_then(|body| body.as_bytes())
+                .and_then(|body| serde_json::from_slice(body).ok()),
+        };
+        let token = built
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(ToString::to_string);
+
+        let mut recording = self.lock();
+        recording.pending.push((url, recorded));
+        if let Some(token) = token {
+            if !recording.tokens.contains(&token) {
+                recording.tokens.push(token);
+            }
+        }
+
+        request
+    }
+
+    fn on_response<'a>(
+        &'a self,
+        _endpoint: &'a str,
+        response: HttpResponse,
+    ) -> BoxFuture<'a, Result<HttpResponse, Error>> {
+        Box::pin(async move {
+            let url = response.url().clone();
+            let status = response.status();
+            let version = response.version();
+            let headers = response.headers().clone();
+            let body = response.bytes().await?;
+
+            {
+                let mut recording = self.lock();
+                let pending = recording
+                    .pending
+                    .iter()
+                    .position(|(pending, _)| *pending == url);
+                if let Some(index) = pending {
+                    let (_, request) = recording.pending.remove(index);
+                    let body = (!body.is_empty()).then(|| {
+                        serde_json::from_slice(&body).unwrap_or_else(|_| {
+                            Value::String(String::from_utf8_lossy(&body).into_owned())
+                        })
+                    });
+                    let response = RecordedResponse {
+                        status: status.as_u16(),
+                        headers: headers
+                            .iter()
+                            .filter(|(name, _)| !SKIPPED_HEADERS.contains(&name.as_str()))
+                            .filter_map(|(name, value)| {
+                                Some((name.to_string(), value.to_str().ok()?.to_owned()))
+                            })
+                            .collect(),
+                        body,
+                    };
+                    recording
+                        .interactions
+                        .push(Interaction { request, response });
+                } else {
+                    log::warn!("Response from {url} has no matching request, not recording");
+                }
+            }
+
+            let mut rebuilt = http::Response::builder()
+                .status(status)
+                .version(version)
+                .url(url);
+            if let Some(rebuilt_headers) = rebuilt.headers_mut() {
+                *rebuilt_headers = headers;
+            }
+            Ok(rebuilt.body(body).expect("Valid response").into())
+        })
+    }
+
+    fn on_error(&self, _endpoint: &str, error: &reqwest::Error) {
+        if let Some(url) = error.url() {
+            let mut recording = self.lock();
+            if let Some(index) = recording
+                .pending
+                .iter()
+                .position(|(pending, _)| pending == url)
+            {
+                recording.pending.remove(index);
+            }
+        }
+    }
+}
+
+struct CassetteResponder {
+    cassette: Cassette,
+    base_url: String,
+    played: Mutex<Vec<bool>>,
+}
+
+impl CassetteResponder {
+    fn find(&self, request: &Request) -> Option<&Interaction> {
+        let mut played = self.played.lock().expect("Lock not poisoned");
+        let matches = |interaction: &Interaction, with_query: bool| {
+            interaction.request.method == request.method.as_str()
+                && interaction.request.path == request.url.path()
+                && (!with_query || interaction.request.query.as_deref() == request.url.query())
+        };
+
+        // Prefer an exact match, but fall back to ignoring the query, which
+        // may contain timestamps that change on every run.
+        let index = [true, false].into_iter().find_map(|with_query| {
+            self.cassette
+                .interactions
+                .iter()
+                .zip(played.iter())
+                .position(|(interaction, played)| !played && matches(interaction, with_query))
+        })?;
+
+        played[index] = true;
+        Some(&self.cassette.interactions[index])
+    }
+}
+
+impl Respond for CassetteResponder {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let Some(interaction) = self.find(request) else {
+            let error = format!(
+                "No recorded interaction for {} {}",
+                request.method,
+                request.url.path()
+            );
+            log::error!("{error}");
+            return ResponseTemplate::new(501)
+                .set_body_json(serde_json::json!({ "error": error, "code": 501 }));
+        };
+
+        let response = &interaction.response;
+        let body = match response.body {
+            None => String::new(),
+            Some(Value::String(ref body)) if !response.content_type().contains("json") => {
+                body.clone()
+            }
+            Some(ref body) => body.to_string(),
+        };
+        let body = body.replace(
+            self.cassette.base_url.as_str().trim_end_matches('/'),
+            &self.base_url,
+        );
+
+        response
+            .headers
+            .iter()
+            .filter(|(name, _)| name.as_str() != CONTENT_TYPE.as_str())
+            .fold(
+                ResponseTemplate::new(response.status),
+                |template, (name, value)| template.insert_header(name.as_str(), value.as_str()),
+            )
+            .set_body_raw(body, response.content_type())
+    }
+}
+
+/// A local server that replays a [`Cassette`], with no network access.
+///
+/// Each request is answered with the first response not yet replayed whose
+/// request had the same method, path and query. If there is none, the query
+/// is ignored. Requests with no recorded response are answered with
+/// `501 Not Implemented`.
+///
+/// The server shuts down when this is dropped.
+pub struct CassettePlayer {
+    server: MockServer,
+}
+
+impl Debug for CassettePlayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CassettePlayer")
+            .field("uri", &self.server.uri())
+            .finish_non_exhaustive()
+    }
+}
+
+impl CassettePlayer {
+    /// Start a server replaying `cassette`.
+    pub async fn start(cassette: Cassette) -> Self {
+        let server = MockServer::start().await;
+        let played = Mutex::new(vec![false; cassette.interactions.len()]);
+
+        Mock::given(any())
+            .respond_with(CassetteResponder {
+                cassette,
+                base_url: server.uri(),
+                played,
+            })
+            .mount(&server)
+            .await;
+
+        Self { server }
+    }
+
+    /// The base URL of the server, to be passed to
+    /// [`WKClientBuilder::base_url`].
+    pub fn uri(&self) -> Url {
+        self.server.uri().parse().expect("Valid URL")
+    }
+
+    /// A [`WKClient`] connected to this server. The server accepts any token.
+    pub fn client(&self) -> WKClient {
+        WKClientBuilder::default()
+            .token(SCRUBBED_TOKEN.into())
+            .base_url(self.uri())
+            .build()
+            .expect("Valid client")
+    }
+}
+
+/// A [`WKClient`] that either records its traffic to a cassette file, or
+/// replays it from one.
+///
+/// When recording, the cassette is saved when this is dropped, unless the
+/// thread is panicking, so a failed test does not overwrite a good cassette.
+///
+/// ### Example
+/// ```rust,no_run
+/// # use wanikani_api::test_util::CassetteClient;
+/// # async {
+/// // Replays `test_files/cassettes/summary.json`, or records it from the
+/// // real API if `WK_RECORD` is set.
+/// let client = CassetteClient::from_env("test_files/cassettes/summary.json")
+///     .await
+///     .unwrap();
+///
+/// let summary = client.get_summary().await.unwrap();
+/// # };
+/// ```
+pub struct CassetteClient {
+    client: WKClient,
+    path: PathBuf,
+    recorder: Option<Recorder>,
+    /// Kept so the server outlives the client.
+    _player: Option<CassettePlayer>,
+    /// Kept so the server outlives the client.
+    _mock: Option<MockWaniKani>,
+}
+
+impl Debug for CassetteClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CassetteClient")
+            .field("client", &self.client)
+            .field("path", &self.path)
+            .field("recorder", &self.recorder)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CassetteClient {
+    /// Record to `path` if the [`RECORD_VAR`] environment variable is set,
+    /// using the token in [`TOKEN_VAR`] against the real API. Otherwise,
+    /// replay `path`.
+    ///
+    /// ### Panics
+    ///
+    /// Panics if recording and no token is set.
+    pub async fn from_env(path: impl Into<PathBuf>) -> Result<Self, CassetteError> {
+        if std::env::var_os(RECORD_VAR).is_some() {
+            let token = std::env::var(TOKEN_VAR)
+                .unwrap_or_else(|_| panic!("{TOKEN_VAR} must be set to record cassettes"));
+            let base_url = URL_BASE.parse().expect("Valid URL");
+            Ok(Self::record(path, base_url, token))
+        } else {
+            Self::replay(path).await
+        }
+    }
+
+    /// Record the client's traffic against `base_url` to `path`.
+    pub fn record(path: impl Into<PathBuf>, base_url: Url, token: String) -> Self {
+        let recorder = Recorder::new(base_url.clone());
+        let client = WKClientBuilder::default()
+            .token(token)
+            .base_url(base_url)
+            .middleware(recorder.clone())
+            .build()
+            .expect("Valid client");
+
+        Self {
+            client,
+            path: path.into(),
+            recorder: Some(recorder),
+            _player: None,
+            _mock: None,
+        }
+    }
+
+    /// Record the client's traffic against a [`MockWaniKani`] serving
+    /// [`MockDataset::fixtures`] to `path`. Collections are served two
+    /// resources per page, so that pagination is recorded as well.
+    ///
+    /// The cassette holds the mock's synthetic data rather than real API
+    /// traffic, so it suits fixtures for code built on the mock, but not
+    /// regression tests against the API itself. [`from_env`](Self::from_env)
+    /// never records this way.
+    pub async fn record_mock(path: impl Into<PathBuf>) -> Self {
+        let mock = MockWaniKani::start_with(
+            MockDataset::fixtures(),
+            MockConfig {
+                page_size: Some(MOCK_PAGE_SIZE),
+                ..MockConfig::default()
+            },
+        )
+        .await;
+
+        let mut client = Self::record(path, mock.uri(), mock.token().to_owned());
+        client._mock = Some(mock);
+        client
+    }
+
+    /// Replay the cassette at `path`.
+    pub async fn replay(path: impl Into<PathBuf>) -> Result<Self, CassetteError> {
+        let path = path.into();
+        let player = CassettePlayer::start(Cassette::load(&path)?).await;
+
+        Ok(Self {
+            client: player.client(),
+            path,
+            recorder: None,
+            _player: Some(player),
+            _mock: None,
+        })
+    }
+
+    /// Whether the client is recording, rather than replaying.
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+}
+
+impl Deref for CassetteClient {
+    type Target = WKClient;
+
+    fn deref(&self) -> &Self::Target {
+        &self.client
+    }
+}
+
+impl Drop for CassetteClient {
+    fn drop(&mut self) {
+        if let Some(ref recorder) = self.recorder {
+            if thread::panicking() {
+                log::warn!("Not saving {} after a panic", self.path.display());
+                return;
+            }
+            if let Err(error) = recorder.cassette().save(&self.path) {
+                panic!("Could not save {}: {error}", self.path.display());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use futures_util::TryStreamExt;
+
+    use super::{Cassette, CassetteClient, CassettePlayer};
+    use crate::{
+        client::{Fetched, SubjectFilter, Validators},
+        test_util::{MockConfig, MockDataset, MockWaniKani},
+    };
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let server = MockWaniKani::start_with(
+            MockDataset::fixtures(),
+            MockConfig {
+                page_size: Some(3),
+                ..MockConfig::default()
+            },
+        )
+        .await;
+        let path =
+            std::env::temp_dir().join(format!("wanikani-cassette-{}.json", uuid::Uuid::new_v4()));
+
+        let client = CassetteClient::record(&path, server.uri(), server.token().into());
+        assert!(client.is_recording());
+        let recorded: Vec<_> = client
+            .stream_subjects(&SubjectFilter::default())
+            .try_collect()
+            .await
+            .expect("Subjects");
+        let (summary, validators) = client
+            .get_summary_if_modified(&Validators::default())
+            .await
+            .expect("Summary")
+            .into_modified()
+            .expect("Modified");
+        assert_eq!(
+            client
+                .get_summary_if_modified(&validators)
+                .await
+                .expect("Summary"),
+            Fetched::NotModified
+        );
+        drop(client);
+
+        let cassette = Cassette::load(&path).expect("Saved cassette");
+        std::fs::remove_file(&path).expect("Remove cassette");
+        let json = serde_json::to_string(&cassette).expect("Serialize");
+        assert!(!json.contains(server.token()));
+        assert!(json.contains(&server.uri().to_string()));
+        assert_eq!(cassette.interactions.len(), 4);
+        assert_eq!(cassette.interactions[1].request.path, "/subjects");
+        assert!(cassette.interactions[1].request.query.is_some());
+        drop(server);
+
+        let player = CassettePlayer::start(cassette).await;
+        let client = player.client();
+        let replayed: Vec<_> = client
+            .stream_subjects(&SubjectFilter::default())
+            .try_collect()
+            .await
+            .expect("Replayed subjects");
+        assert_eq!(replayed, recorded);
+        let fetched = client
+            .get_summary_if_modified(&Validators::default())
+            .await
+            .expect("Replayed summary");
+        assert_eq!(fetched, Fetched::Modified(summary, validators.clone()));
+        assert_eq!(
+            client
+                .get_summary_if_modified(&validators)
+                .await
+                .expect("Summary"),
+            Fetched::NotModified
+        );
+        assert!(client.get_summary().await.is_err());
+
+        let missing = CassetteClient::replay(PathBuf::from("missing.json")).await;
+        assert!(missing.is_err());
+    }
+}
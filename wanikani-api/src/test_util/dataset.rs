@@ -0,0 +1,103 @@
+use crate::{
+    assignment::Assignment, level_progression::LevelProgression, reset::Reset, review::Review,
+    review_statistic::ReviewStatistic, spaced_repetition_system::SpacedRepetitionSystem,
+    study_material::StudyMaterial, subject::Subject, summary::Summary, user::User,
+    voice_actor::VoiceActor, Collection, Resource,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The data served by a [`MockWaniKani`](super::MockWaniKani) server.
+///
+/// Every field is served from the endpoint of the same name. Collections are
+/// served in ascending `id` order, regardless of the order they are stored in.
+/// Write requests, such as
+/// [`create_study_material`](crate::client::WKClient::create_study_material),
+/// modify the dataset in place.
+pub struct MockDataset {
+    /// Served from `/assignments`.
+    pub assignments: Vec<Resource<Assignment>>,
+    /// Served from `/level_progressions`.
+    pub level_progressions: Vec<Resource<LevelProgression>>,
+    /// Served from `/resets`.
+    pub resets: Vec<Resource<Reset>>,
+    /// Served from `/reviews`.
+    pub reviews: Vec<Resource<Review>>,
+    /// Served from `/review_statistics`.
+    pub review_statistics: Vec<Resource<ReviewStatistic>>,
+    /// Served from `/spaced_repetition_systems`.
+    pub spaced_repetition_systems: Vec<Resource<SpacedRepetitionSystem>>,
+    /// Served from `/study_materials`.
+    pub study_materials: Vec<Resource<StudyMaterial>>,
+    /// Served from `/subjects`.
+    pub subjects: Vec<Resource<Subject>>,
+    /// Served from `/summary`. Requests return `404 Not Found` if unset.
+    pub summary: Option<Summary>,
+    /// Served from `/user`. Requests return `404 Not Found` if unset.
+    pub user: Option<User>,
+    /// Served from `/voice_actors`.
+    pub voice_actors: Vec<Resource<VoiceActor>>,
+}
+
+impl MockDataset {
+    /// A dataset made up of the example resources from the WaniKani API
+    /// documentation.
+    ///
+    /// This contains one of each subject type (radical `1`, kanji `440`,
+    /// vocabulary `2467` and kana vocabulary `9210`), the default spaced
+    /// repetition system, both voice actors, and a single example of every
+    /// other resource.
+    pub fn fixtures() -> Self {
+        let voice_actors: Collection<VoiceActor> =
+            fixture(include_str!("../../test_files/voice_actors.json"));
+
+        Self {
+            assignments: vec![fixture(include_str!("../../test_files/assignment.json"))],
+            level_progressions: vec![fixture(include_str!(
+                "../../test_files/level_progression.json"
+            ))],
+            resets: vec![fixture(include_str!("../../test_files/reset.json"))],
+            reviews: vec![fixture(include_str!("../../test_files/review.json"))],
+            review_statistics: vec![fixture(include_str!(
+                "../../test_files/review_statistic.json"
+            ))],
+            spaced_repetition_systems: vec![fixture(include_str!(
+                "../../test_files/spaced_repetition_system.json"
+            ))],
+            study_materials: vec![fixture(include_str!(
+                "../../test_files/study_material.json"
+            ))],
+            subjects: vec![
+                fixture(include_str!("../../test_files/radical.json")),
+                fixture(include_str!("../../test_files/kanji.json")),
+                fixture(include_str!("../../test_files/vocabulary.json")),
+                fixture(include_str!("../../test_files/kana_vocabulary.json")),
+            ],
+            summary: Some(fixture(include_str!("../../test_files/summary.json"))),
+            user: Some(fixture(include_str!("../../test_files/user.json"))),
+            voice_actors: voice_actors.data,
+        }
+    }
+}
+
+fn fixture<T>(json: &str) -> T
+where
+    T: for<'de> serde::Deserialize<'de>,
+{
+    serde_json::from_str(json).expect("Fixtures are valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MockDataset;
+
+    #[test]
+    fn test_fixtures() {
+        let dataset = MockDataset::fixtures();
+
+        assert_eq!(dataset.subjects.len(), 4);
+        assert_eq!(dataset.voice_actors.len(), 2);
+        assert_eq!(dataset.spaced_repetition_systems.len(), 1);
+        assert!(dataset.user.is_some());
+        assert!(dataset.summary.is_some());
+    }
+}
@@ -0,0 +1,382 @@
+//! An offline, in-process imitation of the WaniKani API for use in tests.
+//!
+//! [`MockWaniKani`] serves a [`MockDataset`] over HTTP on a local port, and
+//! behaves like the real API closely enough for a [`WKClient`] to be pointed
+//! at it: requests are authenticated with a bearer token, collections are
+//! paginated and filtered, responses carry `ETag` and rate limit headers, and
+//! write requests update the dataset.
+//!
+//! Traffic to the real API can also be recorded into a [`Cassette`] by a
+//! [`Recorder`], and replayed later by a [`CassettePlayer`] with no network
+//! access. [`CassetteClient`] combines the two for tests, recording when the
+//! `WK_RECORD` environment variable is set and replaying otherwise.
+//!
+//! ### Example
+//! ```rust
+//! # use wanikani_api::test_util::MockWaniKani;
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let server = MockWaniKani::start().await;
+//! let client = server.client();
+//!
+//! let subjects = client
+//!     .get_subjects(&Default::default())
+//!     .await
+//!     .expect("Subjects");
+//! assert_eq!(subjects.total_count, 4);
+//! # }
+//! ```
+
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use chrono::Duration;
+use url::Url;
+use wiremock::{matchers::any, Mock, MockServer};
+
+use crate::client::{WKClient, WKClientBuilder};
+
+mod cassette;
+mod dataset;
+mod responder;
+
+pub use cassette::{
+    Cassette, CassetteClient, CassetteError, CassettePlayer, Interaction, RecordedRequest,
+    RecordedResponse, Recorder, RECORD_VAR, TOKEN_VAR,
+};
+pub use dataset::MockDataset;
+
+use responder::{MockResponder, MockState};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Settings controlling how a [`MockWaniKani`] server behaves.
+pub struct MockConfig {
+    /// The API token the server accepts. Requests without it are rejected with
+    /// `401 Unauthorized`.
+    pub token: String,
+    /// Overrides the page size of every collection. By default, subjects and
+    /// reviews are served 1000 per page and everything else 500 per page, as
+    /// the real API does.
+    pub page_size: Option<u32>,
+    /// The number of requests allowed per rate limit window. Requests beyond
+    /// this are rejected with `429 Too Many Requests`. `None` disables rate
+    /// limiting entirely.
+    pub rate_limit: Option<u32>,
+    /// The length of a rate limit window.
+    pub rate_limit_window: Duration,
+}
+
+impl Default for MockConfig {
+    fn default() -> Self {
+        Self {
+            token: "MOCK_TOKEN".into(),
+            page_size: None,
+            rate_limit: Some(60),
+            rate_limit_window: Duration::seconds(60),
+        }
+    }
+}
+
+/// A running mock WaniKani API server.
+///
+/// The server shuts down when this is dropped.
+pub struct MockWaniKani {
+    server: MockServer,
+    config: MockConfig,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl Debug for MockWaniKani {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MockWaniKani")
+            .field("uri", &self.server.uri())
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl MockWaniKani {
+    /// Start a server serving [`MockDataset::fixtures`] with the default
+    /// [`MockConfig`].
+    pub async fn start() -> Self {
+        Self::start_with(MockDataset::fixtures(), MockConfig::default()).await
+    }
+
+    /// Start a server serving `dataset`, configured by `config`.
+    pub async fn start_with(dataset: MockDataset, config: MockConfig) -> Self {
+        let server = MockServer::start().await;
+        let state = Arc::new(Mutex::new(MockState::new(dataset)));
+
+        Mock::given(any())
+            .respond_with(MockResponder {
+                base_url: server.uri().parse().expect("Valid URL"),
+                config: config.clone(),
+                state: state.clone(),
+            })
+            .mount(&server)
+            .await;
+
+        Self {
+            server,
+            config,
+            state,
+        }
+    }
+
+    /// The base URL of the server, to be passed to
+    /// [`WKClientBuilder::base_url`].
+    pub fn uri(&self) -> Url {
+        self.server.uri().parse().expect("Valid URL")
+    }
+
+    /// The token the server accepts.
+    pub fn token(&self) -> &str {
+        &self.config.token
+    }
+
+    /// A [`WKClientBuilder`] with the base URL and token already set, for
+    /// tests that need to configure the client further.
+    pub fn client_builder(&self) -> WKClientBuilder {
+        let mut builder = WKClientBuilder::default();
+        builder
+            .token(self.config.token.clone())
+            .base_url(self.uri());
+        builder
+    }
+
+    /// A [`WKClient`] connected to this server.
+    pub fn client(&self) -> WKClient {
+        self.client_builder().build().expect("Valid client")
+    }
+
+    /// A copy of the data currently being served.
+    pub fn dataset(&self) -> MockDataset {
+        self.state
+            .lock()
+            .expect("Lock not poisoned")
+            .dataset
+            .clone()
+    }
+
+    /// Modify the data being served. Changes are visible to every request
+    /// made after this returns.
+    pub fn update_dataset<R>(&self, f: impl FnOnce(&mut MockDataset) -> R) -> R {
+        f(&mut self.state.lock().expect("Lock not poisoned").dataset)
+    }
+
+    /// The number of requests the server has received.
+    pub async fn request_count(&self) -> usize {
+        self.server
+            .received_requests()
+            .await
+            .map_or(0, |requests| requests.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use futures_util::TryStreamExt;
+
+    use crate::{
+        client::{
+            AssignmentFilter, Fetched, RetryPolicy, StudyMaterialFilter, SubjectFilter, Validators,
+        },
+        review::{CreateReview, ReviewTarget},
+        study_material::CreateStudyMaterial,
+        subject::{Subject, SubjectType},
+        Collection, Error,
+    };
+
+    use super::{MockConfig, MockDataset, MockWaniKani};
+
+    #[tokio::test]
+    async fn test_pagination() {
+        let server = MockWaniKani::start_with(
+            MockDataset::fixtures(),
+            MockConfig {
+                page_size: Some(1),
+                ..MockConfig::default()
+            },
+        )
+        .await;
+        let client = server.client();
+
+        let first = client
+            .get_subjects(&SubjectFilter::default())
+            .await
+            .expect("Subjects");
+        assert_eq!(first.total_count, 4);
+        assert_eq!(first.data.len(), 1);
+        assert_eq!(first.data[0].id, 1);
+        assert!(first.pages.previous_url.is_none());
+
+        let next = first.pages.next_url.expect("Next page");
+        let second = client
+            .get_resource_by_url::<Collection<Subject>>(&next)
+            .await
+            .expect("Second page");
+        assert_eq!(second.data[0].id, 440);
+        assert!(second.pages.previous_url.is_some());
+
+        let ids: Vec<_> = client
+            .stream_subjects(&SubjectFilter::default())
+            .map_ok(|subject| subject.id)
+            .try_collect()
+            .await
+            .expect("All subjects");
+        assert_eq!(ids, [1, 440, 2467, 9210]);
+        assert_eq!(server.request_count().await, 6);
+    }
+
+    #[tokio::test]
+    async fn test_filters() {
+        let server = MockWaniKani::start().await;
+        let client = server.client();
+
+        let kanji = client
+            .get_subjects(&SubjectFilter {
+                types: Some(vec![SubjectType::Kanji]),
+                ..SubjectFilter::default()
+            })
+            .await
+            .expect("Subjects");
+        assert_eq!(kanji.total_count, 1);
+        assert_eq!(kanji.data[0].id, 440);
+
+        let by_slug = client
+            .get_subjects(&SubjectFilter {
+                slugs: Some(vec!["一".into()]),
+                levels: Some(vec![1]),
+                ..SubjectFilter::default()
+            })
+            .await
+            .expect("Subjects");
+        assert_eq!(by_slug.total_count, 2);
+
+        let assignments = client
+            .get_assignments(&AssignmentFilter {
+                subject_ids: Some(vec![8761]),
+                ..AssignmentFilter::default()
+            })
+            .await
+            .expect("Assignments");
+        assert_eq!(assignments.total_count, 1);
+
+        let missing = client.get_specific_subject::<Subject>(12345).await;
+        assert!(matches!(missing, Err(Error::NotFound { error, .. }) if error.code == 404));
+    }
+
+    #[tokio::test]
+    async fn test_unauthorized() {
+        let server = MockWaniKani::start().await;
+        let mut builder = server.client_builder();
+        let client = builder.token("WRONG".into()).build().expect("Client");
+
+        let result = client.get_user_information().await;
+        assert!(matches!(result, Err(Error::Unauthorized { error, .. }) if error.code == 401));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit() {
+        let server = MockWaniKani::start_with(
+            MockDataset::fixtures(),
+            MockConfig {
+                rate_limit: Some(1),
+                rate_limit_window: Duration::seconds(1),
+                ..MockConfig::default()
+            },
+        )
+        .await;
+
+        let client = server.client();
+        client.get_summary().await.expect("Summary");
+        let limited = client.get_summary().await;
+        assert!(matches!(limited, Err(Error::RateLimit { .. })));
+
+        let client = server
+            .client_builder()
+            .retry_policy(RetryPolicy {
+                rate_limit_margin: std::time::Duration::ZERO,
+                throttle_threshold: 0,
+                ..RetryPolicy::default()
+            })
+            .build()
+            .expect("Client");
+        client.get_summary().await.expect("Retried summary");
+    }
+
+    #[tokio::test]
+    async fn test_conditional_requests() {
+        let server = MockWaniKani::start().await;
+        let client = server.client();
+
+        let fetched = client
+            .get_study_materials_if_modified(
+                &StudyMaterialFilter::default(),
+                &Validators::default(),
+            )
+            .await
+            .expect("Study materials");
+        let (materials, validators) = fetched.into_modified().expect("Modified");
+        assert_eq!(materials.total_count, 1);
+        assert!(validators.etag.is_some());
+
+        let fetched = client
+            .get_study_materials_if_modified(&StudyMaterialFilter::default(), &validators)
+            .await
+            .expect("Study materials");
+        assert_eq!(fetched, Fetched::NotModified);
+
+        let create = CreateStudyMaterial {
+            subject_id: 1,
+            meaning_note: Some("Ground".into()),
+            reading_note: None,
+            meaning_synonyms: None,
+        };
+        client
+            .create_study_material(&create)
+            .await
+            .expect("Created");
+        let duplicate = client.create_study_material(&create).await;
+        assert!(matches!(duplicate, Err(Error::Unprocessable { .. })));
+
+        let fetched = client
+            .get_study_materials_if_modified(&StudyMaterialFilter::default(), &validators)
+            .await
+            .expect("Study materials");
+        assert_eq!(fetched.into_modified().expect("Modified").0.total_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_create_review() {
+        let server = MockWaniKani::start().await;
+        let client = server.client();
+        let assignment = server.dataset().assignments[0].clone();
+        let stage = assignment.data.srs_stage;
+
+        let created = client
+            .create_review(&CreateReview {
+                target: ReviewTarget::Assignment(assignment.id),
+                incorrect_meaning_answers: 0,
+                incorrect_reading_answers: 0,
+                created_at: None,
+            })
+            .await
+            .expect("Review");
+
+        assert_eq!(created.review.data.starting_srs_stage, stage.position());
+        assert_eq!(
+            created.review.data.ending_srs_stage,
+            stage.next().position()
+        );
+        assert_eq!(
+            created.resources_updated.assignment.data.srs_stage,
+            stage.next()
+        );
+        assert_eq!(server.dataset().reviews.len(), 2);
+        assert_eq!(server.dataset().assignments[0].data.srs_stage, stage.next());
+    }
+}
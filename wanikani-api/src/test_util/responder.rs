@@ -0,0 +1,1045 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{Duration, DurationRound, Utc};
+use serde::{Deserialize, Serialize};
+use url::Url;
+use wiremock::{Request, Respond, ResponseTemplate};
+
+use crate::{
+    assignment::{Assignment, AssignmentStart, InvalidSrsStage, SrsStage},
+    cross_feature::SubjectType,
+    review::{CreateReview, CreatedReview, Review, ReviewResourcesUpdated, ReviewTarget},
+    review_statistic::ReviewStatistic,
+    study_material::{CreateStudyMaterial, StudyMaterial, UpdateStudyMaterial},
+    user::UpdateUser,
+    Collection, Id, Pages, Resource, ResourceCommon, ResourceType, Timestamp, WanikaniError,
+};
+
+use super::{MockConfig, MockDataset};
+
+pub(super) struct MockState {
+    pub dataset: MockDataset,
+    window: Option<RateLimitWindow>,
+}
+
+impl MockState {
+    pub fn new(dataset: MockDataset) -> Self {
+        Self {
+            dataset,
+            window: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct RateLimitWindow {
+    reset_time: Timestamp,
+    count: u32,
+}
+
+pub(super) struct MockResponder {
+    pub base_url: Url,
+    pub config: MockConfig,
+    pub state: Arc<Mutex<MockState>>,
+}
+
+struct Reply {
+    status: u16,
+    body: String,
+}
+
+impl Reply {
+    fn ok<T: Serialize>(body: &T) -> Self {
+        Self::with_status(200, body)
+    }
+
+    fn created<T: Serialize>(body: &T) -> Self {
+        Self::with_status(201, body)
+    }
+
+    fn with_status<T: Serialize>(status: u16, body: &T) -> Self {
+        Self {
+            status,
+            body: serde_json::to_string(body).expect("Serializable"),
+        }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self::with_status(
+            status,
+            &WanikaniError {
+                code: status.into(),
+                error: Some(message.into()),
+            },
+        )
+    }
+
+    fn not_found() -> Self {
+        Self::error(404, "Not found")
+    }
+
+    fn unprocessable(message: impl Into<String>) -> Self {
+        Self::error(422, message)
+    }
+}
+
+impl Respond for MockResponder {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        // The mock server only sees the path and query of a request, so URLs
+        // in responses have to be rebuilt from the address it is bound to.
+        let mut request = request.clone();
+        let mut url = self.base_url.clone();
+        url.set_path(request.url.path());
+        url.set_query(request.url.query());
+        request.url = url;
+        let request = &request;
+
+        let mut state = self.state.lock().expect("Lock not poisoned");
+
+        let expected_auth = format!("Bearer {}", self.config.token);
+        let authorized = request
+            .headers
+            .get("Authorization")
+            .and_then(|value| value.to_str().ok())
+            .map_or(false, |value| value == expected_auth);
+        if !authorized {
+            return template(Reply::error(401, "Unauthorized. Nice try."));
+        }
+
+        let rate_limit = self.config.rate_limit.map(|limit| {
+            let now = Utc::now();
+            let window = match state.window {
+                Some(window) if window.reset_time > now => window,
+                _ => RateLimitWindow {
+                    reset_time: (now + self.config.rate_limit_window)
+                        .duration_trunc(Duration::seconds(1))
+                        .expect("Valid timestamp"),
+                    count: 0,
+                },
+            };
+            let window = RateLimitWindow {
+                count: window.count + 1,
+                ..window
+            };
+            state.window = Some(window);
+            (limit, window)
+        });
+
+        let reply = match rate_limit {
+            Some((limit, window)) if window.count > limit => {
+                Reply::error(429, "Rate limit exceeded")
+            }
+            _ => self.route(&mut state.dataset, request),
+        };
+
+        let mut response = template_with_etag(reply, request);
+        if let Some((limit, window)) = rate_limit {
+            response = response
+                .insert_header("Ratelimit-Limit", limit.to_string())
+                .insert_header(
+                    "Ratelimit-Remaining",
+                    limit.saturating_sub(window.count).to_string(),
+                )
+                .insert_header("Ratelimit-Reset", window.reset_time.timestamp().to_string());
+        }
+        response
+    }
+}
+
+fn template(reply: Reply) -> ResponseTemplate {
+    ResponseTemplate::new(reply.status).set_body_raw(reply.body, "application/json")
+}
+
+fn template_with_etag(reply: Reply, request: &Request) -> ResponseTemplate {
+    if reply.status != 200 || request.method != "GET" {
+        return template(reply);
+    }
+
+    let mut hasher = DefaultHasher::new();
+    reply.body.hash(&mut hasher);
+    let etag = format!("W/\"{:016x}\"", hasher.finish());
+
+    let not_modified = request
+        .headers
+        .get("If-None-Match")
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |value| value == etag);
+
+    if not_modified {
+        ResponseTemplate::new(304).insert_header("ETag", etag.as_str())
+    } else {
+        template(reply).insert_header("ETag", etag.as_str())
+    }
+}
+
+impl MockResponder {
+    fn route(&self, dataset: &mut MockDataset, request: &Request) -> Reply {
+        let segments: Vec<&str> = request
+            .url
+            .path_segments()
+            .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default();
+        let method = request.method.as_str();
+
+        let query = Query::new(&request.url);
+        let id = segments.get(1).map(|id| id.parse::<Id>());
+        let id = match id {
+            Some(Ok(id)) => Some(id),
+            Some(Err(_)) => return Reply::not_found(),
+            None => None,
+        };
+
+        let result = match (method, segments.first().copied(), id, segments.get(2)) {
+            ("GET", Some("assignments"), None, None) => self.assignments(dataset, request, &query),
+            ("GET", Some("assignments"), Some(id), None) => resource(&dataset.assignments, id),
+            ("PUT", Some("assignments"), Some(id), Some(&"start")) => {
+                start_assignment(dataset, request, id)
+            }
+            ("GET", Some("level_progressions"), None, None) => {
+                self.id_collection(&dataset.level_progressions, request, &query)
+            }
+            ("GET", Some("level_progressions"), Some(id), None) => {
+                resource(&dataset.level_progressions, id)
+            }
+            ("GET", Some("resets"), None, None) => {
+                self.id_collection(&dataset.resets, request, &query)
+            }
+            ("GET", Some("resets"), Some(id), None) => resource(&dataset.resets, id),
+            ("GET", Some("reviews"), None, None) => self.reviews(dataset, request, &query),
+            ("GET", Some("reviews"), Some(id), None) => resource(&dataset.reviews, id),
+            ("POST", Some("reviews"), None, None) => create_review(dataset, request),
+            ("GET", Some("review_statistics"), None, None) => {
+                self.review_statistics(dataset, request, &query)
+            }
+            ("GET", Some("review_statistics"), Some(id), None) => {
+                resource(&dataset.review_statistics, id)
+            }
+            ("GET", Some("spaced_repetition_systems"), None, None) => {
+                self.id_collection(&dataset.spaced_repetition_systems, request, &query)
+            }
+            ("GET", Some("spaced_repetition_systems"), Some(id), None) => {
+                resource(&dataset.spaced_repetition_systems, id)
+            }
+            ("GET", Some("study_materials"), None, None) => {
+                self.study_materials(dataset, request, &query)
+            }
+            ("GET", Some("study_materials"), Some(id), None) => {
+                resource(&dataset.study_materials, id)
+            }
+            ("POST", Some("study_materials"), None, None) => {
+                create_study_material(dataset, request)
+            }
+            ("PUT", Some("study_materials"), Some(id), None) => {
+                update_study_material(dataset, request, id)
+            }
+            ("GET", Some("subjects"), None, None) => self.subjects(dataset, request, &query),
+            ("GET", Some("subjects"), Some(id), None) => resource(&dataset.subjects, id),
+            ("GET", Some("summary"), None, None) => Ok(dataset
+                .summary
+                .as_ref()
+                .map_or_else(Reply::not_found, Reply::ok)),
+            ("GET", Some("user"), None, None) => Ok(dataset
+                .user
+                .as_ref()
+                .map_or_else(Reply::not_found, Reply::ok)),
+            ("PUT", Some("user"), None, None) => update_user(dataset, request),
+            ("GET", Some("voice_actors"), None, None) => {
+                self.id_collection(&dataset.voice_actors, request, &query)
+            }
+            ("GET", Some("voice_actors"), Some(id), None) => resource(&dataset.voice_actors, id),
+            _ => Ok(Reply::not_found()),
+        };
+
+        result.unwrap_or_else(Reply::unprocessable)
+    }
+
+    fn collection<T>(
+        &self,
+        request: &Request,
+        query: &Query,
+        items: Vec<&Resource<T>>,
+        per_page: u32,
+    ) -> Result<Reply, String>
+    where
+        T: Serialize + Clone,
+    {
+        let mut items = items;
+        items.sort_by_key(|resource| resource.id);
+
+        let per_page = self.config.page_size.unwrap_or(per_page).max(1);
+        let page_size = per_page as usize;
+        let data_updated_at = items
+            .iter()
+            .filter_map(|resource| resource.common.data_updated_at)
+            .max();
+
+        let page: Vec<&Resource<T>> = match query.value::<Id>("page_before_id")? {
+            Some(before) => {
+                let candidates: Vec<_> = items.iter().filter(|r| r.id < before).collect();
+                candidates[candidates.len().saturating_sub(page_size)..]
+                    .iter()
+                    .map(|r| **r)
+                    .collect()
+            }
+            None => {
+                let after = query.value::<Id>("page_after_id")?;
+                items
+                    .iter()
+                    .filter(|r| after.map_or(true, |after| r.id > after))
+                    .take(page_size)
+                    .copied()
+                    .collect()
+            }
+        };
+
+        let next_url = page
+            .last()
+            .filter(|last| items.iter().any(|r| r.id > last.id))
+            .map(|last| page_url(&request.url, "page_after_id", last.id));
+        let previous_url = page
+            .first()
+            .filter(|first| items.iter().any(|r| r.id < first.id))
+            .map(|first| page_url(&request.url, "page_before_id", first.id));
+
+        let collection = Collection {
+            common: ResourceCommon {
+                object: ResourceType::Collection,
+                url: request.url.clone(),
+                data_updated_at,
+            },
+            pages: Pages {
+                next_url,
+                previous_url,
+                per_page,
+            },
+            total_count: items.len() as u64,
+            data: page.into_iter().cloned().collect(),
+        };
+
+        Ok(Reply::ok(&collection))
+    }
+
+    fn id_collection<T>(
+        &self,
+        items: &[Resource<T>],
+        request: &Request,
+        query: &Query,
+    ) -> Result<Reply, String>
+    where
+        T: Serialize + Clone,
+    {
+        let ids = query.list::<Id>("ids")?;
+        let updated_after = query.timestamp("updated_after")?;
+
+        let items = items
+            .iter()
+            .filter(|r| ids.as_ref().map_or(true, |ids| ids.contains(&r.id)))
+            .filter(|r| updated_since(&r.common, updated_after))
+            .collect();
+
+        self.collection(request, query, items, 500)
+    }
+
+    fn assignments(
+        &self,
+        dataset: &MockDataset,
+        request: &Request,
+        query: &Query,
+    ) -> Result<Reply, String> {
+        let now = Utc::now();
+        let available_after = query.timestamp("available_after")?;
+        let available_before = query.timestamp("available_before")?;
+        let burned = query.value::<bool>("burned")?;
+        let hidden = query.value::<bool>("hidden")?;
+        let ids = query.list::<Id>("ids")?;
+        let for_lessons = query.has("immediately_available_for_lessons");
+        let for_review = query.has("immediately_available_for_review");
+        let in_review = query.has("in_review");
+        let levels = query.list::<u32>("levels")?;
+        let srs_stages = query.list::<u32>("srs_stages")?;
+        let started = query.value::<bool>("started")?;
+        let subject_ids = query.list::<Id>("subject_ids")?;
+        let subject_types = query.subject_types("subject_types")?;
+        let unlocked = query.value::<bool>("unlocked")?;
+        let updated_after = query.timestamp("updated_after")?;
+
+        let subject_level = |id: Id| {
+            dataset
+                .subjects
+                .iter()
+                .find(|subject| subject.id == id)
+                .map(|subject| subject.data.common().level)
+        };
+
+        let items = dataset
+            .assignments
+            .iter()
+            .filter(|r| {
+                let a = &r.data;
+                available_after.map_or(true, |t| a.available_at.map_or(false, |at| at >= t))
+                    && available_before.map_or(true, |t| a.available_at.map_or(false, |at| at <= t))
+                    && burned.map_or(true, |b| a.burned_at.is_some() == b)
+                    && hidden.map_or(true, |h| a.hidden == h)
+                    && ids.as_ref().map_or(true, |ids| ids.contains(&r.id))
+                    && (!for_lessons || (a.unlocked_at.is_some() && a.started_at.is_none()))
+                    && (!for_review || a.available_at.map_or(false, |at| at <= now))
+                    && (!in_review || (a.available_at.is_some() && a.burned_at.is_none()))
+                    && levels.as_ref().map_or(true, |levels| {
+                        subject_level(a.subject_id).map_or(false, |l| levels.contains(&l))
+                    })
+                    && srs_stages
+                        .as_ref()
+                        .map_or(true, |stages| stages.contains(&a.srs_stage.position()))
+                    && started.map_or(true, |s| a.started_at.is_some() == s)
+                    && subject_ids
+                        .as_ref()
+                        .map_or(true, |ids| ids.contains(&a.subject_id))
+                    && subject_types
+                        .as_ref()
+                        .map_or(true, |types| types.contains(&a.subject_type))
+                    && unlocked.map_or(true, |u| a.unlocked_at.is_some() == u)
+                    && updated_since(&r.common, updated_after)
+            })
+            .collect();
+
+        self.collection(request, query, items, 500)
+    }
+
+    fn reviews(
+        &self,
+        dataset: &MockDataset,
+        request: &Request,
+        query: &Query,
+    ) -> Result<Reply, String> {
+        let assignment_ids = query.list::<Id>("assignment_ids")?;
+        let ids = query.list::<Id>("ids")?;
+        let subject_ids = query.list::<Id>("subject_ids")?;
+        let updated_after = query.timestamp("updated_after")?;
+
+        let items = dataset
+            .reviews
+            .iter()
+            .filter(|r| {
+                assignment_ids
+                    .as_ref()
+                    .map_or(true, |ids| ids.contains(&r.data.assignment_id))
+                    && ids.as_ref().map_or(true, |ids| ids.contains(&r.id))
+                    && subject_ids
+                        .as_ref()
+                        .map_or(true, |ids| ids.contains(&r.data.subject_id))
+                    && updated_since(&r.common, updated_after)
+            })
+            .collect();
+
+        self.collection(request, query, items, 1000)
+    }
+
+    fn review_statistics(
+        &self,
+        dataset: &MockDataset,
+        request: &Request,
+        query: &Query,
+    ) -> Result<Reply, String> {
+        let hidden = query.value::<bool>("hidden")?;
+        let ids = query.list::<Id>("ids")?;
+        let greater_than = query.value::<u32>("percentages_greater_than")?;
+        let less_than = query.value::<u32>("percentages_less_than")?;
+        let subject_ids = query.list::<Id>("subject_ids")?;
+        let subject_types = query.subject_types("subject_types")?;
+        let updated_after = query.timestamp("updated_after")?;
+
+        let items = dataset
+            .review_statistics
+            .iter()
+            .filter(|r| {
+                let s = &r.data;
+                hidden.map_or(true, |h| s.hidden == h)
+                    && ids.as_ref().map_or(true, |ids| ids.contains(&r.id))
+                    && greater_than.map_or(true, |p| s.percentage_correct > p)
+                    && less_than.map_or(true, |p| s.percentage_correct < p)
+                    && subject_ids
+                        .as_ref()
+                        .map_or(true, |ids| ids.contains(&s.subject_id))
+                    && subject_types
+                        .as_ref()
+                        .map_or(true, |types| types.contains(&s.subject_type))
+                    && updated_since(&r.common, updated_after)
+            })
+            .collect();
+
+        self.collection(request, query, items, 500)
+    }
+
+    fn study_materials(
+        &self,
+        dataset: &MockDataset,
+        request: &Request,
+        query: &Query,
+    ) -> Result<Reply, String> {
+        let hidden = query.value::<bool>("hidden")?;
+        let ids = query.list::<Id>("ids")?;
+        let subject_ids = query.list::<Id>("subject_ids")?;
+        let subject_types = query.subject_types("subject_types")?;
+        let updated_after = query.timestamp("updated_after")?;
+
+        let items = dataset
+            .study_materials
+            .iter()
+            .filter(|r| {
+                let m = &r.data;
+                hidden.map_or(true, |h| m.hidden == h)
+                    && ids.as_ref().map_or(true, |ids| ids.contains(&r.id))
+                    && subject_ids
+                        .as_ref()
+                        .map_or(true, |ids| ids.contains(&m.subject_id))
+                    && subject_types
+                        .as_ref()
+                        .map_or(true, |types| types.contains(&m.subject_type))
+                    && updated_since(&r.common, updated_after)
+            })
+            .collect();
+
+        self.collection(request, query, items, 500)
+    }
+
+    fn subjects(
+        &self,
+        dataset: &MockDataset,
+        request: &Request,
+        query: &Query,
+    ) -> Result<Reply, String> {
+        let ids = query.list::<Id>("ids")?;
+        let types = query.subject_types("types")?;
+        let slugs = query.list::<String>("slugs")?;
+        let levels = query.list::<u32>("levels")?;
+        let hidden = query.value::<bool>("hidden")?;
+        let updated_after = query.timestamp("updated_after")?;
+
+        let items = dataset
+            .subjects
+            .iter()
+            .filter(|r| {
+                let common = r.data.common();
+                ids.as_ref().map_or(true, |ids| ids.contains(&r.id))
+                    && types
+                        .as_ref()
+                        .map_or(true, |types| types.contains(&r.data.subject_type()))
+                    && slugs
+                        .as_ref()
+                        .map_or(true, |slugs| slugs.contains(&common.slug))
+                    && levels
+                        .as_ref()
+                        .map_or(true, |levels| levels.contains(&common.level))
+                    && hidden.map_or(true, |h| common.hidden_at.is_some() == h)
+                    && updated_since(&r.common, updated_after)
+            })
+            .collect();
+
+        self.collection(request, query, items, 1000)
+    }
+}
+
+fn resource<T: Serialize>(items: &[Resource<T>], id: Id) -> Result<Reply, String> {
+    Ok(items
+        .iter()
+        .find(|resource| resource.id == id)
+        .map_or_else(Reply::not_found, Reply::ok))
+}
+
+fn updated_since(common: &ResourceCommon, updated_after: Option<Timestamp>) -> bool {
+    updated_after.map_or(true, |after| {
+        common
+            .data_updated_at
+            .map_or(false, |updated| updated > after)
+    })
+}
+
+fn page_url(url: &Url, key: &str, id: Id) -> Url {
+    let mut url = url.clone();
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(k, _)| k != "page_after_id" && k != "page_before_id")
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    url.query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair(key, &id.to_string());
+    url
+}
+
+fn resource_url(request: &Request, path: &str, id: Id) -> Url {
+    let mut url = request.url.clone();
+    url.set_query(None);
+    url.path_segments_mut()
+        .expect("Valid URL")
+        .clear()
+        .push(path)
+        .push(&id.to_string());
+    url
+}
+
+fn next_id<T>(items: &[Resource<T>]) -> Id {
+    items.iter().map(|resource| resource.id).max().unwrap_or(0) + 1
+}
+
+fn body<T>(request: &Request) -> Result<T, String>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    serde_json::from_slice(&request.body).map_err(|e| format!("Invalid request body: {e}"))
+}
+
+fn start_assignment(dataset: &mut MockDataset, request: &Request, id: Id) -> Result<Reply, String> {
+    let start: AssignmentStart = if request.body.is_empty() {
+        AssignmentStart { started_at: None }
+    } else {
+        body(request)?
+    };
+    let now = Utc::now();
+
+    let Some(subjects_srs) = dataset
+        .assignments
+        .iter()
+        .find(|a| a.id == id)
+        .map(|a| srs_for_subject(dataset, a.data.subject_id))
+    else {
+        return Ok(Reply::not_found());
+    };
+    let assignment = dataset
+        .assignments
+        .iter_mut()
+        .find(|a| a.id == id)
+        .expect("Assignment exists");
+
+    let data = &mut assignment.data;
+    if data.srs_stage != SrsStage::Initiate || data.started_at.is_some() {
+        return Err("Assignment has already been started".into());
+    }
+    let Some(unlocked_at) = data.unlocked_at else {
+        return Err("Assignment is not unlocked".into());
+    };
+    let started_at = start.started_at.unwrap_or(now);
+    if started_at < unlocked_at {
+        return Err("started_at must be after unlocked_at".into());
+    }
+
+    data.started_at = Some(started_at);
+    data.srs_stage = subjects_srs
+        .starting
+        .try_into()
+        .map_err(|e: InvalidSrsStage| e.to_string())?;
+    data.available_at = subjects_srs
+        .interval(subjects_srs.starting)
+        .and_then(|interval| started_at.checked_add_signed(interval))
+        .map(top_of_hour);
+    assignment.common.data_updated_at = Some(now);
+
+    Ok(Reply::ok(&*assignment))
+}
+
+fn create_study_material(dataset: &mut MockDataset, request: &Request) -> Result<Reply, String> {
+    let create: CreateStudyMaterial = body(request)?;
+    let now = Utc::now();
+
+    let Some(subject) = dataset.subjects.iter().find(|s| s.id == create.subject_id) else {
+        return Err("Subject does not exist".into());
+    };
+    if dataset
+        .study_materials
+        .iter()
+        .any(|m| m.data.subject_id == create.subject_id)
+    {
+        return Err("Subject has already been taken".into());
+    }
+
+    let id = next_id(&dataset.study_materials);
+    let material = Resource {
+        id,
+        common: ResourceCommon {
+            object: ResourceType::StudyMaterial,
+            url: resource_url(request, "study_materials", id),
+            data_updated_at: Some(now),
+        },
+        data: StudyMaterial {
+            created_at: now,
+            hidden: subject.data.common().hidden_at.is_some(),
+            meaning_note: create.meaning_note,
+            meaning_synonyms: create.meaning_synonyms.unwrap_or_default(),
+            reading_note: create.reading_note,
+            subject_id: create.subject_id,
+            subject_type: subject.data.subject_type(),
+        },
+    };
+
+    let reply = Reply::created(&material);
+    dataset.study_materials.push(material);
+    Ok(reply)
+}
+
+fn update_study_material(
+    dataset: &mut MockDataset,
+    request: &Request,
+    id: Id,
+) -> Result<Reply, String> {
+    let update: UpdateStudyMaterial = body(request)?;
+
+    let Some(material) = dataset.study_materials.iter_mut().find(|m| m.id == id) else {
+        return Ok(Reply::not_found());
+    };
+
+    if let Some(note) = update.meaning_note {
+        material.data.meaning_note = Some(note);
+    }
+    if let Some(note) = update.reading_note {
+        material.data.reading_note = Some(note);
+    }
+    if let Some(synonyms) = update.meaning_synonyms {
+        material.data.meaning_synonyms = synonyms;
+    }
+    material.common.data_updated_at = Some(Utc::now());
+
+    Ok(Reply::ok(&*material))
+}
+
+fn update_user(dataset: &mut MockDataset, request: &Request) -> Result<Reply, String> {
+    let update: UpdateUser = body(request)?;
+
+    let Some(ref mut user) = dataset.user else {
+        return Ok(Reply::not_found());
+    };
+
+    let prefs = &mut user.data.preferences;
+    let update = update.preferences;
+    if let Some(value) = update.default_voice_actor_id {
+        prefs.default_voice_actor_id = value;
+    }
+    if let Some(value) = update.extra_study_autoplay_audio {
+        prefs.extra_study_autoplay_audio = value;
+    }
+    if let Some(value) = update.lessons_autoplay_audio {
+        prefs.lessons_autoplay_audio = value;
+    }
+    if let Some(value) = update.lessons_batch_size {
+        prefs.lessons_batch_size = value;
+    }
+    if let Some(value) = update.lessons_presentation_order {
+        prefs.lessons_presentation_order = value;
+    }
+    if let Some(value) = update.reviews_autoplay_audio {
+        prefs.reviews_autoplay_audio = value;
+    }
+    if let Some(value) = update.reviews_display_srs_indicator {
+        prefs.reviews_display_srs_indicator = value;
+    }
+    user.common.data_updated_at = Some(Utc::now());
+
+    Ok(Reply::ok(&*user))
+}
+
+fn create_review(dataset: &mut MockDataset, request: &Request) -> Result<Reply, String> {
+    let create: CreateReview = body(request)?;
+    let now = Utc::now();
+    let created_at = create.created_at.unwrap_or(now);
+
+    let position = dataset
+        .assignments
+        .iter()
+        .position(|a| match create.target {
+            ReviewTarget::Assignment(id) => a.id == id,
+            ReviewTarget::Subject(id) => a.data.subject_id == id,
+        });
+    let Some(position) = position else {
+        return Err("Assignment does not exist".into());
+    };
+
+    let subject_id = dataset.assignments[position].data.subject_id;
+    let srs = srs_for_subject(dataset, subject_id);
+
+    let assignment = &mut dataset.assignments[position];
+    let data = &mut assignment.data;
+    if data.started_at.is_none() || data.burned_at.is_some() || data.srs_stage == SrsStage::Initiate
+    {
+        return Err("Assignment is not in review".into());
+    }
+    if data.available_at.map_or(true, |at| at > created_at) {
+        return Err("Assignment is not available for review".into());
+    }
+
+    let starting_srs_stage = data.srs_stage.position();
+    let ending_srs_stage = srs.next_stage(
+        starting_srs_stage,
+        create.incorrect_meaning_answers + create.incorrect_reading_answers,
+    );
+
+    data.srs_stage = ending_srs_stage
+        .try_into()
+        .map_err(|e: InvalidSrsStage| e.to_string())?;
+    data.available_at = srs
+        .interval(ending_srs_stage)
+        .and_then(|interval| created_at.checked_add_signed(interval))
+        .map(top_of_hour);
+    if ending_srs_stage >= srs.passing && data.passed_at.is_none() {
+        data.passed_at = Some(created_at);
+    }
+    if ending_srs_stage >= srs.burning {
+        data.burned_at = Some(created_at);
+    }
+    assignment.common.data_updated_at = Some(now);
+    let assignment = assignment.clone();
+
+    let review_statistic = update_review_statistic(dataset, request, &assignment.data, &create);
+
+    let id = next_id(&dataset.reviews);
+    let review = Resource {
+        id,
+        common: ResourceCommon {
+            object: ResourceType::Review,
+            url: resource_url(request, "reviews", id),
+            data_updated_at: Some(now),
+        },
+        data: Review {
+            assignment_id: assignment.id,
+            created_at,
+            ending_srs_stage,
+            incorrect_meaning_answers: create.incorrect_meaning_answers,
+            incorrect_reading_answers: create.incorrect_reading_answers,
+            spaced_repetition_system_id: srs.id,
+            starting_srs_stage,
+            subject_id,
+        },
+    };
+    dataset.reviews.push(review.clone());
+
+    Ok(Reply::created(&CreatedReview {
+        review,
+        resources_updated: ReviewResourcesUpdated {
+            assignment,
+            review_statistic,
+        },
+    }))
+}
+
+fn update_review_statistic(
+    dataset: &mut MockDataset,
+    request: &Request,
+    assignment: &Assignment,
+    create: &CreateReview,
+) -> Resource<ReviewStatistic> {
+    let now = Utc::now();
+
+    let position = dataset
+        .review_statistics
+        .iter()
+        .position(|s| s.data.subject_id == assignment.subject_id);
+    let position = match position {
+        Some(position) => position,
+        None => {
+            let id = next_id(&dataset.review_statistics);
+            dataset.review_statistics.push(Resource {
+                id,
+                common: ResourceCommon {
+                    object: ResourceType::ReviewStatistic,
+                    url: resource_url(request, "review_statistics", id),
+                    data_updated_at: Some(now),
+                },
+                data: ReviewStatistic {
+                    created_at: now,
+                    hidden: assignment.hidden,
+                    meaning_correct: 0,
+                    meaning_current_streak: 0,
+                    meaning_incorrect: 0,
+                    meaning_max_streak: 0,
+                    percentage_correct: 100,
+                    reading_correct: 0,
+                    reading_current_streak: 0,
+                    reading_incorrect: 0,
+                    reading_max_streak: 0,
+                    subject_id: assignment.subject_id,
+                    subject_type: assignment.subject_type,
+                },
+            });
+            dataset.review_statistics.len() - 1
+        }
+    };
+
+    let stat = &mut dataset.review_statistics[position];
+    let s = &mut stat.data;
+
+    s.meaning_correct += 1;
+    s.meaning_incorrect += create.incorrect_meaning_answers;
+    s.meaning_current_streak = streak(s.meaning_current_streak, create.incorrect_meaning_answers);
+    s.meaning_max_streak = s.meaning_max_streak.max(s.meaning_current_streak);
+
+    if assignment.subject_type != SubjectType::Radical {
+        s.reading_correct += 1;
+        s.reading_incorrect += create.incorrect_reading_answers;
+        s.reading_current_streak =
+            streak(s.reading_current_streak, create.incorrect_reading_answers);
+        s.reading_max_streak = s.reading_max_streak.max(s.reading_current_streak);
+    }
+
+    let correct = s.meaning_correct + s.reading_correct;
+    let total = correct + s.meaning_incorrect + s.reading_incorrect;
+    s.percentage_correct = (correct * 100 + total / 2)
+        .checked_div(total)
+        .unwrap_or(100);
+    stat.common.data_updated_at = Some(now);
+
+    stat.clone()
+}
+
+fn streak(current: u32, incorrect: u32) -> u32 {
+    if incorrect == 0 {
+        current + 1
+    } else {
+        1
+    }
+}
+
+fn top_of_hour(time: Timestamp) -> Timestamp {
+    time.duration_trunc(Duration::hours(1))
+        .expect("Valid timestamp")
+}
+
+/// The parts of a spaced repetition system needed to simulate reviews.
+struct SrsPositions {
+    id: Id,
+    starting: u32,
+    passing: u32,
+    burning: u32,
+    intervals: Vec<(u32, Duration)>,
+}
+
+impl SrsPositions {
+    fn interval(&self, position: u32) -> Option<Duration> {
+        self.intervals
+            .iter()
+            .find(|(p, _)| *p == position)
+            .map(|(_, interval)| *interval)
+    }
+
+    fn next_stage(&self, current: u32, incorrect: u32) -> u32 {
+        if incorrect == 0 {
+            return (current + 1).min(self.burning);
+        }
+
+        let adjustment = (incorrect + 1) / 2;
+        let penalty = if current >= self.passing { 2 } else { 1 };
+
+        current
+            .saturating_sub(adjustment * penalty)
+            .max(self.starting)
+    }
+}
+
+fn srs_for_subject(dataset: &MockDataset, subject_id: Id) -> SrsPositions {
+    let srs_id = dataset
+        .subjects
+        .iter()
+        .find(|s| s.id == subject_id)
+        .map_or(1, |s| s.data.common().spaced_repetition_system_id);
+
+    match dataset
+        .spaced_repetition_systems
+        .iter()
+        .find(|srs| srs.id == srs_id)
+    {
+        Some(srs) => SrsPositions {
+            id: srs.id,
+            starting: srs.data.starting_stage_position,
+            passing: srs.data.passing_stage_position,
+            burning: srs.data.burning_stage_position,
+            intervals: srs
+                .data
+                .stages
+                .iter()
+                .filter_map(|stage| Some((stage.position, stage.interval_duration()?)))
+                .collect(),
+        },
+        None => SrsPositions {
+            id: srs_id,
+            starting: 1,
+            passing: 5,
+            burning: 9,
+            intervals: [4, 8, 23, 47, 167, 335, 719, 2879]
+                .into_iter()
+                .zip(1..)
+                .map(|(hours, position)| (position, Duration::hours(hours)))
+                .collect(),
+        },
+    }
+}
+
+struct Query(Vec<(String, String)>);
+
+impl Query {
+    fn new(url: &Url) -> Self {
+        Self(
+            url.query_pairs()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        )
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn has(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    fn value<T: FromStr>(&self, key: &str) -> Result<Option<T>, String> {
+        self.get(key)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| format!("Invalid value for {key}: {value}"))
+            })
+            .transpose()
+    }
+
+    fn list<T: FromStr>(&self, key: &str) -> Result<Option<Vec<T>>, String> {
+        self.get(key)
+            .map(|values| {
+                values
+                    .split(',')
+                    .filter(|value| !value.is_empty())
+                    .map(|value| {
+                        value
+                            .parse()
+                            .map_err(|_| format!("Invalid value for {key}: {value}"))
+                    })
+                    .collect()
+            })
+            .transpose()
+    }
+
+    fn timestamp(&self, key: &str) -> Result<Option<Timestamp>, String> {
+        self.get(key)
+            .map(|value| {
+                chrono::DateTime::parse_from_rfc3339(value)
+                    .map(Into::into)
+                    .map_err(|_| format!("Invalid timestamp for {key}: {value}"))
+            })
+            .transpose()
+    }
+
+    fn subject_types(&self, key: &str) -> Result<Option<Vec<SubjectType>>, String> {
+        Ok(self.list::<String>(key)?.map(|types| {
+            types
+                .into_iter()
+                .filter_map(|t| serde_json::from_value(serde_json::Value::String(t)).ok())
+                .collect()
+        }))
+    }
+}
@@ -57,7 +57,7 @@ mod tests {
         let data = collection.data;
 
         assert_eq!(data.len(), 2);
-        let kyoko = data.get(0).expect("Exists");
+        let kyoko = data.first().expect("Exists");
         let kenichi = data.get(1).expect("Exists");
 
         let kyoko_expected = Resource::<VoiceActor> {